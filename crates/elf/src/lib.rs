@@ -25,16 +25,13 @@ mod util {
 			<$type>::from_le_bytes(consume!(@arr $bytes => SIZE))
 		}};
 		// Consumes `size_of<$type>` bytes and converts them to
-		// the requested endianness (@see `EI_DATA_LE` and
-		// `EI_DATA_BE` for possible values).
+		// the requested endianness (@see `Endianness`).
 		( $bytes:expr , $endianness:expr => $type:ty ) => {{
 			const SIZE: usize = core::mem::size_of::<$type>();
 
-			// TODO: return err
 			match $endianness {
-				crate::header::consts::ident::data::EI_DATA_BE => Ok(<$type>::from_be_bytes(consume!(@arr $bytes => SIZE))),
-				crate::header::consts::ident::data::EI_DATA_LE => Ok(<$type>::from_le_bytes(consume!(@arr $bytes => SIZE))),
-				_ => Err(crate::error::Error::new(crate::error::ErrorKind::UnknownEndianess))
+				crate::header::consts::ident::data::Endianness::Big => <$type>::from_be_bytes(consume!(@arr $bytes => SIZE)),
+				crate::header::consts::ident::data::Endianness::Little => <$type>::from_le_bytes(consume!(@arr $bytes => SIZE)),
 			}
 		}};
 		// Consumes `$len` bytes and returns them as array.
@@ -60,10 +57,107 @@ mod util {
 	/// Exports macro for use in other modules of the crate.
 	pub(crate) use consume;
 
+	/// Writes `$value` to the start of the `bytes` slice with the requested
+	/// endianness (@see `Endianness`).
+	/// The `bytes` slice will be advanced by the amount of bytes written.
+	///
+	/// The caller has to make sure that `bytes` is large enough.
+	macro_rules! produce {
+		( $bytes:expr , $endianness:expr => $value:expr ) => {{
+			match $endianness {
+				crate::header::consts::ident::data::Endianness::Big => crate::util::produce!(@arr $bytes => $value.to_be_bytes()),
+				crate::header::consts::ident::data::Endianness::Little => crate::util::produce!(@arr $bytes => $value.to_le_bytes()),
+			}
+		}};
+		// Writes the byte array `$arr` as is.
+		( $bytes:expr => $arr:expr ) => {{
+			crate::util::produce!(@arr $bytes => $arr)
+		}};
+		// PRIVATE: Shared code to write a byte array.
+		( @arr $bytes:expr => $arr:expr ) => {{
+			let buf = $arr;
+			let (left, right) = core::mem::take(&mut $bytes).split_at_mut(buf.len());
+			left.copy_from_slice(&buf);
+			$bytes = right;
+		}};
+	}
+
+	/// Exports macro for use in other modules of the crate.
+	pub(crate) use produce;
+
 	/// Defines a list on constants with some added doc comments and a
 	/// convienient function which converts a value of the shared `field/type`
 	/// to a string representation.
+	///
+	/// If a `$lookup` function is named (`$as_str / $lookup`), it is defined
+	/// as an alternative to `$as_str` which binary searches the constants
+	/// instead of matching them one by one (the constants must be declared
+	/// in ascending order, which is checked at compile time).
 	macro_rules! def_consts {
+		(
+			$field:ident : $size:ty : $as_str:ident / $lookup:ident => {
+				$(
+					$(
+						#[doc = $doc:literal]
+					)+
+					$name:ident : $repr:literal = $value:literal ,
+				)+
+			}
+			$(
+				, {
+					$(
+						$extra_match_pattern:pat => $extra_match_value:literal ,
+					)+
+				}
+			)?
+		) => {
+			crate::util::def_consts! {
+				$field : $size : $as_str => {
+					$(
+						$(
+							#[doc = $doc]
+						)+
+						$name : $repr = $value ,
+					)+
+				}
+				$(
+					, {
+						$(
+							$extra_match_pattern => $extra_match_value ,
+						)+
+					}
+				)?
+			}
+
+			#[doc = concat!("Same as [`", stringify!($as_str), "`], but looks up the value with a binary search.")]
+			pub fn $lookup(value: $size) -> &'static str {
+				const TABLE: &[($size, &str)] = &[
+					$(
+						($name, $repr),
+					)+
+				];
+
+				const _: () = {
+					let mut idx = 1;
+					while idx < TABLE.len() {
+						assert!(TABLE[idx - 1].0 < TABLE[idx].0, "Constants must be declared in ascending order");
+						idx += 1;
+					}
+				};
+
+				match TABLE.binary_search_by_key(&value, |&(value, _)| value) {
+					Ok(idx) => TABLE[idx].1,
+					Err(_) => match value {
+						$(
+							$(
+								$extra_match_pattern => $extra_match_value ,
+							)+
+						)?
+						_ => "UNKNOWN",
+					},
+				}
+			}
+		};
 		(
 			$field:ident : $size:ty : $as_str:ident => {
 				$(
@@ -122,6 +216,13 @@ pub mod error {
 		InvalidMagic,
 		InvalidClass,
 		UnknownEndianess,
+		UnknownVersion,
+		UnknownOsAbi,
+		MalformedHeader,
+		InvalidHeaderSize,
+		InvalidTableSize,
+		InvalidSegmentSize,
+		MisalignedSegment,
 	}
 
 	impl fmt::Display for ErrorKind {
@@ -140,6 +241,33 @@ pub mod error {
 				Self::UnknownEndianess => f.write_str(
 					"Found unknown endianness in field `e_ident[EI_DATA]`",
 				),
+				Self::UnknownVersion => f.write_str(
+					"Found unknown version in field `e_ident[EI_VERSION]`",
+				),
+				Self::UnknownOsAbi => f.write_str(
+					"Found unknown os abi in field `e_ident[EI_OSABI]`",
+				),
+				Self::MalformedHeader => f.write_str(
+					"Found inconsistent values in the elf header (e.g. a \
+					 table which exceeds the addressable range)",
+				),
+				Self::InvalidHeaderSize => f.write_str(
+					"Found header size in field `e_ehsize` which does not \
+					 match the size of the header",
+				),
+				Self::InvalidTableSize => f.write_str(
+					"Found table whose size is not a multiple of the size of \
+					 its entries",
+				),
+				Self::InvalidSegmentSize => f.write_str(
+					"Found loadable segment whose size in the file \
+					 (`p_filesz`) exceeds its size in memory (`p_memsz`)",
+				),
+				Self::MisalignedSegment => f.write_str(
+					"Found loadable segment whose address (`p_vaddr`) and \
+					 offset (`p_offset`) are not congruent modulo its \
+					 alignment (`p_align`)",
+				),
 			}
 		}
 	}
@@ -173,8 +301,14 @@ pub mod error {
 
 	impl fmt::Display for Error {
 		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-			// TODO: print message
-			fmt::Display::fmt(&self.kind, f)
+			fmt::Display::fmt(&self.kind, f)?;
+
+			#[cfg(feature = "std")]
+			if let Some(message) = &self.message {
+				write!(f, ": {}", message)?;
+			}
+
+			Ok(())
 		}
 	}
 
@@ -231,6 +365,47 @@ pub mod header {
 						EI_CLASS_64 : "64-bit" = 2,
 					}
 				}
+
+				/// Typed value of `e_ident[EI_CLASS]`.
+				#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+				pub enum Class {
+					/// `EI_CLASS_32`
+					Elf32,
+					/// `EI_CLASS_64`
+					Elf64,
+				}
+
+				impl Class {
+					/// Returns the class for the value of `e_ident[EI_CLASS]`
+					/// or `None` if it is unknown.
+					pub const fn from_ei_class(ei_class: u8) -> Option<Self> {
+						match ei_class {
+							EI_CLASS_32 => Some(Self::Elf32),
+							EI_CLASS_64 => Some(Self::Elf64),
+							_ => None,
+						}
+					}
+
+					/// Returns the value of `e_ident[EI_CLASS]`.
+					pub const fn ei_class(self) -> u8 {
+						match self {
+							Self::Elf32 => EI_CLASS_32,
+							Self::Elf64 => EI_CLASS_64,
+						}
+					}
+				}
+
+				impl TryFrom<u8> for Class {
+					type Error = crate::error::Error;
+
+					fn try_from(ei_class: u8) -> Result<Self, Self::Error> {
+						Self::from_ei_class(ei_class).ok_or(
+							crate::error::Error::new(
+								crate::error::ErrorKind::InvalidClass,
+							),
+						)
+					}
+				}
 			}
 
 			pub mod data {
@@ -243,6 +418,49 @@ pub mod header {
 						EI_DATA_BE : "BE" = 2,
 					}
 				}
+
+				/// Typed value of `e_ident[EI_DATA]`, used by all readers and
+				/// writers to select the byte order.
+				#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+				pub enum Endianness {
+					/// `EI_DATA_LE`
+					#[default]
+					Little,
+					/// `EI_DATA_BE`
+					Big,
+				}
+
+				impl Endianness {
+					/// Returns the endianness for the value of
+					/// `e_ident[EI_DATA]` or `None` if it is unknown.
+					pub const fn from_ei_data(ei_data: u8) -> Option<Self> {
+						match ei_data {
+							EI_DATA_LE => Some(Self::Little),
+							EI_DATA_BE => Some(Self::Big),
+							_ => None,
+						}
+					}
+
+					/// Returns the value of `e_ident[EI_DATA]`.
+					pub const fn ei_data(self) -> u8 {
+						match self {
+							Self::Little => EI_DATA_LE,
+							Self::Big => EI_DATA_BE,
+						}
+					}
+				}
+
+				impl TryFrom<u8> for Endianness {
+					type Error = crate::error::Error;
+
+					fn try_from(ei_data: u8) -> Result<Self, Self::Error> {
+						Self::from_ei_data(ei_data).ok_or(
+							crate::error::Error::new(
+								crate::error::ErrorKind::UnknownEndianess,
+							),
+						)
+					}
+				}
 			}
 
 			pub mod version {
@@ -346,7 +564,7 @@ pub mod header {
 
 		pub mod machine {
 			crate::util::def_consts! {
-				e_machine : u16 : e_machine_as_str => {
+				e_machine : u16 : e_machine_as_str / e_machine_lookup => {
 					/// Unspecified.
 					E_MACHINE_UNSPECIFIED : "Unspecified" = 0x0000,
 
@@ -504,12 +722,91 @@ pub mod header {
 		}
 	}
 
+	/// Decoding of the processor specific `e_flags` of RISC-V elfs.
+	///
+	/// # References
+	/// - <https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#file-header>
+	pub mod riscv_flags {
+		/// Field `e_flags`: Uses compressed instructions (`C` extension).
+		pub const EF_RISCV_RVC: u32 = 0x0001;
+
+		/// Field `e_flags`: Float ABI (mask).
+		pub const EF_RISCV_FLOAT_ABI: u32 = 0x0006;
+
+		/// Field `e_flags`: Float ABI: Soft-float.
+		pub const EF_RISCV_FLOAT_ABI_SOFT: u32 = 0x0000;
+
+		/// Field `e_flags`: Float ABI: Single precision.
+		pub const EF_RISCV_FLOAT_ABI_SINGLE: u32 = 0x0002;
+
+		/// Field `e_flags`: Float ABI: Double precision.
+		pub const EF_RISCV_FLOAT_ABI_DOUBLE: u32 = 0x0004;
+
+		/// Field `e_flags`: Float ABI: Quad precision.
+		pub const EF_RISCV_FLOAT_ABI_QUAD: u32 = 0x0006;
+
+		/// Field `e_flags`: Uses the reduced register set (`E` base ISA).
+		pub const EF_RISCV_RVE: u32 = 0x0008;
+
+		/// Field `e_flags`: Requires the `RVTSO` memory model.
+		pub const EF_RISCV_TSO: u32 = 0x0010;
+
+		/// Registers used to pass floating-point arguments.
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub enum FloatAbi {
+			Soft,
+			Single,
+			Double,
+			Quad,
+		}
+
+		#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		pub struct RiscvFlags {
+			pub float_abi: FloatAbi,
+			pub rvc: bool,
+			pub rve: bool,
+			pub tso: bool,
+		}
+
+		impl RiscvFlags {
+			pub fn from_e_flags(e_flags: u32) -> Self {
+				let float_abi = match e_flags & EF_RISCV_FLOAT_ABI {
+					EF_RISCV_FLOAT_ABI_SOFT => FloatAbi::Soft,
+					EF_RISCV_FLOAT_ABI_SINGLE => FloatAbi::Single,
+					EF_RISCV_FLOAT_ABI_DOUBLE => FloatAbi::Double,
+					_ => FloatAbi::Quad,
+				};
+
+				Self {
+					float_abi,
+					rvc: e_flags & EF_RISCV_RVC != 0,
+					rve: e_flags & EF_RISCV_RVE != 0,
+					tso: e_flags & EF_RISCV_TSO != 0,
+				}
+			}
+		}
+	}
+
 	use core::fmt;
 
 	#[repr(C)]
 	#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	#[cfg_attr(
+		feature = "serde",
+		derive(serde::Serialize, serde::Deserialize)
+	)]
 	pub struct Ident(pub [u8; 16]);
 
+	/// Decoded fields of an [`Ident`] (see [`Ident::fields`]).
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct IdentFields {
+		pub class: consts::ident::class::Class,
+		pub data: consts::ident::data::Endianness,
+		pub version: u8,
+		pub osabi: u8,
+		pub abiversion: u8,
+	}
+
 	impl Ident {
 		pub fn ei_mag(&self) -> &[u8] {
 			&self.0[..consts::ident::index::EI_CLASS]
@@ -523,6 +820,13 @@ pub mod header {
 			self.0[consts::ident::index::EI_DATA]
 		}
 
+		/// Returns the typed `ei_data`, failing if it is unknown.
+		pub fn endianness(
+			&self,
+		) -> crate::error::Result<consts::ident::data::Endianness> {
+			self.ei_data().try_into()
+		}
+
 		pub fn ei_version(&self) -> u8 {
 			self.0[consts::ident::index::EI_VERSION]
 		}
@@ -538,6 +842,61 @@ pub mod header {
 		pub fn ei_pad(&self) -> &[u8] {
 			&self.0[consts::ident::index::EI_PAD_START..]
 		}
+
+		/// Checks if the padding bytes are all zero as recommended by the
+		/// specification.
+		///
+		/// Non-zero padding is not an error, but can be used by tooling to
+		/// flag non-conforming files.
+		pub fn is_pad_zeroed(&self) -> bool {
+			self.ei_pad().iter().all(|&b| b == 0)
+		}
+
+		/// Decodes the fields, failing if the class or data is unknown.
+		///
+		/// The raw bytes are kept in the [`Ident`] itself (e.g. for
+		/// writing).
+		pub fn fields(&self) -> crate::error::Result<IdentFields> {
+			Ok(IdentFields {
+				class: self.ei_class().try_into()?,
+				data: self.endianness()?,
+				version: self.ei_version(),
+				osabi: self.ei_osabi(),
+				abiversion: self.ei_abiversion(),
+			})
+		}
+
+		/// Checks that class, data, version and os abi hold known values.
+		///
+		/// Parsing only requires a known class and data, this can be used by
+		/// strict tooling to also reject unrecognized versions and os abis.
+		pub fn validate_strict(&self) -> crate::error::Result<()> {
+			use crate::error::{Error, ErrorKind};
+
+			if !matches!(
+				self.ei_class(),
+				consts::ident::class::EI_CLASS_32
+					| consts::ident::class::EI_CLASS_64
+			) {
+				return Err(Error::new(ErrorKind::InvalidClass));
+			}
+
+			self.endianness()?;
+
+			if self.ei_version() as u32
+				!= consts::ident::version::EI_VERSION_CURRENT
+			{
+				return Err(Error::new(ErrorKind::UnknownVersion));
+			}
+
+			if consts::ident::osabi::ei_osabi_as_str(self.ei_osabi())
+				== "UNKNOWN"
+			{
+				return Err(Error::new(ErrorKind::UnknownOsAbi));
+			}
+
+			Ok(())
+		}
 	}
 
 	#[rustfmt::skip]
@@ -562,6 +921,7 @@ pub mod header {
 		( $size:ty ) => {
 			#[repr(C)]
 			#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+			#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 			pub struct Header {
 				/// Field `e_ident`: Identifiers.
 				pub e_ident: crate::header::Ident,
@@ -617,8 +977,14 @@ pub mod header {
 			}
 
 			impl Header {
-                #[allow(unused_assignments, clippy::eval_order_dependence)]
-				pub fn from_bytes(mut bytes: &[u8]) -> crate::error::Result<Self> {
+				pub fn from_bytes(bytes: &[u8]) -> crate::error::Result<Self> {
+					Self::from_bytes_consuming(bytes).map(|(header, _)| header)
+				}
+
+				/// Parses the header and returns it together with the
+				/// remaining bytes following it.
+                #[allow(unused_assignments, clippy::mixed_read_write_in_expression)]
+				pub fn from_bytes_consuming(mut bytes: &[u8]) -> crate::error::Result<(Self, &[u8])> {
 					use crate::util::consume;
 
 					if bytes.len() < core::mem::size_of::<Self>() {
@@ -631,24 +997,83 @@ pub mod header {
                     if e_ident.ei_mag() != &[0x7f, 0x45, 0x4c, 0x46] {
                         return Err(crate::error::Error::new(crate::error::ErrorKind::InvalidMagic))
                     }
-                    let endianness = e_ident.ei_data();
+                    let endianness = e_ident.endianness()?;
 
-                    Ok(Self {
+                    let header = Self {
                         e_ident,
-                        e_type: consume!(bytes, endianness => u16)?,
-                        e_machine: consume!(bytes, endianness => u16)?,
-                        e_version: consume!(bytes, endianness => u32)?,
-                        e_entry: consume!(bytes, endianness => $size)?,
-                        e_phoff: consume!(bytes, endianness => $size)?,
-                        e_shoff: consume!(bytes, endianness => $size)?,
-                        e_flags: consume!(bytes, endianness => u32)?,
-                        e_ehsize: consume!(bytes, endianness => u16)?,
-                        e_phentsize: consume!(bytes, endianness => u16)?,
-                        e_phnum: consume!(bytes, endianness => u16)?,
-                        e_shentsize: consume!(bytes, endianness => u16)?,
-                        e_shnum: consume!(bytes, endianness => u16)?,
-                        e_shstrndx: consume!(bytes, endianness => u16)?,
-                    })
+                        e_type: consume!(bytes, endianness => u16),
+                        e_machine: consume!(bytes, endianness => u16),
+                        e_version: consume!(bytes, endianness => u32),
+                        e_entry: consume!(bytes, endianness => $size),
+                        e_phoff: consume!(bytes, endianness => $size),
+                        e_shoff: consume!(bytes, endianness => $size),
+                        e_flags: consume!(bytes, endianness => u32),
+                        e_ehsize: consume!(bytes, endianness => u16),
+                        e_phentsize: consume!(bytes, endianness => u16),
+                        e_phnum: consume!(bytes, endianness => u16),
+                        e_shentsize: consume!(bytes, endianness => u16),
+                        e_shnum: consume!(bytes, endianness => u16),
+                        e_shstrndx: consume!(bytes, endianness => u16),
+                    };
+
+                    Ok((header, bytes))
+				}
+
+				/// Writes the on-disk representation of the header with the
+				/// given `endianness` to the start of `bytes` and returns the
+				/// remaining bytes following it.
+				///
+				/// `e_ident[EI_DATA]` is set to `endianness`, so the written
+				/// header can be parsed again.
+				pub fn to_bytes<'b>(&self, endianness: crate::header::consts::ident::data::Endianness, mut bytes: &'b mut [u8]) -> crate::error::Result<&'b mut [u8]> {
+					use crate::util::produce;
+
+					if bytes.len() < core::mem::size_of::<Self>() {
+                        return Err(crate::error::Error::new(crate::error::ErrorKind::InsufficantSize))
+					}
+
+                    let mut e_ident = self.e_ident;
+                    e_ident.0[crate::header::consts::ident::index::EI_DATA] = endianness.ei_data();
+
+                    produce!(bytes => e_ident.0);
+                    produce!(bytes, endianness => self.e_type);
+                    produce!(bytes, endianness => self.e_machine);
+                    produce!(bytes, endianness => self.e_version);
+                    produce!(bytes, endianness => self.e_entry);
+                    produce!(bytes, endianness => self.e_phoff);
+                    produce!(bytes, endianness => self.e_shoff);
+                    produce!(bytes, endianness => self.e_flags);
+                    produce!(bytes, endianness => self.e_ehsize);
+                    produce!(bytes, endianness => self.e_phentsize);
+                    produce!(bytes, endianness => self.e_phnum);
+                    produce!(bytes, endianness => self.e_shentsize);
+                    produce!(bytes, endianness => self.e_shnum);
+                    produce!(bytes, endianness => self.e_shstrndx);
+
+                    Ok(bytes)
+				}
+
+				/// Decodes `e_flags` for RISC-V elfs (`e_machine` is
+				/// `E_MACHINE_RISCV`), returns `None` for other machines.
+				pub fn riscv_flags(&self) -> Option<crate::header::riscv_flags::RiscvFlags> {
+					if self.e_machine == crate::header::consts::machine::E_MACHINE_RISCV {
+						Some(crate::header::riscv_flags::RiscvFlags::from_e_flags(self.e_flags))
+					} else {
+						None
+					}
+				}
+
+				/// Checks that the self-reported `e_ehsize` matches the
+				/// actual size of the header ([`EHSIZE`]).
+				///
+				/// This is not checked while parsing, as a mismatch does not
+				/// prevent the header from being read.
+				pub fn validate_ehsize(&self) -> crate::error::Result<()> {
+					if self.e_ehsize == EHSIZE {
+						Ok(())
+					} else {
+						Err(crate::error::Error::new(crate::error::ErrorKind::InvalidHeaderSize))
+					}
 				}
 			}
 
@@ -700,10 +1125,177 @@ pub mod header {
 	}
 
 	pub mod elf32 {
+		/// Size of the on-disk 32-bit elf header in bytes.
+		pub const EHSIZE: u16 = 52;
+
 		header!(u32);
 	}
 
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+
+		#[test]
+		fn ident_pad_zeroed() {
+			let mut ident = Ident([
+				0x7f, 0x45, 0x4c, 0x46, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00,
+				0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			]);
+			assert!(ident.is_pad_zeroed());
+
+			ident.0[consts::ident::index::EI_PAD_START + 3] = 0xaa;
+			assert!(!ident.is_pad_zeroed());
+		}
+
+		#[test]
+		fn ident_fields() {
+			use consts::ident::class::Class;
+			use consts::ident::data::Endianness;
+			use consts::ident::osabi::EI_OSABI_LINUX;
+
+			use crate::error::ErrorKind;
+
+			let mut ident = Ident([
+				0x7f, 0x45, 0x4c, 0x46, 0x01, 0x02, 0x01, 0x03, 0x05, 0x00,
+				0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			]);
+			assert_eq!(
+				ident.fields(),
+				Ok(IdentFields {
+					class: Class::Elf32,
+					data: Endianness::Big,
+					version: 1,
+					osabi: EI_OSABI_LINUX,
+					abiversion: 5,
+				})
+			);
+
+			ident.0[consts::ident::index::EI_CLASS] = 0x03;
+			assert_eq!(
+				ident.fields().unwrap_err().kind,
+				ErrorKind::InvalidClass
+			);
+		}
+
+		#[test]
+		fn ident_validate_strict() {
+			use crate::error::ErrorKind;
+
+			let valid = Ident([
+				0x7f, 0x45, 0x4c, 0x46, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00,
+				0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+			]);
+			assert_eq!(valid.validate_strict(), Ok(()));
+
+			for (index, value, kind) in [
+				(
+					consts::ident::index::EI_CLASS,
+					0x03,
+					ErrorKind::InvalidClass,
+				),
+				(
+					consts::ident::index::EI_DATA,
+					0x00,
+					ErrorKind::UnknownEndianess,
+				),
+				(
+					consts::ident::index::EI_VERSION,
+					0x02,
+					ErrorKind::UnknownVersion,
+				),
+				(
+					consts::ident::index::EI_OSABI,
+					0xaa,
+					ErrorKind::UnknownOsAbi,
+				),
+			] {
+				let mut ident = Ident(valid.0);
+				ident.0[index] = value;
+				assert_eq!(ident.validate_strict().unwrap_err().kind, kind);
+			}
+		}
+
+		#[test]
+		fn endianness() {
+			use consts::ident::data::{Endianness, EI_DATA_BE, EI_DATA_LE};
+			use consts::ident::index::EI_DATA;
+
+			assert_eq!(
+				Endianness::try_from(EI_DATA_LE),
+				Ok(Endianness::Little)
+			);
+			assert_eq!(Endianness::try_from(EI_DATA_BE), Ok(Endianness::Big));
+			assert_eq!(Endianness::Big.ei_data(), EI_DATA_BE);
+			assert_eq!(
+				Endianness::try_from(0xff).err().unwrap().kind,
+				crate::error::ErrorKind::UnknownEndianess
+			);
+
+			let mut bytes = [0u8; elf64::EHSIZE as usize];
+			bytes[..6].copy_from_slice(&[0x7f, 0x45, 0x4c, 0x46, 0x02, 0xff]);
+			assert_eq!(
+				elf64::Header::from_bytes(&bytes).err().unwrap().kind,
+				crate::error::ErrorKind::UnknownEndianess
+			);
+
+			bytes[EI_DATA] = EI_DATA_BE;
+			let header = elf64::Header::from_bytes(&bytes).unwrap();
+			assert_eq!(header.e_ident.endianness(), Ok(Endianness::Big));
+		}
+
+		#[test]
+		fn e_machine_lookup() {
+			use consts::machine::*;
+
+			for value in 0..=u16::MAX {
+				assert_eq!(
+					e_machine_lookup(value),
+					e_machine_as_str(value),
+					"{value:#06x}"
+				);
+			}
+
+			assert_eq!(e_machine_lookup(E_MACHINE_UNSPECIFIED), "Unspecified");
+			assert_eq!(e_machine_lookup(E_MACHINE_RISCV), "RISC-V");
+			assert_eq!(e_machine_lookup(E_MACHINE_WDC65C816), "WDC 65C816");
+			assert_eq!(e_machine_lookup(0x0c), "RESERVED");
+			assert_eq!(e_machine_lookup(0x23), "RESERVED");
+			assert_eq!(e_machine_lookup(0x0102), "UNKNOWN");
+		}
+
+		#[test]
+		fn riscv_flags() {
+			use riscv_flags::{FloatAbi, RiscvFlags};
+
+			let header = elf64::Header::from_bytes(include_bytes!(
+				"../../../resources/riscv-tests/rv64uc-p-rvc"
+			))
+			.unwrap();
+
+			// `RVC, soft-float ABI`
+			assert_eq!(
+				header.riscv_flags(),
+				Some(RiscvFlags {
+					float_abi: FloatAbi::Soft,
+					rvc: true,
+					rve: false,
+					tso: false,
+				})
+			);
+
+			let flags = RiscvFlags::from_e_flags(0x0000_001c);
+			assert_eq!(flags.float_abi, FloatAbi::Double);
+			assert!(!flags.rvc && flags.rve && flags.tso);
+
+			let header = elf64::Header { e_flags: 0x1, ..Default::default() };
+			assert_eq!(header.riscv_flags(), None);
+		}
+	}
+
 	pub mod elf64 {
+		/// Size of the on-disk 64-bit elf header in bytes.
+		pub const EHSIZE: u16 = 64;
+
 		header!(u64);
 
 		#[cfg(test)]
@@ -731,6 +1323,45 @@ pub mod header {
 				let header = Header::from_bytes(&bytes).unwrap();
 				println!("{:#}", header);
 			}
+
+			#[test]
+			fn validate_ehsize() {
+				use super::*;
+
+				let mut header =
+					Header { e_ehsize: EHSIZE, ..Header::default() };
+				assert!(header.validate_ehsize().is_ok());
+
+				header.e_ehsize = crate::header::elf32::EHSIZE;
+				assert_eq!(
+					header.validate_ehsize().unwrap_err().kind,
+					crate::error::ErrorKind::InvalidHeaderSize
+				);
+			}
+
+			#[test]
+			#[cfg(all(feature = "std", feature = "serde"))]
+			fn serde_header_roundtrip() {
+				use super::*;
+
+				let bytes = [
+					0x7f, 0x45, 0x4c, 0x46, 0x02, 0x01, 0x01, 0x00, 0x00,
+					0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x00,
+					0x3e, 0x00, 0x01, 0x00, 0x00, 0x00, 0x80, 0x98, 0x07,
+					0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00,
+					0x00, 0x00, 0x00, 0x00, 0x38, 0xb8, 0x3d, 0x00, 0x00,
+					0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x40, 0x00,
+					0x38, 0x00, 0x0c, 0x00, 0x40, 0x00, 0x2b, 0x00, 0x29,
+					0x00,
+				];
+
+				let header = Header::from_bytes(&bytes).unwrap();
+
+				let json = serde_json::to_string(&header).unwrap();
+				let parsed: Header = serde_json::from_str(&json).unwrap();
+
+				assert_eq!(parsed, header);
+			}
 		}
 	}
 }
@@ -769,6 +1400,90 @@ pub mod program_header {
 				}
 			}
 		}
+
+		pub mod flags {
+			/// Field `p_flags`: Executable.
+			pub const P_FLAGS_PF_X: u32 = 0x1;
+
+			/// Field `p_flags`: Writable.
+			pub const P_FLAGS_PF_W: u32 = 0x2;
+
+			/// Field `p_flags`: Readable.
+			pub const P_FLAGS_PF_R: u32 = 0x4;
+
+			/// Field `p_flags`: Mask for operating system specific flags.
+			pub const P_FLAGS_PF_MASKOS: u32 = 0x0ff0_0000;
+
+			/// Field `p_flags`: Mask for processor specific flags.
+			pub const P_FLAGS_PF_MASKPROC: u32 = 0xf000_0000;
+		}
+	}
+
+	/// Shared implementation of `ProgramHeader::validate` for both classes.
+	fn validate(
+		is_load: bool,
+		offset: u64,
+		vaddr: u64,
+		filesz: u64,
+		memsz: u64,
+		align: u64,
+	) -> crate::error::Result<()> {
+		use crate::error::{Error, ErrorKind};
+
+		// Only loadable segments occupy memory (e.g. `PT_NOTE` in core dumps
+		// has a `p_memsz` of `0`)
+		if is_load && filesz > memsz {
+			return Err(Error::new(ErrorKind::InvalidSegmentSize));
+		}
+
+		// `0` and `1` mean no alignment is required
+		if is_load && align > 1 && vaddr % align != offset % align {
+			return Err(Error::new(ErrorKind::MisalignedSegment));
+		}
+
+		Ok(())
+	}
+
+	/// Virtual address of a segment (`p_vaddr`).
+	///
+	/// This is the address the program expects the segment at during
+	/// execution, i.e. the one the program counter and loads/stores use.
+	#[derive(
+		Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+	)]
+	pub struct VirtAddr(pub u64);
+
+	/// Physical address of a segment (`p_paddr`).
+	///
+	/// Only relevant on systems with physical addressing; most loaders
+	/// (including ones without address translation) ignore it.
+	#[derive(
+		Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+	)]
+	pub struct PhysAddr(pub u64);
+
+	impl From<VirtAddr> for u64 {
+		fn from(addr: VirtAddr) -> Self {
+			addr.0
+		}
+	}
+
+	impl From<PhysAddr> for u64 {
+		fn from(addr: PhysAddr) -> Self {
+			addr.0
+		}
+	}
+
+	impl core::fmt::Display for VirtAddr {
+		fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+			write!(f, "{:#x}", self.0)
+		}
+	}
+
+	impl core::fmt::Display for PhysAddr {
+		fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+			write!(f, "{:#x}", self.0)
+		}
 	}
 
 	/// # Note
@@ -781,9 +1496,17 @@ pub mod program_header {
 		use core::fmt;
 
 		use crate::error::Result;
+		use crate::header::consts::ident::data::Endianness;
+
+		/// Size of an on-disk 32-bit program header in bytes.
+		pub const PHENTSIZE: u16 = 32;
 
 		#[repr(C)]
 		#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		#[cfg_attr(
+			feature = "serde",
+			derive(serde::Serialize, serde::Deserialize)
+		)]
 		pub struct ProgramHeader {
 			/// Field `p_type`: Identifies the type of the segment.
 			pub p_type: u32,
@@ -818,44 +1541,161 @@ pub mod program_header {
 		}
 
 		impl ProgramHeader {
-			#[allow(unused_assignments, clippy::eval_order_dependence)]
 			pub fn from_bytes(
-				endianness: u8,
-				mut bytes: &[u8],
+				endianness: Endianness,
+				bytes: &[u8],
 			) -> Result<Self> {
+				Self::from_bytes_consuming(endianness, bytes)
+					.map(|(header, _)| header)
+			}
+
+			/// Parses the program header and returns it together with the
+			/// remaining bytes following it.
+			#[allow(
+				unused_assignments,
+				clippy::mixed_read_write_in_expression
+			)]
+			pub fn from_bytes_consuming(
+				endianness: Endianness,
+				mut bytes: &[u8],
+			) -> Result<(Self, &[u8])> {
 				use crate::util::consume;
 
-				Ok(Self {
-					p_type: consume!(bytes, endianness => u32)?,
-					p_offset: consume!(bytes, endianness => u32)?,
-					p_vaddr: consume!(bytes, endianness => u32)?,
-					p_paddr: consume!(bytes, endianness => u32)?,
-					p_filesz: consume!(bytes, endianness => u32)?,
-					p_memsz: consume!(bytes, endianness => u32)?,
-					p_flags: consume!(bytes, endianness => u32)?,
-					p_align: consume!(bytes, endianness => u32)?,
-				})
+				if bytes.len() < core::mem::size_of::<Self>() {
+					return Err(crate::error::Error::new(
+						crate::error::ErrorKind::InsufficantSize,
+					));
+				}
+
+				let header = Self {
+					p_type: consume!(bytes, endianness => u32),
+					p_offset: consume!(bytes, endianness => u32),
+					p_vaddr: consume!(bytes, endianness => u32),
+					p_paddr: consume!(bytes, endianness => u32),
+					p_filesz: consume!(bytes, endianness => u32),
+					p_memsz: consume!(bytes, endianness => u32),
+					p_flags: consume!(bytes, endianness => u32),
+					p_align: consume!(bytes, endianness => u32),
+				};
+
+				Ok((header, bytes))
 			}
 
+			/// Writes the on-disk representation of the program header with
+			/// the given `endianness` to the start of `bytes` and returns the
+			/// remaining bytes following it.
+			pub fn to_bytes<'b>(
+				&self,
+				endianness: Endianness,
+				mut bytes: &'b mut [u8],
+			) -> Result<&'b mut [u8]> {
+				use crate::util::produce;
+
+				if bytes.len() < core::mem::size_of::<Self>() {
+					return Err(crate::error::Error::new(
+						crate::error::ErrorKind::InsufficantSize,
+					));
+				}
+
+				produce!(bytes, endianness => self.p_type);
+				produce!(bytes, endianness => self.p_offset);
+				produce!(bytes, endianness => self.p_vaddr);
+				produce!(bytes, endianness => self.p_paddr);
+				produce!(bytes, endianness => self.p_filesz);
+				produce!(bytes, endianness => self.p_memsz);
+				produce!(bytes, endianness => self.p_flags);
+				produce!(bytes, endianness => self.p_align);
+
+				Ok(bytes)
+			}
+
+			/// Returns the data of the segment in `bytes` (the whole file).
+			///
+			/// # Panics
+			///
+			/// If the segment lies outside of `bytes` (see
+			/// [`ProgramHeader::try_extract_data`]).
 			pub fn extract_data<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
 				let start = self.p_offset as usize;
 				let end = start + (self.p_filesz as usize);
 
 				core::ops::Index::index(bytes, start..end)
 			}
-		}
 
-		impl core::ops::Index<&ProgramHeader> for &[u8] {
-			type Output = [u8];
+			/// Returns the data of the segment in `bytes` (the whole file) or
+			/// `None` if the segment lies outside of `bytes`.
+			pub fn try_extract_data<'a>(
+				&self,
+				bytes: &'a [u8],
+			) -> Option<&'a [u8]> {
+				let start = usize::try_from(self.p_offset).ok()?;
+				let len = usize::try_from(self.p_filesz).ok()?;
 
-			fn index(&self, index: &ProgramHeader) -> &Self::Output {
-				index.extract_data(self)
+				bytes.get(start..start.checked_add(len)?)
 			}
-		}
 
-		#[rustfmt::skip]
-		impl fmt::Display for ProgramHeader {
-			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			/// Returns the virtual address (`p_vaddr`) of the segment.
+			pub fn virt_addr(&self) -> crate::program_header::VirtAddr {
+				crate::program_header::VirtAddr(self.p_vaddr as u64)
+			}
+
+			/// Returns the physical address (`p_paddr`) of the segment.
+			pub fn phys_addr(&self) -> crate::program_header::PhysAddr {
+				crate::program_header::PhysAddr(self.p_paddr as u64)
+			}
+
+			/// Checks if the segment is loadable (`PT_LOAD`).
+			pub fn is_load(&self) -> bool {
+				self.p_type
+					== crate::program_header::consts::typ::P_TYPE_PT_LOAD
+			}
+
+			/// Checks if the segment is readable (`PF_R`).
+			pub fn is_readable(&self) -> bool {
+				self.p_flags
+					& crate::program_header::consts::flags::P_FLAGS_PF_R
+					!= 0
+			}
+
+			/// Checks if the segment is writable (`PF_W`).
+			pub fn is_writable(&self) -> bool {
+				self.p_flags
+					& crate::program_header::consts::flags::P_FLAGS_PF_W
+					!= 0
+			}
+
+			/// Checks if the segment is executable (`PF_X`).
+			pub fn is_executable(&self) -> bool {
+				self.p_flags
+					& crate::program_header::consts::flags::P_FLAGS_PF_X
+					!= 0
+			}
+
+			/// Checks that loadable segments have `p_filesz <= p_memsz` and
+			/// `p_vaddr` congruent to `p_offset` modulo `p_align`.
+			pub fn validate(&self) -> Result<()> {
+				super::validate(
+					self.is_load(),
+					self.p_offset as u64,
+					self.p_vaddr as u64,
+					self.p_filesz as u64,
+					self.p_memsz as u64,
+					self.p_align as u64,
+				)
+			}
+		}
+
+		impl core::ops::Index<&ProgramHeader> for &[u8] {
+			type Output = [u8];
+
+			fn index(&self, index: &ProgramHeader) -> &Self::Output {
+				index.extract_data(self)
+			}
+		}
+
+		#[rustfmt::skip]
+		impl fmt::Display for ProgramHeader {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 				f.write_fmt(format_args!(r#"ProgramHeader:
 	p_type  : {}
 	p_offset: {}
@@ -882,9 +1722,17 @@ pub mod program_header {
 		use core::fmt;
 
 		use crate::error::Result;
+		use crate::header::consts::ident::data::Endianness;
+
+		/// Size of an on-disk 64-bit program header in bytes.
+		pub const PHENTSIZE: u16 = 56;
 
 		#[repr(C)]
 		#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		#[cfg_attr(
+			feature = "serde",
+			derive(serde::Serialize, serde::Deserialize)
+		)]
 		pub struct ProgramHeader {
 			/// Field `p_type`: Identifies the type of the segment.
 			pub p_type: u32,
@@ -919,31 +1767,148 @@ pub mod program_header {
 		}
 
 		impl ProgramHeader {
-			#[allow(unused_assignments, clippy::eval_order_dependence)]
 			pub fn from_bytes(
-				endianness: u8,
-				mut bytes: &[u8],
+				endianness: Endianness,
+				bytes: &[u8],
 			) -> Result<Self> {
+				Self::from_bytes_consuming(endianness, bytes)
+					.map(|(header, _)| header)
+			}
+
+			/// Parses the program header and returns it together with the
+			/// remaining bytes following it.
+			#[allow(
+				unused_assignments,
+				clippy::mixed_read_write_in_expression
+			)]
+			pub fn from_bytes_consuming(
+				endianness: Endianness,
+				mut bytes: &[u8],
+			) -> Result<(Self, &[u8])> {
 				use crate::util::consume;
 
-				Ok(Self {
-					p_type: consume!(bytes, endianness => u32)?,
-					p_flags: consume!(bytes, endianness => u32)?,
-					p_offset: consume!(bytes, endianness => u64)?,
-					p_vaddr: consume!(bytes, endianness => u64)?,
-					p_paddr: consume!(bytes, endianness => u64)?,
-					p_filesz: consume!(bytes, endianness => u64)?,
-					p_memsz: consume!(bytes, endianness => u64)?,
-					p_align: consume!(bytes, endianness => u64)?,
-				})
+				if bytes.len() < core::mem::size_of::<Self>() {
+					return Err(crate::error::Error::new(
+						crate::error::ErrorKind::InsufficantSize,
+					));
+				}
+
+				let header = Self {
+					p_type: consume!(bytes, endianness => u32),
+					p_flags: consume!(bytes, endianness => u32),
+					p_offset: consume!(bytes, endianness => u64),
+					p_vaddr: consume!(bytes, endianness => u64),
+					p_paddr: consume!(bytes, endianness => u64),
+					p_filesz: consume!(bytes, endianness => u64),
+					p_memsz: consume!(bytes, endianness => u64),
+					p_align: consume!(bytes, endianness => u64),
+				};
+
+				Ok((header, bytes))
+			}
+
+			/// Writes the on-disk representation of the program header with
+			/// the given `endianness` to the start of `bytes` and returns the
+			/// remaining bytes following it.
+			pub fn to_bytes<'b>(
+				&self,
+				endianness: Endianness,
+				mut bytes: &'b mut [u8],
+			) -> Result<&'b mut [u8]> {
+				use crate::util::produce;
+
+				if bytes.len() < core::mem::size_of::<Self>() {
+					return Err(crate::error::Error::new(
+						crate::error::ErrorKind::InsufficantSize,
+					));
+				}
+
+				produce!(bytes, endianness => self.p_type);
+				produce!(bytes, endianness => self.p_flags);
+				produce!(bytes, endianness => self.p_offset);
+				produce!(bytes, endianness => self.p_vaddr);
+				produce!(bytes, endianness => self.p_paddr);
+				produce!(bytes, endianness => self.p_filesz);
+				produce!(bytes, endianness => self.p_memsz);
+				produce!(bytes, endianness => self.p_align);
+
+				Ok(bytes)
 			}
 
+			/// Returns the data of the segment in `bytes` (the whole file).
+			///
+			/// # Panics
+			///
+			/// If the segment lies outside of `bytes` (see
+			/// [`ProgramHeader::try_extract_data`]).
 			pub fn extract_data<'a>(&self, bytes: &'a [u8]) -> &'a [u8] {
 				let start = self.p_offset as usize;
 				let end = start + (self.p_filesz as usize);
 
 				core::ops::Index::index(bytes, start..end)
 			}
+
+			/// Returns the data of the segment in `bytes` (the whole file) or
+			/// `None` if the segment lies outside of `bytes`.
+			pub fn try_extract_data<'a>(
+				&self,
+				bytes: &'a [u8],
+			) -> Option<&'a [u8]> {
+				let start = usize::try_from(self.p_offset).ok()?;
+				let len = usize::try_from(self.p_filesz).ok()?;
+
+				bytes.get(start..start.checked_add(len)?)
+			}
+
+			/// Returns the virtual address (`p_vaddr`) of the segment.
+			pub fn virt_addr(&self) -> crate::program_header::VirtAddr {
+				crate::program_header::VirtAddr(self.p_vaddr)
+			}
+
+			/// Returns the physical address (`p_paddr`) of the segment.
+			pub fn phys_addr(&self) -> crate::program_header::PhysAddr {
+				crate::program_header::PhysAddr(self.p_paddr)
+			}
+
+			/// Checks if the segment is loadable (`PT_LOAD`).
+			pub fn is_load(&self) -> bool {
+				self.p_type
+					== crate::program_header::consts::typ::P_TYPE_PT_LOAD
+			}
+
+			/// Checks if the segment is readable (`PF_R`).
+			pub fn is_readable(&self) -> bool {
+				self.p_flags
+					& crate::program_header::consts::flags::P_FLAGS_PF_R
+					!= 0
+			}
+
+			/// Checks if the segment is writable (`PF_W`).
+			pub fn is_writable(&self) -> bool {
+				self.p_flags
+					& crate::program_header::consts::flags::P_FLAGS_PF_W
+					!= 0
+			}
+
+			/// Checks if the segment is executable (`PF_X`).
+			pub fn is_executable(&self) -> bool {
+				self.p_flags
+					& crate::program_header::consts::flags::P_FLAGS_PF_X
+					!= 0
+			}
+
+			/// Checks that loadable segments have `p_filesz <= p_memsz` and
+			/// `p_vaddr` congruent to `p_offset` modulo `p_align`.
+			pub fn validate(&self) -> Result<()> {
+				super::validate(
+					self.is_load(),
+					self.p_offset,
+					self.p_vaddr,
+					self.p_filesz,
+					self.p_memsz,
+					self.p_align,
+				)
+			}
 		}
 
 		impl core::ops::Index<&ProgramHeader> for &[u8] {
@@ -978,6 +1943,66 @@ pub mod program_header {
 			}
 		}
 	}
+	#[cfg(test)]
+	mod tests {
+		use super::consts::flags::*;
+
+		#[test]
+		fn permissions() {
+			// (p_flags, (readable, writable, executable))
+			let cases = [
+				(0b000, (false, false, false)),
+				(0b001, (false, false, true)),
+				(0b010, (false, true, false)),
+				(0b011, (false, true, true)),
+				(0b100, (true, false, false)),
+				(0b101, (true, false, true)),
+				(0b110, (true, true, false)),
+				(0b111, (true, true, true)),
+			];
+
+			for (p_flags, expected) in cases {
+				let ph32 = super::elf32::ProgramHeader {
+					p_flags,
+					..Default::default()
+				};
+				let ph64 = super::elf64::ProgramHeader {
+					p_flags,
+					..Default::default()
+				};
+
+				assert_eq!(
+					(
+						ph32.is_readable(),
+						ph32.is_writable(),
+						ph32.is_executable()
+					),
+					expected,
+					"p_flags: {p_flags:#05b}"
+				);
+				assert_eq!(
+					(
+						ph64.is_readable(),
+						ph64.is_writable(),
+						ph64.is_executable()
+					),
+					expected,
+					"p_flags: {p_flags:#05b}"
+				);
+			}
+
+			// os/processor specific bits do not affect the permissions
+			let ph = super::elf64::ProgramHeader {
+				p_flags: P_FLAGS_PF_MASKOS
+					| P_FLAGS_PF_MASKPROC
+					| P_FLAGS_PF_R | P_FLAGS_PF_X,
+				..Default::default()
+			};
+			assert!(ph.is_readable());
+			assert!(!ph.is_writable());
+			assert!(ph.is_executable());
+		}
+	}
 }
 
 pub mod section_header {
@@ -1038,10 +2063,62 @@ pub mod section_header {
 
 					/// Number of defined types.
 					SH_TYPE_SHT_NUM: "SHT_NUM" = 0x00000013,
+
+					/// GNU-style symbol hash table.
+					SH_TYPE_SHT_GNU_HASH: "SHT_GNU_HASH" = 0x6ffffff6,
 				}, {
+					(0x70000000..=0x7fffffff) => "RESERVED: Processor specific",
 					(0x60000000..) => "RESERVED: Operating system specific",
 				}
 			}
+
+			/// Processor specific section types of RISC-V.
+			///
+			/// # References
+			/// - <https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#section-types>
+			pub mod riscv {
+				/// Field `sh_type`: RISC-V attributes (`.riscv.attributes`).
+				pub const SH_TYPE_SHT_RISCV_ATTRIBUTES: u32 = 0x70000003;
+
+				/// Like [`super::sh_type_as_str`], but also names the RISC-V
+				/// specific types.
+				pub fn sh_type_as_str(value: u32) -> &'static str {
+					match value {
+						SH_TYPE_SHT_RISCV_ATTRIBUTES => "SHT_RISCV_ATTRIBUTES",
+						_ => super::sh_type_as_str(value),
+					}
+				}
+			}
+		}
+
+		pub mod shn {
+			crate::util::def_consts! {
+				shn : u16 : shn_as_str => {
+					/// Undefined section.
+					SHN_UNDEF : "SHN_UNDEF" = 0x0000,
+
+					/// Absolute value, not affected by relocation.
+					SHN_ABS : "SHN_ABS" = 0xfff1,
+
+					/// Common symbol, not yet allocated (e.g. unallocated C
+					/// external variables).
+					SHN_COMMON : "SHN_COMMON" = 0xfff2,
+
+					/// Section index is too large and stored elsewhere
+					/// (e.g. in `SHT_SYMTAB_SHNDX`).
+					SHN_XINDEX : "SHN_XINDEX" = 0xffff,
+				}, {
+					(0xff00..=0xff1f) => "RESERVED: Processor specific",
+					(0xff20..=0xff3f) => "RESERVED: Operating system specific",
+					(0xff40..=0xfffe) => "RESERVED",
+				}
+			}
+
+			/// Field `shn`: Lower bound of the reserved indices.
+			pub const SHN_LORESERVE: u16 = 0xff00;
+
+			/// Field `shn`: Upper bound of the reserved indices.
+			pub const SHN_HIRESERVE: u16 = 0xffff;
 		}
 
 		pub mod flags {
@@ -1054,7 +2131,7 @@ pub mod section_header {
 					pub const SH_FLAG_SHF_ALLOC: $size = 0x02;
 
 					/// Field `sh_flags`: Executable.
-					pub const SH_FLAG_SHF_EXECINSTR: $size = 0x03;
+					pub const SH_FLAG_SHF_EXECINSTR: $size = 0x04;
 
 					/// Field `sh_flags`: Might be merged.
 					pub const SH_FLAG_SHF_MERGE: $size = 0x10;
@@ -1085,11 +2162,11 @@ pub mod section_header {
 					pub const SH_FLAG_SHF_MASKPROC: $size = 0xf000_0000;
 
 					/// Field `sh_flags`: Special ordering requirement (Solaris).
-					pub const SH_FLAG_SHF_ORDERED: $size = 0x400_0000;
+					pub const SH_FLAG_SHF_ORDERED: $size = 0x4000_0000;
 
 					/// Field `sh_flags`: Section is excluded unless referenced
 					/// or allocated (Solaris).
-					pub const SH_FLAG_SHF_EXCLUDE: $size = 0x800_0000;
+					pub const SH_FLAG_SHF_EXCLUDE: $size = 0x8000_0000;
 				};
 			}
 
@@ -1100,6 +2177,61 @@ pub mod section_header {
 			pub mod elf64 {
 				def_flags!(u64);
 			}
+
+			/// Displays `sh_flags` as the flag letters used by `readelf`
+			/// (e.g. `WAX` for a writable, allocated and executable section).
+			///
+			/// Unknown flags are shown as `o` (OS-specific), `p`
+			/// (processor-specific) or `x` (unknown).
+			#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+			pub struct FlagLetters(pub u64);
+
+			impl core::fmt::Display for FlagLetters {
+				fn fmt(
+					&self,
+					f: &mut core::fmt::Formatter<'_>,
+				) -> core::fmt::Result {
+					use core::fmt::Write;
+
+					use self::elf64::*;
+
+					const LETTERS: [(u64, char); 11] = [
+						(SH_FLAG_SHF_WRITE, 'W'),
+						(SH_FLAG_SHF_ALLOC, 'A'),
+						(SH_FLAG_SHF_EXECINSTR, 'X'),
+						(SH_FLAG_SHF_MERGE, 'M'),
+						(SH_FLAG_SHF_STRINGS, 'S'),
+						(SH_FLAG_SHF_INFO_LINK, 'I'),
+						(SH_FLAG_SHF_LINK_ORDER, 'L'),
+						(SH_FLAG_SHF_OS_NONCONFORMING, 'O'),
+						(SH_FLAG_SHF_GROUP, 'G'),
+						(SH_FLAG_SHF_TLS, 'T'),
+						(SH_FLAG_SHF_EXCLUDE, 'E'),
+					];
+
+					let mut rest = self.0;
+
+					for (flag, letter) in LETTERS {
+						if rest & flag != 0 {
+							f.write_char(letter)?;
+							rest &= !flag;
+						}
+					}
+
+					for (mask, letter) in [
+						(SH_FLAG_SHF_MASKOS, 'o'),
+						(SH_FLAG_SHF_MASKPROC, 'p'),
+						(!0, 'x'),
+					] {
+						if rest & mask != 0 {
+							f.write_char(letter)?;
+							rest &= !mask;
+						}
+					}
+
+					Ok(())
+				}
+			}
 		}
 	}
 
@@ -1107,6 +2239,7 @@ pub mod section_header {
 		( $size:ty ) => {
 			#[repr(C)]
 			#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+			#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 			pub struct SectionHeader {
 				/// Field `sh_name`: Offset to a string in the `.shstrtab`
 				/// section containing the name of the section.
@@ -1156,30 +2289,93 @@ pub mod section_header {
 			}
 
 			impl SectionHeader {
-                #[allow(unused_assignments, clippy::eval_order_dependence)]
-				pub fn from_bytes(endianness: u8, mut bytes: &[u8]) -> crate::error::Result<Self> {
+				pub fn from_bytes(endianness: crate::header::consts::ident::data::Endianness, bytes: &[u8]) -> crate::error::Result<Self> {
+					Self::from_bytes_consuming(endianness, bytes).map(|(header, _)| header)
+				}
+
+				/// Parses the section header and returns it together with the
+				/// remaining bytes following it.
+                #[allow(unused_assignments, clippy::mixed_read_write_in_expression)]
+				pub fn from_bytes_consuming(endianness: crate::header::consts::ident::data::Endianness, mut bytes: &[u8]) -> crate::error::Result<(Self, &[u8])> {
 					use crate::util::consume;
 
-                    Ok(Self {
-                        sh_name: consume!(bytes, endianness => u32)?,
-                        sh_type: consume!(bytes, endianness => u32)?,
-                        sh_flags: consume!(bytes, endianness => $size)?,
-                        sh_addr: consume!(bytes, endianness => $size)?,
-                        sh_offset: consume!(bytes, endianness => $size)?,
-                        sh_size: consume!(bytes, endianness => $size)?,
-                        sh_link: consume!(bytes, endianness => u32)?,
-                        sh_info: consume!(bytes, endianness => u32)?,
-                        sh_addralign: consume!(bytes, endianness => $size)?,
-                        sh_entsize: consume!(bytes, endianness => $size)?,
-                    })
+					if bytes.len() < core::mem::size_of::<Self>() {
+                        return Err(crate::error::Error::new(crate::error::ErrorKind::InsufficantSize))
+					}
+
+                    let header = Self {
+                        sh_name: consume!(bytes, endianness => u32),
+                        sh_type: consume!(bytes, endianness => u32),
+                        sh_flags: consume!(bytes, endianness => $size),
+                        sh_addr: consume!(bytes, endianness => $size),
+                        sh_offset: consume!(bytes, endianness => $size),
+                        sh_size: consume!(bytes, endianness => $size),
+                        sh_link: consume!(bytes, endianness => u32),
+                        sh_info: consume!(bytes, endianness => u32),
+                        sh_addralign: consume!(bytes, endianness => $size),
+                        sh_entsize: consume!(bytes, endianness => $size),
+                    };
+
+                    Ok((header, bytes))
+				}
+
+				/// Writes the on-disk representation of the section header
+				/// with the given `endianness` to the start of `bytes` and
+				/// returns the remaining bytes following it.
+				pub fn to_bytes<'b>(&self, endianness: crate::header::consts::ident::data::Endianness, mut bytes: &'b mut [u8]) -> crate::error::Result<&'b mut [u8]> {
+					use crate::util::produce;
+
+					if bytes.len() < core::mem::size_of::<Self>() {
+                        return Err(crate::error::Error::new(crate::error::ErrorKind::InsufficantSize))
+					}
+
+                    produce!(bytes, endianness => self.sh_name);
+                    produce!(bytes, endianness => self.sh_type);
+                    produce!(bytes, endianness => self.sh_flags);
+                    produce!(bytes, endianness => self.sh_addr);
+                    produce!(bytes, endianness => self.sh_offset);
+                    produce!(bytes, endianness => self.sh_size);
+                    produce!(bytes, endianness => self.sh_link);
+                    produce!(bytes, endianness => self.sh_info);
+                    produce!(bytes, endianness => self.sh_addralign);
+                    produce!(bytes, endianness => self.sh_entsize);
+
+                    Ok(bytes)
 				}
 
+				/// Returns the data of the section in `bytes` (the whole file).
+				///
+				/// Sections which occupy no space in the file (`SHT_NOBITS`,
+				/// e.g. `.bss`) have no data, even if `sh_size` is non-zero.
+				///
+				/// # Panics
+				///
+				/// If the section lies outside of `bytes` (see
+				/// [`SectionHeader::try_extract_data`]).
 				pub fn extract_data<'a>(&self, bytes: &'a[u8]) -> &'a [u8] {
+					if self.sh_type == crate::section_header::consts::typ::SH_TYPE_SHT_NOBITS {
+						return &[];
+					}
+
 					let start = self.sh_offset as usize;
 					let end = start + (self.sh_size as usize);
 
 					core::ops::Index::index(bytes, start..end)
 				}
+
+				/// Returns the data of the section in `bytes` (the whole file)
+				/// like [`SectionHeader::extract_data`] or `None` if the
+				/// section lies outside of `bytes`.
+				pub fn try_extract_data<'a>(&self, bytes: &'a[u8]) -> Option<&'a [u8]> {
+					if self.sh_type == crate::section_header::consts::typ::SH_TYPE_SHT_NOBITS {
+						return Some(&[]);
+					}
+
+					let start = usize::try_from(self.sh_offset).ok()?;
+					let len = usize::try_from(self.sh_size).ok()?;
+
+					bytes.get(start..start.checked_add(len)?)
+				}
 			}
 
 			impl core::ops::Index<&SectionHeader> for &[u8] {
@@ -1195,7 +2391,7 @@ pub mod section_header {
 					f.write_fmt(format_args!(r#"SectionHeader:
 	sh_name     : {}
 	sh_type     : {}
-	sh_flags    : 0b{:0size_bin$b}
+	sh_flags    : {} (0x{:0size_hex$x})
 	sh_addr     : 0x{:0size_hex$x}
 	sh_offset   : {}
 	sh_size     : {}
@@ -1205,6 +2401,7 @@ pub mod section_header {
 	sh_entsize  : {}"#,
 						self.sh_name,
 						crate::section_header::consts::typ::sh_type_as_str(self.sh_type),
+						crate::section_header::consts::flags::FlagLetters(self.sh_flags as u64),
 						self.sh_flags,
 						self.sh_addr,
 						self.sh_offset,
@@ -1214,7 +2411,6 @@ pub mod section_header {
 						self.sh_addralign,
 						self.sh_entsize,
 						size_hex = core::mem::size_of::<$size>() * 2,
-						size_bin = core::mem::size_of::<$size>() * 8,
 					))
 				}
 			}
@@ -1222,15 +2418,41 @@ pub mod section_header {
 	}
 
 	pub mod elf32 {
+		/// Size of an on-disk 32-bit section header in bytes.
+		pub const SHENTSIZE: u16 = 40;
+
 		section_header!(u32);
 	}
 
 	pub mod elf64 {
+		/// Size of an on-disk 64-bit section header in bytes.
+		pub const SHENTSIZE: u16 = 64;
+
 		section_header!(u64);
 	}
 }
 
 pub mod strtab {
+	/// Reads the null-terminated string starting at `offset` (without the
+	/// terminator).
+	///
+	/// Returns `None` if `offset` is out of range or no terminator is found
+	/// before the end of `bytes`.
+	pub fn read_cstr(bytes: &[u8], offset: usize) -> Option<&[u8]> {
+		let data = bytes.get(offset..)?;
+		let end = data.iter().position(|&b| b == b'\0')?;
+
+		data.get(..end)
+	}
+
+	/// Same as [`read_cstr`], but converts the string to utf-8.
+	pub fn read_cstr_str(
+		bytes: &[u8],
+		offset: usize,
+	) -> Option<core::result::Result<&str, core::str::Utf8Error>> {
+		read_cstr(bytes, offset).map(core::str::from_utf8)
+	}
+
 	pub struct Strtab<'a> {
 		delim: u8,
 		data: &'a [u8],
@@ -1250,11 +2472,13 @@ pub mod strtab {
 			self.data.split(|b| b == &self.delim).skip(index).next()
 		}
 
+		/// Returns the string starting at `offset` (without the delimiter),
+		/// or `None` if `offset` is past the end of the table.
 		pub fn get_bytes_off(
 			&self,
 			offset: usize,
 		) -> core::option::Option<&'a [u8]> {
-			let data = core::ops::Index::index(self.data, offset..);
+			let data = self.data.get(offset..)?;
 			data.split(|b| b == &self.delim).next()
 		}
 
@@ -1282,52 +2506,203 @@ pub mod strtab {
 				.map(|b| core::str::from_utf8_unchecked(b))
 		}
 	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+
+		#[test]
+		fn cstr() {
+			let bytes = b"\0.text\0.data\0.bss";
+
+			assert_eq!(read_cstr(bytes, 0), Some(&b""[..]));
+			assert_eq!(read_cstr(bytes, 1), Some(&b".text"[..]));
+			assert_eq!(read_cstr(bytes, 3), Some(&b"ext"[..]));
+			assert_eq!(read_cstr_str(bytes, 7), Some(Ok(".data")));
+
+			// Unterminated
+			assert_eq!(read_cstr(bytes, 13), None);
+			assert_eq!(read_cstr_str(bytes, 14), None);
+
+			// Out of range
+			assert_eq!(read_cstr(bytes, bytes.len()), None);
+			assert_eq!(read_cstr(bytes, usize::MAX), None);
+
+			assert!(matches!(read_cstr_str(b"\xff\0", 0), Some(Err(_))));
+		}
+
+		#[test]
+		fn get_bytes_off_out_of_range() {
+			let strtab = Strtab::new(Strtab::DEFAULT_DELIM, b"\0.text\0");
+
+			assert_eq!(strtab.get_bytes_off(1), Some(&b".text"[..]));
+			assert_eq!(strtab.get_bytes_off(7), Some(&b""[..]));
+			assert_eq!(strtab.get_bytes_off(8), None);
+			assert_eq!(strtab.get_bytes_off(usize::MAX), None);
+			assert_eq!(unsafe { strtab.get_str_off_unchecked(9) }, None);
+		}
+	}
 }
 
 pub mod symtab {
-	macro_rules! symbol_table {
-		( $size:ty ) => {
-			#[repr(C)]
-			#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
-			pub struct Symbol {
-				/// Field `st_name`: Name of the symbol.
-				pub st_name: u32,
+	pub mod consts {
+		pub mod typ {
+			crate::util::def_consts! {
+				st_type : u8 : st_type_as_str => {
+					/// Type not specified.
+					ST_TYPE_STT_NOTYPE : "STT_NOTYPE" = 0x00,
+
+					/// Data object (e.g. variable or array).
+					ST_TYPE_STT_OBJECT : "STT_OBJECT" = 0x01,
+
+					/// Function or other executable code.
+					ST_TYPE_STT_FUNC : "STT_FUNC" = 0x02,
+
+					/// Section.
+					ST_TYPE_STT_SECTION : "STT_SECTION" = 0x03,
+
+					/// Source file.
+					ST_TYPE_STT_FILE : "STT_FILE" = 0x04,
+
+					/// Uninitialized common block.
+					ST_TYPE_STT_COMMON : "STT_COMMON" = 0x05,
+
+					/// Thread-local storage entity.
+					ST_TYPE_STT_TLS : "STT_TLS" = 0x06,
+				}
+			}
+		}
+
+		pub mod bind {
+			crate::util::def_consts! {
+				st_bind : u8 : st_bind_as_str => {
+					/// Not visible outside the object file.
+					ST_BIND_STB_LOCAL : "STB_LOCAL" = 0x00,
+
+					/// Visible to all object files.
+					ST_BIND_STB_GLOBAL : "STB_GLOBAL" = 0x01,
 
-				/// Field `st_value`: Value of the symbol.
-				pub st_value: $size,
+					/// Global with lower precedence.
+					ST_BIND_STB_WEAK : "STB_WEAK" = 0x02,
+				}
+			}
+		}
+	}
+
+	use crate::header::consts::ident::data::Endianness;
+
+	/// Meaning of the `st_shndx` of a symbol (see `Symbol::section_kind`).
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum SectionKind {
+		/// Index of the section the symbol is defined in.
+		Index(u16),
+		/// `SHN_UNDEF`: Undefined (e.g. defined in another object).
+		Undefined,
+		/// `SHN_ABS`: Absolute value, not relative to any section.
+		Absolute,
+		/// `SHN_COMMON`: Common symbol, not yet allocated.
+		Common,
+		/// `SHN_XINDEX`: The index is stored in the `SHT_SYMTAB_SHNDX`
+		/// table.
+		Extended,
+		/// Other reserved index (e.g. processor specific).
+		Reserved(u16),
+	}
+
+	impl SectionKind {
+		/// Classifies the value of `st_shndx`.
+		pub const fn from_st_shndx(st_shndx: u16) -> Self {
+			use crate::section_header::consts::shn::{
+				SHN_ABS, SHN_COMMON, SHN_LORESERVE, SHN_UNDEF, SHN_XINDEX,
+			};
+
+			match st_shndx {
+				SHN_UNDEF => Self::Undefined,
+				SHN_ABS => Self::Absolute,
+				SHN_COMMON => Self::Common,
+				SHN_XINDEX => Self::Extended,
+				index if index >= SHN_LORESERVE => Self::Reserved(index),
+				index => Self::Index(index),
+			}
+		}
+	}
+
+	/// Extended section indices of a symbol table (`SHT_SYMTAB_SHNDX`).
+	///
+	/// Holds one 32-bit entry per symbol of the associated symbol table,
+	/// which is only used if the symbols `st_shndx` is `SHN_XINDEX`.
+	pub struct ShndxTable<'a> {
+		endianness: Endianness,
+		data: &'a [u8],
+	}
+
+	impl<'a> ShndxTable<'a> {
+		const ENTRY_SIZE: usize = core::mem::size_of::<u32>();
+
+		pub fn new(endianness: Endianness, data: &'a [u8]) -> Self {
+			Self { endianness, data }
+		}
 
-				/// Field `st_size`: Size of the symbol.
-				pub st_size: $size,
+		pub fn len(&self) -> usize {
+			self.data.len() / Self::ENTRY_SIZE
+		}
 
-				/// Field `st_info`: Additional information.
-				pub st_info: u8,
+		pub fn is_empty(&self) -> bool {
+			self.len() == 0
+		}
 
-				/// Field `st_other`: Other (currently not used).
-				pub st_other: u8,
+		/// Returns the section index of the symbol at `index`.
+		#[allow(unused_assignments)]
+		pub fn get(&self, index: usize) -> core::option::Option<u32> {
+			use crate::util::consume;
 
-				/// Field `st_shndx`: Index of the SectionHeader.
-				pub st_shndx: u16,
+			if index >= self.len() {
+				return None;
 			}
 
+			let mut bytes =
+				core::ops::Index::index(self.data, index * Self::ENTRY_SIZE..);
+			Some(consume!(bytes, self.endianness => u32))
+		}
+	}
+
+	/// Shared parts of the symbol tables. The `Symbol` itself is defined per
+	/// class, as the order of the fields differs.
+	macro_rules! symbol_table {
+		() => {
 			impl Symbol {
-                #[allow(unused_assignments, clippy::eval_order_dependence)]
-				pub fn from_bytes(endianness: u8, mut bytes: &[u8]) -> crate::error::Result<Self> {
-					use crate::util::consume;
+				pub fn from_bytes(
+					endianness: Endianness,
+					bytes: &[u8],
+				) -> crate::error::Result<Self> {
+					Self::from_bytes_consuming(endianness, bytes)
+						.map(|(symbol, _)| symbol)
+				}
+
+				/// Type of the symbol (lower 4 bits of `st_info`).
+				pub fn st_type(&self) -> u8 {
+					self.st_info & 0xf
+				}
+
+				/// Binding of the symbol (upper 4 bits of `st_info`).
+				pub fn st_bind(&self) -> u8 {
+					self.st_info >> 4
+				}
 
-                    Ok(Self {
-                        st_name: consume!(bytes, endianness => u32)?,
-                        st_value: consume!(bytes, endianness => $size)?,
-                        st_size: consume!(bytes, endianness => $size)?,
-                        st_info: consume!(bytes, endianness => u8)?,
-                        st_other: consume!(bytes, endianness => u8)?,
-                        st_shndx: consume!(bytes, endianness => u16)?,
-                    })
+				/// Returns whether `st_shndx` is a real section index or one
+				/// of the special values (e.g. `SHN_ABS`).
+				pub fn section_kind(&self) -> crate::symtab::SectionKind {
+					crate::symtab::SectionKind::from_st_shndx(self.st_shndx)
 				}
 			}
 
 			impl core::fmt::Display for Symbol {
-				fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-					f.write_fmt(format_args!(r#"Symbol:
+				fn fmt(
+					&self,
+					f: &mut core::fmt::Formatter<'_>,
+				) -> core::fmt::Result {
+					f.write_fmt(format_args!(
+						r#"Symbol:
 	st_name : {}
 	st_value: {}
 	st_size : {}
@@ -1345,19 +2720,41 @@ pub mod symtab {
 			}
 
 			pub struct Symtab<'a> {
-				endianness: u8,
+				endianness: Endianness,
 				data: &'a [u8],
+				len: usize,
 			}
 
 			impl<'a> Symtab<'a> {
 				const SYMBOL_SIZE: usize = core::mem::size_of::<Symbol>();
 
-				pub fn new(endianness: u8, data: &'a [u8]) -> Self {
-					Self { endianness, data }
+				/// Creates a new symbol table. A trailing partial entry is
+				/// ignored (see [`Symtab::try_new`]).
+				pub fn new(endianness: Endianness, data: &'a [u8]) -> Self {
+					Self {
+						endianness,
+						data,
+						len: data.len() / Self::SYMBOL_SIZE,
+					}
+				}
+
+				/// Creates a new symbol table, failing if the size of `data`
+				/// is not a multiple of the size of a symbol.
+				pub fn try_new(
+					endianness: Endianness,
+					data: &'a [u8],
+				) -> crate::error::Result<Self> {
+					if data.len() % Self::SYMBOL_SIZE != 0 {
+						return Err(crate::error::Error::new(
+							crate::error::ErrorKind::InvalidTableSize,
+						));
+					}
+
+					Ok(Self::new(endianness, data))
 				}
 
 				pub fn len(&self) -> usize {
-					self.data.len() / Self::SYMBOL_SIZE
+					self.len
 				}
 
 				pub fn get_symbol(
@@ -1366,83 +2763,2143 @@ pub mod symtab {
 				) -> core::option::Option<Symbol> {
 					let start = index * Self::SYMBOL_SIZE;
 
-					if start < self.data.len() {
+					if index < self.len {
 						let data = core::ops::Index::index(self.data, start..);
 						Symbol::from_bytes(self.endianness, data).ok()
 					} else {
 						None
 					}
 				}
+
+				/// Returns the index of the section the symbol at `index`
+				/// is defined in relation to.
+				///
+				/// Extended indices (`SHN_XINDEX`) are looked up in `shndx`
+				/// (`SHT_SYMTAB_SHNDX` associated with this table). Returns
+				/// `None` if the symbol does not exist or the extended index
+				/// can not be resolved.
+				pub fn section_index(
+					&self,
+					index: usize,
+					shndx: core::option::Option<
+						&crate::symtab::ShndxTable<'_>,
+					>,
+				) -> core::option::Option<u32> {
+					let symbol = self.get_symbol(index)?;
+
+					if symbol.st_shndx
+						== crate::section_header::consts::shn::SHN_XINDEX
+					{
+						shndx?.get(index)
+					} else {
+						Some(symbol.st_shndx as u32)
+					}
+				}
 			}
 		};
 	}
 
 	pub mod elf32 {
-		symbol_table!(u32);
-	}
+		use crate::error::Result;
+		use crate::header::consts::ident::data::Endianness;
 
-	pub mod elf64 {
-		symbol_table!(u64);
-	}
-}
+		/// Size of an on-disk 32-bit symbol in bytes.
+		pub const SYMENTSIZE: u16 = 16;
 
-#[cfg(feature = "std")]
-pub mod elf {
-	use crate::error::{Error, ErrorKind, Result};
-	use crate::header::consts::ident::class::{EI_CLASS_32, EI_CLASS_64};
-	use crate::header::consts::ident::index::EI_CLASS;
-	use crate::header::elf32::Header as Header32;
-	use crate::header::elf64::Header as Header64;
-	use crate::program_header::elf32::ProgramHeader as ProgramHeader32;
-	use crate::program_header::elf64::ProgramHeader as ProgramHeader64;
-	use crate::section_header::elf32::SectionHeader as SectionHeader32;
-	use crate::section_header::elf64::SectionHeader as SectionHeader64;
+		#[repr(C)]
+		#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		#[cfg_attr(
+			feature = "serde",
+			derive(serde::Serialize, serde::Deserialize)
+		)]
+		pub struct Symbol {
+			/// Field `st_name`: Name of the symbol.
+			pub st_name: u32,
 
-	pub enum Elf<'a> {
-		Elf32 {
-			bytes: &'a [u8],
-			header: Header32,
-			pheaders: Vec<ProgramHeader32>,
-			sheaders: Vec<SectionHeader32>,
-		},
-		Elf64 {
-			bytes: &'a [u8],
-			header: Header64,
-			pheaders: Vec<ProgramHeader64>,
-			sheaders: Vec<SectionHeader64>,
-		},
-	}
+			/// Field `st_value`: Value of the symbol.
+			pub st_value: u32,
 
-	impl<'a> Elf<'a> {
-		pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
-			let class = *core::ops::Index::index(bytes, EI_CLASS);
+			/// Field `st_size`: Size of the symbol.
+			pub st_size: u32,
 
-			match class {
-				EI_CLASS_32 => Self::from_bytes_c32(bytes),
-				EI_CLASS_64 => Self::from_bytes_c64(bytes),
-				_ => return Err(Error::new(ErrorKind::InvalidClass)),
-			}
+			/// Field `st_info`: Type and binding.
+			pub st_info: u8,
+
+			/// Field `st_other`: Other (currently not used).
+			pub st_other: u8,
+
+			/// Field `st_shndx`: Index of the SectionHeader.
+			pub st_shndx: u16,
 		}
 
-		fn from_bytes_c32(bytes: &'a [u8]) -> Result<Self> {
-			let header = Header32::from_bytes(bytes)?;
-			assert_eq!(header.e_ident.ei_class(), EI_CLASS_32);
-			let endianness = header.e_ident.ei_data();
+		impl Symbol {
+			/// Parses the symbol and returns it together with the remaining
+			/// bytes following it.
+			#[allow(
+				unused_assignments,
+				clippy::mixed_read_write_in_expression
+			)]
+			pub fn from_bytes_consuming(
+				endianness: Endianness,
+				mut bytes: &[u8],
+			) -> Result<(Self, &[u8])> {
+				use crate::util::consume;
 
-			// ProgramHeader
-			let pheaders = {
+				if bytes.len() < core::mem::size_of::<Self>() {
+					return Err(crate::error::Error::new(
+						crate::error::ErrorKind::InsufficantSize,
+					));
+				}
+
+				let symbol = Self {
+					st_name: consume!(bytes, endianness => u32),
+					st_value: consume!(bytes, endianness => u32),
+					st_size: consume!(bytes, endianness => u32),
+					st_info: consume!(bytes, endianness => u8),
+					st_other: consume!(bytes, endianness => u8),
+					st_shndx: consume!(bytes, endianness => u16),
+				};
+
+				Ok((symbol, bytes))
+			}
+
+			/// Returns the on-disk representation of the symbol with the
+			/// given `endianness`.
+			#[allow(unused_assignments)]
+			pub fn to_bytes(
+				&self,
+				endianness: Endianness,
+			) -> [u8; SYMENTSIZE as usize] {
+				use crate::util::produce;
+
+				let mut buf = [0u8; SYMENTSIZE as usize];
+				let mut bytes = &mut buf[..];
+
+				produce!(bytes, endianness => self.st_name);
+				produce!(bytes, endianness => self.st_value);
+				produce!(bytes, endianness => self.st_size);
+				produce!(bytes, endianness => self.st_info);
+				produce!(bytes, endianness => self.st_other);
+				produce!(bytes, endianness => self.st_shndx);
+
+				buf
+			}
+		}
+
+		symbol_table!();
+	}
+
+	pub mod elf64 {
+		use crate::error::Result;
+		use crate::header::consts::ident::data::Endianness;
+
+		/// Size of an on-disk 64-bit symbol in bytes.
+		pub const SYMENTSIZE: u16 = 24;
+
+		#[repr(C)]
+		#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+		#[cfg_attr(
+			feature = "serde",
+			derive(serde::Serialize, serde::Deserialize)
+		)]
+		pub struct Symbol {
+			/// Field `st_name`: Name of the symbol.
+			pub st_name: u32,
+
+			/// Field `st_info`: Type and binding.
+			pub st_info: u8,
+
+			/// Field `st_other`: Other (currently not used).
+			pub st_other: u8,
+
+			/// Field `st_shndx`: Index of the SectionHeader.
+			pub st_shndx: u16,
+
+			/// Field `st_value`: Value of the symbol.
+			pub st_value: u64,
+
+			/// Field `st_size`: Size of the symbol.
+			pub st_size: u64,
+		}
+
+		impl Symbol {
+			/// Parses the symbol and returns it together with the remaining
+			/// bytes following it.
+			#[allow(
+				unused_assignments,
+				clippy::mixed_read_write_in_expression
+			)]
+			pub fn from_bytes_consuming(
+				endianness: Endianness,
+				mut bytes: &[u8],
+			) -> Result<(Self, &[u8])> {
+				use crate::util::consume;
+
+				if bytes.len() < core::mem::size_of::<Self>() {
+					return Err(crate::error::Error::new(
+						crate::error::ErrorKind::InsufficantSize,
+					));
+				}
+
+				let symbol = Self {
+					st_name: consume!(bytes, endianness => u32),
+					st_info: consume!(bytes, endianness => u8),
+					st_other: consume!(bytes, endianness => u8),
+					st_shndx: consume!(bytes, endianness => u16),
+					st_value: consume!(bytes, endianness => u64),
+					st_size: consume!(bytes, endianness => u64),
+				};
+
+				Ok((symbol, bytes))
+			}
+
+			/// Returns the on-disk representation of the symbol with the
+			/// given `endianness`.
+			#[allow(unused_assignments)]
+			pub fn to_bytes(
+				&self,
+				endianness: Endianness,
+			) -> [u8; SYMENTSIZE as usize] {
+				use crate::util::produce;
+
+				let mut buf = [0u8; SYMENTSIZE as usize];
+				let mut bytes = &mut buf[..];
+
+				produce!(bytes, endianness => self.st_name);
+				produce!(bytes, endianness => self.st_info);
+				produce!(bytes, endianness => self.st_other);
+				produce!(bytes, endianness => self.st_shndx);
+				produce!(bytes, endianness => self.st_value);
+				produce!(bytes, endianness => self.st_size);
+
+				buf
+			}
+		}
+
+		/// Widens a 32-bit symbol.
+		impl From<super::elf32::Symbol> for Symbol {
+			fn from(value: super::elf32::Symbol) -> Self {
+				Self {
+					st_name: value.st_name,
+					st_info: value.st_info,
+					st_other: value.st_other,
+					st_shndx: value.st_shndx,
+					st_value: value.st_value as u64,
+					st_size: value.st_size as u64,
+				}
+			}
+		}
+
+		symbol_table!();
+	}
+
+	#[cfg(test)]
+	mod tests {
+		#[test]
+		fn symbol_size() {
+			// On-disk sizes of a symbol table entry
+			assert_eq!(core::mem::size_of::<super::elf32::Symbol>(), 16);
+			assert_eq!(core::mem::size_of::<super::elf64::Symbol>(), 24);
+			assert_eq!(super::elf32::SYMENTSIZE, 16);
+			assert_eq!(super::elf64::SYMENTSIZE, 24);
+		}
+
+		#[test]
+		fn section_kind() {
+			use super::elf64::Symbol;
+			use super::SectionKind;
+			use crate::section_header::consts::shn::{
+				shn_as_str, SHN_ABS, SHN_COMMON, SHN_UNDEF, SHN_XINDEX,
+			};
+
+			let kind = |st_shndx| {
+				Symbol { st_shndx, ..Default::default() }.section_kind()
+			};
+
+			assert_eq!(kind(SHN_UNDEF), SectionKind::Undefined);
+			assert_eq!(kind(SHN_ABS), SectionKind::Absolute);
+			assert_eq!(kind(SHN_COMMON), SectionKind::Common);
+			assert_eq!(kind(SHN_XINDEX), SectionKind::Extended);
+			assert_eq!(kind(0xff01), SectionKind::Reserved(0xff01));
+			assert_eq!(kind(1), SectionKind::Index(1));
+			assert_eq!(kind(0xfeff), SectionKind::Index(0xfeff));
+
+			assert_eq!(shn_as_str(SHN_ABS), "SHN_ABS");
+			assert_eq!(shn_as_str(0xff01), "RESERVED: Processor specific");
+		}
+
+		#[test]
+		fn symbol_to_bytes_round_trip() {
+			use super::{elf32, elf64};
+			use crate::header::consts::ident::data::Endianness;
+
+			let symbol32 = elf32::Symbol {
+				st_name: 0x0102_0304,
+				st_value: 0x8000_0000,
+				st_size: 0x10,
+				st_info: 0x12,
+				st_other: 0x02,
+				st_shndx: 0x0506,
+			};
+			let symbol64 = elf64::Symbol::from(symbol32);
+
+			for endianness in [Endianness::Little, Endianness::Big] {
+				let bytes = symbol32.to_bytes(endianness);
+				assert_eq!(
+					elf32::Symbol::from_bytes(endianness, &bytes).unwrap(),
+					symbol32
+				);
+
+				let bytes = symbol64.to_bytes(endianness);
+				assert_eq!(
+					elf64::Symbol::from_bytes(endianness, &bytes).unwrap(),
+					symbol64
+				);
+			}
+
+			// On-disk field order: `st_info` follows `st_name` for 64-bit
+			let bytes = symbol64.to_bytes(Endianness::Little);
+			assert_eq!(bytes[..6], [0x04, 0x03, 0x02, 0x01, 0x12, 0x02]);
+			let bytes = symbol32.to_bytes(Endianness::Little);
+			assert_eq!(bytes[12..], [0x12, 0x02, 0x06, 0x05]);
+		}
+
+		#[test]
+		fn section_index() {
+			use super::elf64::{Symbol, Symtab};
+			use super::ShndxTable;
+			use crate::header::consts::ident::data::Endianness;
+			use crate::section_header::consts::shn::SHN_XINDEX;
+
+			fn symbol(st_shndx: u16) -> [u8; 24] {
+				let mut bytes = [0u8; 24];
+				bytes[6..8].copy_from_slice(&st_shndx.to_le_bytes());
+				bytes
+			}
+
+			let mut symtab = [0u8; 24 * 3];
+			symtab[24..48].copy_from_slice(&symbol(5));
+			symtab[48..72].copy_from_slice(&symbol(SHN_XINDEX));
+
+			let mut shndx = [0u8; 4 * 3];
+			shndx[8..12].copy_from_slice(&70_000_u32.to_le_bytes());
+
+			let symtab = Symtab::new(Endianness::Little, &symtab);
+			let shndx = ShndxTable::new(Endianness::Little, &shndx);
+
+			assert_eq!(
+				symtab.get_symbol(2),
+				Some(Symbol { st_shndx: SHN_XINDEX, ..Default::default() })
+			);
+			assert_eq!(symtab.section_index(1, Some(&shndx)), Some(5));
+			assert_eq!(symtab.section_index(2, Some(&shndx)), Some(70_000));
+
+			// Extended index without table
+			assert_eq!(symtab.section_index(1, None), Some(5));
+			assert_eq!(symtab.section_index(2, None), None);
+
+			assert_eq!(symtab.section_index(3, Some(&shndx)), None);
+		}
+
+		#[test]
+		fn symtab_try_new() {
+			use super::elf64::Symtab;
+			use crate::error::ErrorKind;
+			use crate::header::consts::ident::data::Endianness::Little;
+
+			let data = [0u8; 24 * 3 + 5];
+
+			let err = Symtab::try_new(Little, &data).err().unwrap();
+			assert_eq!(err.kind, ErrorKind::InvalidTableSize);
+
+			// Trailing partial entry is ignored
+			let symtab = Symtab::new(Little, &data);
+			assert_eq!(symtab.len(), 3);
+			assert!(symtab.get_symbol(2).is_some());
+			assert!(symtab.get_symbol(3).is_none());
+
+			let symtab = Symtab::try_new(Little, &data[..24 * 3]).unwrap();
+			assert_eq!(symtab.len(), 3);
+		}
+
+		#[test]
+		fn symtab_big_endian() {
+			use super::elf32::{Symbol, Symtab};
+			use crate::header::consts::ident::data::Endianness;
+
+			let symbol = Symbol {
+				st_name: 0x0102_0304,
+				st_value: 0x8000_0000,
+				st_size: 0x10,
+				st_info: 0x12,
+				st_other: 0,
+				st_shndx: 0x0506,
+			};
+
+			let mut data = [0u8; 16 * 2];
+			data[16..20].copy_from_slice(&symbol.st_name.to_be_bytes());
+			data[20..24].copy_from_slice(&symbol.st_value.to_be_bytes());
+			data[24..28].copy_from_slice(&symbol.st_size.to_be_bytes());
+			data[28] = symbol.st_info;
+			data[30..32].copy_from_slice(&symbol.st_shndx.to_be_bytes());
+
+			let symtab = Symtab::new(Endianness::Big, &data);
+			assert_eq!(symtab.len(), 2);
+			assert_eq!(symtab.get_symbol(1), Some(symbol));
+
+			// Decoding with the wrong byte order swaps the fields
+			let symtab = Symtab::new(Endianness::Little, &data);
+			assert_eq!(symtab.get_symbol(1).unwrap().st_name, 0x0403_0201);
+		}
+	}
+}
+
+/// Parsing of attribute sections (e.g. `.riscv.attributes`).
+///
+/// The section starts with a format version followed by subsections for each
+/// vendor. Each subsection contains sub-subsections which group attributes by
+/// their scope (only attributes of the whole file are supported).
+///
+/// # References
+/// - <https://github.com/riscv-non-isa/riscv-elf-psabi-doc/blob/master/riscv-elf.adoc#risc-v-specific-attributes>
+pub mod attributes {
+	pub mod consts {
+		/// Version of the attributes format (first byte of the section).
+		pub const FORMAT_VERSION: u8 = b'A';
+
+		/// Sub-subsection tag: The attributes apply to the whole file.
+		pub const TAG_FILE: u8 = 1;
+
+		/// Vendor name of the RISC-V attributes.
+		pub const VENDOR_RISCV: &[u8] = b"riscv";
+
+		pub mod riscv {
+			crate::util::def_consts! {
+				tag : u64 : tag_as_str => {
+					/// Stack alignment in bytes.
+					TAG_RISCV_STACK_ALIGN : "Tag_RISCV_stack_align" = 4,
+
+					/// Target ISA (e.g. `rv64i2p0_m2p0`).
+					TAG_RISCV_ARCH : "Tag_RISCV_arch" = 5,
+
+					/// Whether unaligned memory accesses are allowed.
+					TAG_RISCV_UNALIGNED_ACCESS : "Tag_RISCV_unaligned_access" = 6,
+
+					/// Major version of the privileged specification.
+					TAG_RISCV_PRIV_SPEC : "Tag_RISCV_priv_spec" = 8,
+
+					/// Minor version of the privileged specification.
+					TAG_RISCV_PRIV_SPEC_MINOR : "Tag_RISCV_priv_spec_minor" = 10,
+
+					/// Revision of the privileged specification.
+					TAG_RISCV_PRIV_SPEC_REVISION : "Tag_RISCV_priv_spec_revision" = 12,
+				}
+			}
+		}
+	}
+
+	use self::consts::riscv::TAG_RISCV_ARCH;
+	use self::consts::{FORMAT_VERSION, TAG_FILE, VENDOR_RISCV};
+	use crate::error::{Error, ErrorKind, Result};
+	use crate::header::consts::ident::data::Endianness;
+
+	/// Value of an attribute.
+	///
+	/// Attributes with an even tag hold an integer (`ULEB128`), those with an
+	/// odd tag a null-terminated string.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum Value<'a> {
+		Int(u64),
+		Str(&'a [u8]),
+	}
+
+	/// Attribute section (e.g. `SHT_RISCV_ATTRIBUTES`).
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct Attributes<'a> {
+		endianness: Endianness,
+		data: &'a [u8],
+	}
+
+	impl<'a> Attributes<'a> {
+		/// Creates the attributes from the data of the section, failing if
+		/// the format version is not [`FORMAT_VERSION`].
+		pub fn new(endianness: Endianness, data: &'a [u8]) -> Result<Self> {
+			match data.split_first() {
+				Some((&FORMAT_VERSION, data)) => Ok(Self { endianness, data }),
+				Some(_) => Err(Error::new(ErrorKind::MalformedHeader)),
+				None => Err(Error::new(ErrorKind::InsufficantSize)),
+			}
+		}
+
+		/// Returns the subsections of all vendors.
+		///
+		/// Iteration stops at the first malformed subsection.
+		pub fn subsections(&self) -> impl Iterator<Item = Subsection<'a>> {
+			let endianness = self.endianness;
+			let mut data = self.data;
+
+			core::iter::from_fn(move || {
+				let mut content = split_sized(&mut data, endianness, 0)?;
+				let vendor = split_ntbs(&mut content)?;
+
+				Some(Subsection { endianness, vendor, data: content })
+			})
+		}
+
+		/// Returns the target ISA (`Tag_RISCV_arch`) of the `riscv`
+		/// subsection.
+		pub fn riscv_arch(&self) -> Option<&'a str> {
+			let riscv = self
+				.subsections()
+				.find(|subsection| subsection.vendor == VENDOR_RISCV)?;
+
+			match riscv.get(TAG_RISCV_ARCH)? {
+				Value::Str(arch) => core::str::from_utf8(arch).ok(),
+				Value::Int(_) => None,
+			}
+		}
+	}
+
+	/// Attributes of a single vendor.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct Subsection<'a> {
+		endianness: Endianness,
+		pub vendor: &'a [u8],
+		data: &'a [u8],
+	}
+
+	impl<'a> Subsection<'a> {
+		/// Returns the attributes (`tag`, `value`) which apply to the whole
+		/// file ([`TAG_FILE`]).
+		///
+		/// Iteration stops at the first malformed attribute.
+		pub fn attributes(&self) -> impl Iterator<Item = (u64, Value<'a>)> {
+			let endianness = self.endianness;
+			let mut data = self.data;
+			let mut attributes: &[u8] = &[];
+
+			core::iter::from_fn(move || {
+				// Skip to the next file scoped sub-subsection
+				while attributes.is_empty() {
+					let tag = *data.first()?;
+					let content = split_sized(&mut data, endianness, 1)?;
+
+					if tag == TAG_FILE {
+						attributes = content;
+					}
+				}
+
+				let tag = split_uleb128(&mut attributes)?;
+				let value = if tag % 2 == 0 {
+					Value::Int(split_uleb128(&mut attributes)?)
+				} else {
+					Value::Str(split_ntbs(&mut attributes)?)
+				};
+
+				Some((tag, value))
+			})
+		}
+
+		/// Returns the value of the file attribute `tag`.
+		pub fn get(&self, tag: u64) -> Option<Value<'a>> {
+			self.attributes()
+				.find(|(attribute, _)| *attribute == tag)
+				.map(|(_, value)| value)
+		}
+	}
+
+	/// Splits off a block whose size (`u32`, counting from the start of the
+	/// block) is stored after `header` bytes and returns its content.
+	#[allow(unused_assignments)]
+	fn split_sized<'a>(
+		data: &mut &'a [u8],
+		endianness: Endianness,
+		header: usize,
+	) -> Option<&'a [u8]> {
+		use crate::util::consume;
+
+		let start = header + 4;
+		let mut bytes = data.get(header..start)?;
+		let size = consume!(bytes, endianness => u32) as usize;
+		let content = data.get(start..size)?;
+
+		*data = data.get(size..)?;
+		Some(content)
+	}
+
+	/// Splits off a null-terminated string (without the terminator).
+	fn split_ntbs<'a>(data: &mut &'a [u8]) -> Option<&'a [u8]> {
+		let end = data.iter().position(|&b| b == b'\0')?;
+		let (string, rest) = data.split_at(end);
+
+		*data = rest.get(1..)?;
+		Some(string)
+	}
+
+	/// Splits off an unsigned `LEB128` encoded integer.
+	fn split_uleb128(data: &mut &[u8]) -> Option<u64> {
+		let mut value = 0_u64;
+
+		for (idx, &byte) in data.iter().enumerate() {
+			let shift = idx as u32 * 7;
+
+			if shift >= u64::BITS {
+				return None;
+			}
+
+			value |= ((byte & 0x7f) as u64) << shift;
+
+			if byte & 0x80 == 0 {
+				*data = data.get(idx + 1..)?;
+				return Some(value);
+			}
+		}
+
+		None
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::consts::riscv::*;
+		use super::*;
+		use crate::header::consts::ident::data::Endianness::Little;
+
+		/// `.riscv.attributes` of `rv64ui-p-add`.
+		const RV64UI_P_ADD: &[u8] = b"A1\0\0\0riscv\0\x01'\0\0\0\x05\
+			rv64i2p0_m2p0_a2p0_f2p0_d2p0\0\x08\x01\x0a\x0b";
+
+		#[test]
+		fn riscv_attributes() {
+			let attributes = Attributes::new(Little, RV64UI_P_ADD).unwrap();
+
+			let mut subsections = attributes.subsections();
+			let riscv = subsections.next().unwrap();
+			assert_eq!(riscv.vendor, b"riscv");
+			assert_eq!(subsections.next(), None);
+
+			assert!(riscv.attributes().eq([
+				(TAG_RISCV_ARCH, Value::Str(b"rv64i2p0_m2p0_a2p0_f2p0_d2p0")),
+				(TAG_RISCV_PRIV_SPEC, Value::Int(1)),
+				(TAG_RISCV_PRIV_SPEC_MINOR, Value::Int(11)),
+			]));
+			assert_eq!(
+				riscv.get(TAG_RISCV_PRIV_SPEC_MINOR),
+				Some(Value::Int(11))
+			);
+			assert_eq!(riscv.get(TAG_RISCV_STACK_ALIGN), None);
+			assert_eq!(
+				attributes.riscv_arch(),
+				Some("rv64i2p0_m2p0_a2p0_f2p0_d2p0")
+			);
+			assert_eq!(tag_as_str(TAG_RISCV_PRIV_SPEC), "Tag_RISCV_priv_spec");
+
+			// Truncated
+			let attributes =
+				Attributes::new(Little, RV64UI_P_ADD.split_at(20).0).unwrap();
+			assert_eq!(attributes.subsections().count(), 0);
+
+			assert_eq!(
+				Attributes::new(Little, b"B").err().unwrap().kind,
+				ErrorKind::MalformedHeader
+			);
+		}
+
+		#[test]
+		fn uleb128() {
+			let mut data: &[u8] = &[0xe5, 0x8e, 0x26, 0xff];
+			assert_eq!(split_uleb128(&mut data), Some(624_485));
+			assert_eq!(data, [0xff]);
+
+			// Unterminated
+			assert_eq!(split_uleb128(&mut data), None);
+		}
+	}
+}
+
+pub mod hash {
+	use crate::error::{Error, ErrorKind, Result};
+	use crate::header::consts::ident::class::Class;
+	use crate::header::consts::ident::data::Endianness;
+
+	/// Hash function of the sysv hash table (`SHT_HASH`).
+	pub fn sysv_hash(name: &[u8]) -> u32 {
+		name.iter().fold(0_u32, |hash, &b| {
+			let hash = (hash << 4).wrapping_add(b as u32);
+			let high = hash & 0xf000_0000;
+
+			(hash ^ (high >> 24)) & !high
+		})
+	}
+
+	/// Hash function of the GNU hash table (`SHT_GNU_HASH`).
+	pub fn gnu_hash(name: &[u8]) -> u32 {
+		name.iter().fold(5381_u32, |hash, &b| {
+			hash.wrapping_mul(33).wrapping_add(b as u32)
+		})
+	}
+
+	/// Sysv hash table (`SHT_HASH`).
+	///
+	/// Consists of `nbucket` and `nchain` followed by the bucket and chain
+	/// arrays (all `u32`). Both arrays hold indices into the associated
+	/// symbol table (`sh_link`), with `0` (`STN_UNDEF`) terminating a chain.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct SysvHash<'a> {
+		endianness: Endianness,
+		buckets: &'a [u8],
+		chains: &'a [u8],
+	}
+
+	impl<'a> SysvHash<'a> {
+		/// Creates the hash table from the data of the section, failing if
+		/// `data` is too small to hold the arrays.
+		pub fn new(endianness: Endianness, data: &'a [u8]) -> Result<Self> {
+			let nbucket = get_u32(data, endianness, 0)
+				.ok_or(Error::new(ErrorKind::InsufficantSize))?;
+			let nchain = get_u32(data, endianness, 1)
+				.ok_or(Error::new(ErrorKind::InsufficantSize))?;
+
+			let mut data = core::ops::Index::index(data, 8..);
+			let buckets = split_words(&mut data, nbucket as usize, 4)?;
+			let chains = split_words(&mut data, nchain as usize, 4)?;
+
+			Ok(Self { endianness, buckets, chains })
+		}
+
+		/// Returns the number of buckets (`nbucket`).
+		pub fn nbucket(&self) -> usize {
+			self.buckets.len() / 4
+		}
+
+		/// Returns the number of chain entries (`nchain`), which equals the
+		/// number of symbols in the associated symbol table.
+		pub fn nchain(&self) -> usize {
+			self.chains.len() / 4
+		}
+
+		/// Returns the first symbol index of the bucket at `index`.
+		pub fn bucket(&self, index: usize) -> Option<u32> {
+			get_u32(self.buckets, self.endianness, index)
+		}
+
+		/// Returns the next symbol index in the chain of the symbol at
+		/// `index`.
+		pub fn chain(&self, index: usize) -> Option<u32> {
+			get_u32(self.chains, self.endianness, index)
+		}
+
+		/// Looks up the symbol `name` and returns its index.
+		///
+		/// `is_match` is called with the index of each candidate and has to
+		/// compare the name of the symbol (e.g. via `.dynsym` and `.dynstr`).
+		pub fn find(
+			&self,
+			name: &[u8],
+			mut is_match: impl FnMut(u32) -> bool,
+		) -> Option<u32> {
+			if self.nbucket() == 0 {
+				return None;
+			}
+
+			let hash = sysv_hash(name) as usize;
+			let mut index = self.bucket(hash % self.nbucket())?;
+
+			// Bounded by the number of symbols, in case of a cyclic chain
+			for _ in 0..self.nchain() {
+				if index == 0 {
+					break;
+				}
+
+				if is_match(index) {
+					return Some(index);
+				}
+
+				index = self.chain(index as usize)?;
+			}
+
+			None
+		}
+	}
+
+	/// GNU hash table (`SHT_GNU_HASH`).
+	///
+	/// Consists of `nbuckets`, `symoffset`, `bloom_size` and `bloom_shift`
+	/// (all `u32`), followed by the bloom filter (words of the size of the
+	/// class), the buckets (`u32`) and the hash values of the symbols
+	/// starting at `symoffset` (`u32`).
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct GnuHash<'a> {
+		endianness: Endianness,
+		class: Class,
+		symoffset: u32,
+		bloom_shift: u32,
+		bloom: &'a [u8],
+		buckets: &'a [u8],
+		chains: &'a [u8],
+	}
+
+	impl<'a> GnuHash<'a> {
+		/// Creates the hash table from the data of the section, failing if
+		/// `data` is too small to hold the bloom filter and the buckets.
+		pub fn new(
+			endianness: Endianness,
+			class: Class,
+			data: &'a [u8],
+		) -> Result<Self> {
+			let mut header = [0_u32; 4];
+			for (index, value) in header.iter_mut().enumerate() {
+				*value = get_u32(data, endianness, index)
+					.ok_or(Error::new(ErrorKind::InsufficantSize))?;
+			}
+			let [nbuckets, symoffset, bloom_size, bloom_shift] = header;
+
+			let mut data = core::ops::Index::index(data, 16..);
+			let bloom =
+				split_words(&mut data, bloom_size as usize, word_size(class))?;
+			let buckets = split_words(&mut data, nbuckets as usize, 4)?;
+
+			Ok(Self {
+				endianness,
+				class,
+				symoffset,
+				bloom_shift,
+				bloom,
+				buckets,
+				chains: data,
+			})
+		}
+
+		/// Returns the number of buckets (`nbuckets`).
+		pub fn nbuckets(&self) -> usize {
+			self.buckets.len() / 4
+		}
+
+		/// Returns the index of the first symbol accessible through the
+		/// table (`symoffset`).
+		pub fn symoffset(&self) -> u32 {
+			self.symoffset
+		}
+
+		/// Returns the number of words of the bloom filter (`bloom_size`).
+		pub fn bloom_size(&self) -> usize {
+			self.bloom.len() / word_size(self.class)
+		}
+
+		/// Returns the shift of the second bloom filter bit (`bloom_shift`).
+		pub fn bloom_shift(&self) -> u32 {
+			self.bloom_shift
+		}
+
+		/// Returns the bloom filter word at `index`.
+		pub fn bloom(&self, index: usize) -> Option<u64> {
+			match self.class {
+				Class::Elf32 => get_u32(self.bloom, self.endianness, index)
+					.map(|word| word as u64),
+				Class::Elf64 => get_u64(self.bloom, self.endianness, index),
+			}
+		}
+
+		/// Returns the first symbol index of the bucket at `index` (`0` if
+		/// the bucket is empty).
+		pub fn bucket(&self, index: usize) -> Option<u32> {
+			get_u32(self.buckets, self.endianness, index)
+		}
+
+		/// Returns the hash value of the symbol at `index` (the lowest bit
+		/// marks the end of a chain), or `None` if the symbol is below
+		/// `symoffset` or out of range.
+		pub fn chain(&self, index: u32) -> Option<u32> {
+			let index = index.checked_sub(self.symoffset)?;
+			get_u32(self.chains, self.endianness, index as usize)
+		}
+
+		/// Checks the bloom filter for `hash` (see [`gnu_hash`]).
+		///
+		/// A `false` result means no symbol with this hash is in the table.
+		pub fn may_contain(&self, hash: u32) -> bool {
+			let bits = word_size(self.class) as u32 * 8;
+			let bloom_size = self.bloom_size();
+
+			if bloom_size == 0 {
+				return false;
+			}
+
+			let word = (hash / bits) as usize % bloom_size;
+			let mask = (1_u64 << (hash % bits))
+				| (1_u64 << (hash.wrapping_shr(self.bloom_shift) % bits));
+
+			self.bloom(word).is_some_and(|word| word & mask == mask)
+		}
+
+		/// Looks up the symbol `name` and returns its index.
+		///
+		/// `is_match` is called with the index of each candidate whose hash
+		/// matches and has to compare the name of the symbol (e.g. via
+		/// `.dynsym` and `.dynstr`).
+		pub fn find(
+			&self,
+			name: &[u8],
+			mut is_match: impl FnMut(u32) -> bool,
+		) -> Option<u32> {
+			let hash = gnu_hash(name);
+
+			if self.nbuckets() == 0 || !self.may_contain(hash) {
+				return None;
+			}
+
+			let mut index = self.bucket(hash as usize % self.nbuckets())?;
+
+			if index < self.symoffset {
+				return None;
+			}
+
+			loop {
+				let chain = self.chain(index)?;
+
+				if chain | 1 == hash | 1 && is_match(index) {
+					return Some(index);
+				}
+
+				if chain & 1 != 0 {
+					return None;
+				}
+
+				index = index.checked_add(1)?;
+			}
+		}
+	}
+
+	/// Size of a bloom filter word.
+	fn word_size(class: Class) -> usize {
+		match class {
+			Class::Elf32 => 4,
+			Class::Elf64 => 8,
+		}
+	}
+
+	/// Splits off an array of `len` words of `size` bytes.
+	fn split_words<'a>(
+		data: &mut &'a [u8],
+		len: usize,
+		size: usize,
+	) -> Result<&'a [u8]> {
+		let bytes = len
+			.checked_mul(size)
+			.filter(|&bytes| bytes <= data.len())
+			.ok_or(Error::new(ErrorKind::InsufficantSize))?;
+		let (words, rest) = data.split_at(bytes);
+
+		*data = rest;
+		Ok(words)
+	}
+
+	#[allow(unused_assignments)]
+	fn get_u32(
+		data: &[u8],
+		endianness: Endianness,
+		index: usize,
+	) -> Option<u32> {
+		use crate::util::consume;
+
+		let mut bytes = data.get(index.checked_mul(4)?..)?.get(..4)?;
+		Some(consume!(bytes, endianness => u32))
+	}
+
+	#[allow(unused_assignments)]
+	fn get_u64(
+		data: &[u8],
+		endianness: Endianness,
+		index: usize,
+	) -> Option<u64> {
+		use crate::util::consume;
+
+		let mut bytes = data.get(index.checked_mul(8)?..)?.get(..8)?;
+		Some(consume!(bytes, endianness => u64))
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::header::consts::ident::data::Endianness::Little;
+
+		const HELLO_PIE: &[u8] =
+			include_bytes!("../../../resources/elf/hello-pie");
+
+		/// Names of the symbols in `.dynsym` of `hello-pie`.
+		const DYNSYM: &[&[u8]] = &[
+			b"",
+			b"__libc_start_main",
+			b"_ITM_deregisterTMCloneTable",
+			b"printf",
+			b"__gmon_start__",
+			b"_ITM_registerTMCloneTable",
+			b"__cxa_finalize",
+		];
+
+		fn matches(name: &[u8]) -> impl FnMut(u32) -> bool + '_ {
+			move |index| DYNSYM[index as usize] == name
+		}
+
+		#[test]
+		fn hash_functions() {
+			assert_eq!(sysv_hash(b""), 0);
+			assert_eq!(sysv_hash(b"printf"), 0x077905a6);
+			assert_eq!(gnu_hash(b""), 5381);
+			assert_eq!(gnu_hash(b"printf"), 0x156b2bb8);
+		}
+
+		#[test]
+		fn sysv() {
+			// `.hash` of `hello-pie`
+			let data = HELLO_PIE.get(0x368..0x368 + 0x30).unwrap();
+			let hash = SysvHash::new(Little, data).unwrap();
+
+			assert_eq!(hash.nbucket(), 3);
+			assert_eq!(hash.nchain(), DYNSYM.len());
+			assert_eq!(hash.bucket(0), Some(4));
+			assert_eq!(hash.bucket(3), None);
+			assert_eq!(hash.chain(6), Some(3));
+
+			for (index, &name) in DYNSYM.iter().enumerate().skip(1) {
+				assert_eq!(hash.find(name, matches(name)), Some(index as u32));
+			}
+			assert_eq!(hash.find(b"main", matches(b"main")), None);
+
+			// Truncated chain array
+			assert_eq!(
+				SysvHash::new(Little, data.get(..0x2c).unwrap())
+					.err()
+					.map(|e| e.kind),
+				Some(ErrorKind::InsufficantSize)
+			);
+		}
+
+		#[test]
+		fn gnu() {
+			// `.gnu.hash` of `hello-pie`
+			let data = HELLO_PIE.get(0x398..0x398 + 0x24).unwrap();
+			let hash = GnuHash::new(Little, Class::Elf64, data).unwrap();
+
+			assert_eq!(hash.nbuckets(), 2);
+			assert_eq!(hash.symoffset(), 6);
+			assert_eq!(hash.bloom_size(), 1);
+			assert_eq!(hash.bloom_shift(), 6);
+			assert_eq!(hash.bucket(0), Some(6));
+			assert_eq!(hash.bucket(1), Some(0));
+			assert_eq!(hash.chain(5), None);
+			assert_eq!(hash.chain(6), Some(gnu_hash(b"__cxa_finalize") | 1));
+
+			assert!(hash.may_contain(gnu_hash(b"__cxa_finalize")));
+			assert_eq!(
+				hash.find(b"__cxa_finalize", matches(b"__cxa_finalize")),
+				Some(6)
+			);
+			// Only symbols from `symoffset` on are part of the table
+			assert_eq!(hash.find(b"printf", matches(b"printf")), None);
+
+			// Truncated buckets
+			assert_eq!(
+				GnuHash::new(Little, Class::Elf64, data.get(..0x1c).unwrap())
+					.err()
+					.map(|e| e.kind),
+				Some(ErrorKind::InsufficantSize)
+			);
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+pub mod elf {
+	use core::fmt;
+	use core::ops::Range;
+
+	use crate::attributes::Attributes;
+	use crate::error::{Error, ErrorKind, Result};
+	use crate::header::consts::ident::class::{EI_CLASS_32, EI_CLASS_64};
+	use crate::header::consts::ident::index::EI_CLASS;
+	use crate::header::consts::typ::{E_TYPE_ET_DYN, E_TYPE_ET_EXEC};
+	use crate::header::elf32::Header as Header32;
+	use crate::header::elf64::Header as Header64;
+	use crate::program_header::consts::typ::{
+		P_TYPE_PT_INTERP, P_TYPE_PT_LOAD, P_TYPE_PT_TLS,
+	};
+	use crate::program_header::elf32::ProgramHeader as ProgramHeader32;
+	use crate::program_header::elf64::ProgramHeader as ProgramHeader64;
+	use crate::program_header::VirtAddr;
+	use crate::section_header::consts::shn::{SHN_UNDEF, SHN_XINDEX};
+	use crate::section_header::consts::typ::riscv::SH_TYPE_SHT_RISCV_ATTRIBUTES;
+	use crate::section_header::consts::typ::{
+		SH_TYPE_SHT_NOBITS, SH_TYPE_SHT_SYMTAB,
+	};
+	use crate::section_header::elf32::SectionHeader as SectionHeader32;
+	use crate::section_header::elf64::SectionHeader as SectionHeader64;
+	use crate::strtab::Strtab;
+	use crate::symtab::consts::typ::{ST_TYPE_STT_FUNC, ST_TYPE_STT_OBJECT};
+	use crate::symtab::elf32::Symtab as Symtab32;
+	use crate::symtab::elf64::{Symbol as Symbol64, Symtab as Symtab64};
+	use crate::symtab::SectionKind;
+
+	pub enum Elf<'a> {
+		Elf32 {
+			bytes: &'a [u8],
+			header: Header32,
+			pheaders: Vec<ProgramHeader32>,
+			sheaders: Vec<SectionHeader32>,
+		},
+		Elf64 {
+			bytes: &'a [u8],
+			header: Header64,
+			pheaders: Vec<ProgramHeader64>,
+			sheaders: Vec<SectionHeader64>,
+		},
+	}
+
+	/// Thread-local storage template of an elf (see [`Elf::tls`]).
+	///
+	/// Each thread gets a copy of the template: The initialized data
+	/// (`.tdata`) followed by zeroed memory (`.tbss`) up to `mem_size`.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct TlsTemplate<'a> {
+		/// Initialized data of the template (`p_filesz` bytes).
+		pub data: &'a [u8],
+
+		/// Total size of the template in memory (`p_memsz`).
+		pub mem_size: u64,
+
+		/// Required alignment of the template (`p_align`).
+		pub align: u64,
+	}
+
+	impl TlsTemplate<'_> {
+		/// Returns the size of the zero-initialized part (`.tbss`).
+		pub fn bss_size(&self) -> u64 {
+			self.mem_size.saturating_sub(self.data.len() as u64)
+		}
+	}
+
+	/// Program header of either class, as returned by the class independent
+	/// lookups on [`Elf`].
+	///
+	/// The accessors widen all fields to the size used by the 64-bit class.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum ProgramHeaderRef<'a> {
+		Elf32(&'a ProgramHeader32),
+		Elf64(&'a ProgramHeader64),
+	}
+
+	/// Section header of either class, as returned by the class independent
+	/// lookups on [`Elf`].
+	///
+	/// The accessors widen all fields to the size used by the 64-bit class.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum SectionHeaderRef<'a> {
+		Elf32(&'a SectionHeader32),
+		Elf64(&'a SectionHeader64),
+	}
+
+	/// Defines accessors for the fields shared by both variants of a
+	/// `*HeaderRef`.
+	macro_rules! header_ref_fields {
+		( $( $field:ident : $size:ty ),+ ) => {
+			$(
+				pub fn $field(&self) -> $size {
+					match self {
+						Self::Elf32(header) => header.$field as $size,
+						Self::Elf64(header) => header.$field as $size,
+					}
+				}
+			)+
+		};
+	}
+
+	impl<'a> ProgramHeaderRef<'a> {
+		header_ref_fields!(
+			p_type: u32,
+			p_flags: u32,
+			p_offset: u64,
+			p_vaddr: u64,
+			p_paddr: u64,
+			p_filesz: u64,
+			p_memsz: u64,
+			p_align: u64
+		);
+
+		pub fn extract_data<'b>(&self, bytes: &'b [u8]) -> &'b [u8] {
+			match self {
+				Self::Elf32(ph) => ph.extract_data(bytes),
+				Self::Elf64(ph) => ph.extract_data(bytes),
+			}
+		}
+
+		pub fn try_extract_data<'b>(
+			&self,
+			bytes: &'b [u8],
+		) -> Option<&'b [u8]> {
+			match self {
+				Self::Elf32(ph) => ph.try_extract_data(bytes),
+				Self::Elf64(ph) => ph.try_extract_data(bytes),
+			}
+		}
+
+		/// Checks if the segment is loadable (`PT_LOAD`).
+		pub fn is_load(&self) -> bool {
+			self.p_type() == P_TYPE_PT_LOAD
+		}
+	}
+
+	impl<'a> SectionHeaderRef<'a> {
+		header_ref_fields!(
+			sh_name: u32,
+			sh_type: u32,
+			sh_flags: u64,
+			sh_addr: u64,
+			sh_offset: u64,
+			sh_size: u64,
+			sh_link: u32,
+			sh_info: u32,
+			sh_addralign: u64,
+			sh_entsize: u64
+		);
+
+		pub fn extract_data<'b>(&self, bytes: &'b [u8]) -> &'b [u8] {
+			match self {
+				Self::Elf32(sh) => sh.extract_data(bytes),
+				Self::Elf64(sh) => sh.extract_data(bytes),
+			}
+		}
+
+		pub fn try_extract_data<'b>(
+			&self,
+			bytes: &'b [u8],
+		) -> Option<&'b [u8]> {
+			match self {
+				Self::Elf32(sh) => sh.try_extract_data(bytes),
+				Self::Elf64(sh) => sh.try_extract_data(bytes),
+			}
+		}
+
+		/// Returns the range of the file occupied by the section.
+		///
+		/// Returns `None` for sections which occupy no space in the file
+		/// (`SHT_NOBITS` or empty).
+		pub fn file_range(&self) -> Option<Range<u64>> {
+			if self.sh_type() == SH_TYPE_SHT_NOBITS || self.sh_size() == 0 {
+				return None;
+			}
+
+			let start = self.sh_offset();
+			Some(start..start.saturating_add(self.sh_size()))
+		}
+	}
+
+	/// Layout of the sections in the file (see [`Elf::section_layout`]).
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	pub struct SectionLayout<'a> {
+		/// All sections as `(index, header)`, sorted by `sh_offset`.
+		pub sections: Vec<(usize, SectionHeaderRef<'a>)>,
+
+		/// Pairs of section indices whose file ranges overlap.
+		pub overlaps: Vec<(usize, usize)>,
+
+		/// Ranges between the first and the last section which are not
+		/// covered by any section.
+		pub gaps: Vec<Range<u64>>,
+	}
+
+	/// Returns the bytes of a table with `count` entries of `entsize` bytes
+	/// each, starting at `offset`.
+	///
+	/// Fails with [`ErrorKind::MalformedHeader`] if `entsize` is smaller than
+	/// `min_entsize` (the size of a parsed entry) or the end of the table can
+	/// not be represented and with [`ErrorKind::InsufficantSize`] if the table
+	/// exceeds `bytes`.
+	fn table_bytes(
+		bytes: &[u8],
+		offset: impl Into<u64>,
+		count: u16,
+		entsize: u16,
+		min_entsize: u16,
+	) -> Result<&[u8]> {
+		if count == 0 {
+			return Ok(&[]);
+		}
+
+		// Entries would overlap and be read misaligned
+		if entsize < min_entsize {
+			return Err(Error::new(ErrorKind::MalformedHeader).with_message(
+				format!(
+					"Table entry size ({}) is smaller than the size of an \
+					 entry ({})",
+					entsize, min_entsize
+				),
+			));
+		}
+
+		let offset = offset.into();
+
+		let end = (count as u64)
+			.checked_mul(entsize as u64)
+			.and_then(|size| offset.checked_add(size))
+			.ok_or_else(|| Error::new(ErrorKind::MalformedHeader))?;
+
+		let (start, end) =
+			match (usize::try_from(offset), usize::try_from(end)) {
+				(Ok(start), Ok(end)) if end <= bytes.len() => (start, end),
+				_ => return Err(Error::new(ErrorKind::InsufficantSize)),
+			};
+
+		Ok(core::ops::Index::index(bytes, start..end))
+	}
+
+	/// Returns the name of `sh` from the section name string table.
+	fn section_name<'a>(
+		shstrtab: &'a [u8],
+		sh: &SectionHeaderRef<'_>,
+	) -> Option<&'a [u8]> {
+		let offset = sh.sh_name() as usize;
+
+		if offset < shstrtab.len() {
+			Strtab::new(Strtab::DEFAULT_DELIM, shstrtab).get_bytes_off(offset)
+		} else {
+			None
+		}
+	}
+
+	/// Returns the name of `symbol` in the string table `strtab` or an empty
+	/// name if it can not be resolved.
+	fn symbol_name<'a>(strtab: &'a [u8], symbol: &Symbol64) -> &'a str {
+		let offset = symbol.st_name as usize;
+
+		if offset < strtab.len() {
+			Strtab::new(Strtab::DEFAULT_DELIM, strtab)
+				.get_bytes_off(offset)
+				.and_then(|name| core::str::from_utf8(name).ok())
+				.unwrap_or("")
+		} else {
+			""
+		}
+	}
+
+	impl<'a> Elf<'a> {
+		/// Returns all loadable (`PT_LOAD`) segments as tuples of
+		/// `(target_addr, data, mem_size)`.
+		///
+		/// The `target_addr` is the virtual address (`p_vaddr`) of the
+		/// segment, which is where the code expects to be executed from (see
+		/// [`ProgramHeader64::phys_addr`] for the physical address). The
+		/// `data` only contains the bytes present in the file (`p_filesz`);
+		/// any remaining bytes up to `mem_size` (`p_memsz`) must be
+		/// zero-filled by the loader.
+		///
+		/// Segments whose data lies outside of the file are skipped (see
+		/// [`Elf::verify`]).
+		pub fn load_segments(
+			&self,
+		) -> impl Iterator<Item = (VirtAddr, &'a [u8], u64)> + '_ {
+			let (elf32, elf64) = match self {
+				Self::Elf32 { bytes, pheaders, .. } => {
+					let segments = pheaders
+						.iter()
+						.filter(|ph| ph.is_load())
+						.filter_map(|ph| {
+							Some((
+								ph.virt_addr(),
+								ph.try_extract_data(bytes)?,
+								ph.p_memsz as u64,
+							))
+						});
+
+					(Some(segments), None)
+				}
+				Self::Elf64 { bytes, pheaders, .. } => {
+					let segments = pheaders
+						.iter()
+						.filter(|ph| ph.is_load())
+						.filter_map(|ph| {
+							Some((
+								ph.virt_addr(),
+								ph.try_extract_data(bytes)?,
+								ph.p_memsz,
+							))
+						});
+
+					(None, Some(segments))
+				}
+			};
+
+			elf32.into_iter().flatten().chain(elf64.into_iter().flatten())
+		}
+
+		/// Returns the range of virtual addresses occupied by the loadable
+		/// (`PT_LOAD`) segments as `(start, end)`, with `end` being
+		/// exclusive.
+		///
+		/// This can be used to size the memory before loading the segments.
+		/// Returns `None` if there are no loadable segments.
+		pub fn memory_span(&self) -> Option<(u64, u64)> {
+			let spans: Vec<(u64, u64)> = match self {
+				Self::Elf32 { pheaders, .. } => pheaders
+					.iter()
+					.filter(|ph| ph.is_load())
+					.map(|ph| (ph.p_vaddr as u64, ph.p_memsz as u64))
+					.collect(),
+				Self::Elf64 { pheaders, .. } => pheaders
+					.iter()
+					.filter(|ph| ph.is_load())
+					.map(|ph| (ph.p_vaddr, ph.p_memsz))
+					.collect(),
+			};
+
+			spans.into_iter().fold(None, |span, (vaddr, memsz)| {
+				let end = vaddr.saturating_add(memsz);
+
+				Some(match span {
+					Some((start, max)) => (start.min(vaddr), max.max(end)),
+					None => (vaddr, end),
+				})
+			})
+		}
+
+		/// Returns `len` bytes of the file at the entry point (`e_entry`).
+		///
+		/// The entry point is mapped to the file via the loadable segment
+		/// (`PT_LOAD`) containing it. Returns `None` if there is no such
+		/// segment or the segment holds less than `len` bytes in the file
+		/// after the entry point.
+		pub fn entry_bytes(&self, len: usize) -> Option<&'a [u8]> {
+			let (data, offset) = match self {
+				Self::Elf32 { bytes, header, pheaders, .. } => {
+					let entry = header.e_entry;
+					let ph = pheaders.iter().find(|ph| {
+						ph.is_load()
+							&& entry >= ph.p_vaddr
+							&& entry - ph.p_vaddr < ph.p_memsz
+					})?;
+
+					(
+						ph.try_extract_data(bytes)?,
+						(entry - ph.p_vaddr) as usize,
+					)
+				}
+				Self::Elf64 { bytes, header, pheaders, .. } => {
+					let entry = header.e_entry;
+					let ph = pheaders.iter().find(|ph| {
+						ph.is_load()
+							&& entry >= ph.p_vaddr
+							&& entry - ph.p_vaddr < ph.p_memsz
+					})?;
+
+					(
+						ph.try_extract_data(bytes)?,
+						(entry - ph.p_vaddr) as usize,
+					)
+				}
+			};
+
+			data.get(offset..offset.checked_add(len)?)
+		}
+
+		/// Returns the file offset of the virtual address `vaddr`.
+		///
+		/// The address is mapped to the file via the loadable segment
+		/// (`PT_LOAD`) containing it. Returns `None` if there is no such
+		/// segment or the address lies in the part of the segment which is
+		/// not backed by the file (e.g. `.bss`, beyond `p_filesz`).
+		pub fn vaddr_to_offset(&self, vaddr: u64) -> Option<u64> {
+			let (vaddr_start, offset, filesz, memsz) = match self {
+				Self::Elf32 { pheaders, .. } => pheaders
+					.iter()
+					.filter(|ph| ph.is_load())
+					.map(|ph| {
+						(
+							ph.p_vaddr as u64,
+							ph.p_offset as u64,
+							ph.p_filesz as u64,
+							ph.p_memsz as u64,
+						)
+					})
+					.find(|&(start, _, _, memsz)| {
+						vaddr >= start && vaddr - start < memsz
+					})?,
+				Self::Elf64 { pheaders, .. } => pheaders
+					.iter()
+					.filter(|ph| ph.is_load())
+					.map(|ph| {
+						(ph.p_vaddr, ph.p_offset, ph.p_filesz, ph.p_memsz)
+					})
+					.find(|&(start, _, _, memsz)| {
+						vaddr >= start && vaddr - start < memsz
+					})?,
+			};
+
+			let delta = vaddr - vaddr_start;
+
+			if delta < filesz.min(memsz) {
+				offset.checked_add(delta)
+			} else {
+				None
+			}
+		}
+
+		/// Returns the RISC-V attributes (`SHT_RISCV_ATTRIBUTES`).
+		///
+		/// Returns `None` if there is no such section, its data lies outside
+		/// of the file or its format version is unknown.
+		pub fn riscv_attributes(&self) -> Option<Attributes<'a>> {
+			let (bytes, endianness) = match self {
+				Self::Elf32 { bytes, header, .. } => {
+					(*bytes, header.e_ident.endianness().ok()?)
+				}
+				Self::Elf64 { bytes, header, .. } => {
+					(*bytes, header.e_ident.endianness().ok()?)
+				}
+			};
+
+			let sh =
+				self.first_section_by_type(SH_TYPE_SHT_RISCV_ATTRIBUTES)?;
+			Attributes::new(endianness, sh.try_extract_data(bytes)?).ok()
+		}
+
+		/// Returns all symbols of the symbol table (`SHT_SYMTAB`) together
+		/// with their names.
+		///
+		/// Symbols of 32-bit elfs are widened to the 64-bit representation.
+		/// Names are empty if they can not be resolved (or are not valid
+		/// UTF-8). No symbols are returned if the tables lie outside of the
+		/// file.
+		pub fn symbols(
+			&self,
+		) -> impl Iterator<Item = (&'a str, Symbol64)> + '_ {
+			let (bytes, endianness) = match self {
+				Self::Elf32 { bytes, header, .. } => {
+					(*bytes, header.e_ident.endianness().ok())
+				}
+				Self::Elf64 { bytes, header, .. } => {
+					(*bytes, header.e_ident.endianness().ok())
+				}
+			};
+
+			let tables = endianness.and_then(|endianness| {
+				let symtab = self.first_section_by_type(SH_TYPE_SHT_SYMTAB)?;
+				let strtab = self.sections().nth(symtab.sh_link() as usize)?;
+
+				Some((
+					endianness,
+					symtab.try_extract_data(bytes)?,
+					strtab.try_extract_data(bytes)?,
+				))
+			});
+			let strtab = tables.map_or(&[][..], |(_, _, strtab)| strtab);
+
+			let (elf32, elf64) = match (self, tables) {
+				(_, None) => (None, None),
+				(Self::Elf32 { .. }, Some((endianness, data, _))) => {
+					let symtab = Symtab32::new(endianness, data);
+					let symbols = (0..symtab.len())
+						.filter_map(move |idx| symtab.get_symbol(idx))
+						.map(Symbol64::from);
+
+					(Some(symbols), None)
+				}
+				(Self::Elf64 { .. }, Some((endianness, data, _))) => {
+					let symtab = Symtab64::new(endianness, data);
+					let symbols = (0..symtab.len())
+						.filter_map(move |idx| symtab.get_symbol(idx));
+
+					(None, Some(symbols))
+				}
+			};
+
+			elf32
+				.into_iter()
+				.flatten()
+				.chain(elf64.into_iter().flatten())
+				.map(move |symbol| (symbol_name(strtab, &symbol), symbol))
+		}
+
+		/// Returns the function or object symbol of the symbol table
+		/// (`SHT_SYMTAB`) which contains `addr`, together with its name and
+		/// the offset of `addr` into the symbol.
+		///
+		/// Symbols of 32-bit elfs are widened to the 64-bit representation.
+		pub fn symbol_at_address(
+			&self,
+			addr: u64,
+		) -> Option<(&'a str, Symbol64, u64)> {
+			self.symbols()
+				.find(|(_, symbol)| {
+					matches!(
+						symbol.st_type(),
+						ST_TYPE_STT_FUNC | ST_TYPE_STT_OBJECT
+					) && addr >= symbol.st_value
+						&& addr - symbol.st_value < symbol.st_size
+				})
+				.map(|(name, symbol)| (name, symbol, addr - symbol.st_value))
+		}
+
+		/// Returns the `st_size` bytes in the file referenced by `symbol`
+		/// (e.g. the initial value of a global variable).
+		///
+		/// Returns `None` for symbols without a section (undefined or with
+		/// a reserved index like `SHN_ABS`), symbols in sections without
+		/// data in the file (`SHT_NOBITS`) or if the data exceeds the
+		/// section.
+		pub fn symbol_data(&self, symbol: &Symbol64) -> Option<&'a [u8]> {
+			let bytes = match self {
+				Self::Elf32 { bytes, .. } => *bytes,
+				Self::Elf64 { bytes, .. } => *bytes,
+			};
+
+			let SectionKind::Index(index) = symbol.section_kind() else {
+				return None;
+			};
+
+			let sh = self.sections().nth(index as usize)?;
+			if sh.sh_type() == SH_TYPE_SHT_NOBITS {
+				return None;
+			}
+
+			// Relocatable files have `sh_addr` of zero and section relative
+			// values
+			let offset = symbol.st_value.checked_sub(sh.sh_addr())?;
+			let end = offset.checked_add(symbol.st_size)?;
+			if end > sh.sh_size() {
+				return None;
+			}
+
+			let start =
+				usize::try_from(sh.sh_offset().checked_add(offset)?).ok()?;
+			let end =
+				start.checked_add(usize::try_from(symbol.st_size).ok()?)?;
+
+			bytes.get(start..end)
+		}
+
+		/// Returns the path of the program interpreter (`PT_INTERP`), e.g.
+		/// the dynamic linker.
+		///
+		/// Returns `None` if there is no such segment, its data lies outside
+		/// of the file or the path is not valid UTF-8.
+		pub fn interpreter(&self) -> Option<&'a str> {
+			let data = match self {
+				Self::Elf32 { bytes, pheaders, .. } => pheaders
+					.iter()
+					.find(|ph| ph.p_type == P_TYPE_PT_INTERP)?
+					.try_extract_data(bytes)?,
+				Self::Elf64 { bytes, pheaders, .. } => pheaders
+					.iter()
+					.find(|ph| ph.p_type == P_TYPE_PT_INTERP)?
+					.try_extract_data(bytes)?,
+			};
+
+			// The path is NUL terminated
+			let data = data.strip_suffix(&[0]).unwrap_or(data);
+
+			core::str::from_utf8(data).ok()
+		}
+
+		/// Checks if the elf is a position-independent executable, i.e. of
+		/// type `ET_DYN` and requests a program interpreter (`PT_INTERP`).
+		pub fn is_pie(&self) -> bool {
+			self.e_type() == E_TYPE_ET_DYN && self.has_interpreter()
+		}
+
+		/// Checks if the elf is a shared object, i.e. of type `ET_DYN`
+		/// without a program interpreter (`PT_INTERP`).
+		pub fn is_shared_object(&self) -> bool {
+			self.e_type() == E_TYPE_ET_DYN && !self.has_interpreter()
+		}
+
+		/// Checks if the elf can be run as a program, i.e. is of type
+		/// `ET_EXEC` or a position-independent executable (see
+		/// [`Elf::is_pie`]).
+		pub fn is_executable(&self) -> bool {
+			self.e_type() == E_TYPE_ET_EXEC || self.is_pie()
+		}
+
+		fn e_type(&self) -> u16 {
+			match self {
+				Self::Elf32 { header, .. } => header.e_type,
+				Self::Elf64 { header, .. } => header.e_type,
+			}
+		}
+
+		fn has_interpreter(&self) -> bool {
+			match self {
+				Self::Elf32 { pheaders, .. } => {
+					pheaders.iter().any(|ph| ph.p_type == P_TYPE_PT_INTERP)
+				}
+				Self::Elf64 { pheaders, .. } => {
+					pheaders.iter().any(|ph| ph.p_type == P_TYPE_PT_INTERP)
+				}
+			}
+		}
+
+		/// Returns the thread-local storage template (`PT_TLS`).
+		///
+		/// Returns `None` if there is no such segment or its data lies
+		/// outside of the file.
+		pub fn tls(&self) -> Option<TlsTemplate<'a>> {
+			let (data, mem_size, align) = match self {
+				Self::Elf32 { bytes, pheaders, .. } => {
+					let ph = pheaders
+						.iter()
+						.find(|ph| ph.p_type == P_TYPE_PT_TLS)?;
+
+					(
+						ph.try_extract_data(bytes)?,
+						u64::from(ph.p_memsz),
+						u64::from(ph.p_align),
+					)
+				}
+				Self::Elf64 { bytes, pheaders, .. } => {
+					let ph = pheaders
+						.iter()
+						.find(|ph| ph.p_type == P_TYPE_PT_TLS)?;
+
+					(ph.try_extract_data(bytes)?, ph.p_memsz, ph.p_align)
+				}
+			};
+
+			Some(TlsTemplate { data, mem_size, align })
+		}
+
+		/// Returns all section headers in the order of the section header
+		/// table.
+		pub fn sections(
+			&self,
+		) -> impl Iterator<Item = SectionHeaderRef<'_>> + '_ {
+			let (elf32, elf64) = match self {
+				Self::Elf32 { sheaders, .. } => {
+					(Some(sheaders.iter().map(SectionHeaderRef::Elf32)), None)
+				}
+				Self::Elf64 { sheaders, .. } => {
+					(None, Some(sheaders.iter().map(SectionHeaderRef::Elf64)))
+				}
+			};
+
+			elf32.into_iter().flatten().chain(elf64.into_iter().flatten())
+		}
+
+		/// Returns the number of parsed program headers.
+		pub fn program_header_count(&self) -> usize {
+			match self {
+				Self::Elf32 { pheaders, .. } => pheaders.len(),
+				Self::Elf64 { pheaders, .. } => pheaders.len(),
+			}
+		}
+
+		/// Returns the number of parsed section headers.
+		pub fn section_header_count(&self) -> usize {
+			match self {
+				Self::Elf32 { sheaders, .. } => sheaders.len(),
+				Self::Elf64 { sheaders, .. } => sheaders.len(),
+			}
+		}
+
+		/// Returns the program header at `index` of the program header
+		/// table.
+		pub fn program_header(
+			&self,
+			index: usize,
+		) -> Option<ProgramHeaderRef<'_>> {
+			match self {
+				Self::Elf32 { pheaders, .. } => {
+					pheaders.get(index).map(ProgramHeaderRef::Elf32)
+				}
+				Self::Elf64 { pheaders, .. } => {
+					pheaders.get(index).map(ProgramHeaderRef::Elf64)
+				}
+			}
+		}
+
+		/// Returns the section header at `index` of the section header
+		/// table.
+		pub fn section_header(
+			&self,
+			index: usize,
+		) -> Option<SectionHeaderRef<'_>> {
+			match self {
+				Self::Elf32 { sheaders, .. } => {
+					sheaders.get(index).map(SectionHeaderRef::Elf32)
+				}
+				Self::Elf64 { sheaders, .. } => {
+					sheaders.get(index).map(SectionHeaderRef::Elf64)
+				}
+			}
+		}
+
+		/// Returns all section headers together with their names, resolved
+		/// via the section name string table (`e_shstrndx`).
+		///
+		/// Names are empty if the file has no section name string table or
+		/// a name can not be resolved (or is not valid UTF-8).
+		pub fn sections_with_names(
+			&self,
+		) -> impl Iterator<Item = (&'a str, SectionHeaderRef<'_>)> + '_ {
+			let shstrtab = self.shstrtab_data();
+
+			self.sections().map(move |sh| {
+				let name = shstrtab
+					.and_then(|shstrtab| section_name(shstrtab, &sh))
+					.and_then(|name| core::str::from_utf8(name).ok())
+					.unwrap_or("");
+
+				(name, sh)
+			})
+		}
+
+		/// Returns the section headers sorted by their offset together with
+		/// any overlaps and gaps between them.
+		///
+		/// Sections which occupy no space in the file (e.g. `.bss`) are
+		/// included in the sorted sections but ignored for overlaps and gaps
+		/// (see [`SectionHeaderRef::file_range`]).
+		pub fn section_layout(&self) -> SectionLayout<'_> {
+			let mut sections: Vec<_> = self.sections().enumerate().collect();
+			sections.sort_by_key(|(_, sh)| sh.sh_offset());
+
+			let ranges: Vec<_> = sections
+				.iter()
+				.filter_map(|(idx, sh)| Some((*idx, sh.file_range()?)))
+				.collect();
+
+			let mut overlaps = Vec::new();
+			let mut gaps = Vec::new();
+			let mut end = None;
+
+			for (pos, (idx, range)) in ranges.iter().enumerate() {
+				// Sorted by start, so only the following ranges which start
+				// before this one ends can overlap
+				for (other, other_range) in &ranges[pos + 1..] {
+					if other_range.start >= range.end {
+						break;
+					}
+					overlaps.push((*idx, *other));
+				}
+
+				match end {
+					Some(end) if end < range.start => {
+						gaps.push(end..range.start)
+					}
+					_ => {}
+				}
+				end = end.max(Some(range.end));
+			}
+
+			SectionLayout { sections, overlaps, gaps }
+		}
+
+		/// Returns all section headers with the given `sh_type` (see
+		/// [`crate::section_header::consts::typ`]).
+		pub fn section_by_type(
+			&self,
+			sh_type: u32,
+		) -> impl Iterator<Item = SectionHeaderRef<'_>> + '_ {
+			self.sections().filter(move |sh| sh.sh_type() == sh_type)
+		}
+
+		/// Returns the first section header with the given `sh_type`.
+		pub fn first_section_by_type(
+			&self,
+			sh_type: u32,
+		) -> Option<SectionHeaderRef<'_>> {
+			self.section_by_type(sh_type).next()
+		}
+
+		/// Returns the first section header named `name`.
+		///
+		/// Names are resolved via the section name string table
+		/// (`e_shstrndx`). Returns `None` if there is no such section or the
+		/// file has no section name string table.
+		pub fn section_by_name(
+			&self,
+			name: &str,
+		) -> Option<SectionHeaderRef<'_>> {
+			let shstrtab = self.shstrtab_data()?;
+
+			self.sections()
+				.find(|sh| section_name(shstrtab, sh) == Some(name.as_bytes()))
+		}
+
+		/// Returns the section header of the section name string table
+		/// (`e_shstrndx`).
+		///
+		/// Returns `None` if the file has no section name string table
+		/// (`SHN_UNDEF`) or the index is out of range. An index of
+		/// `SHN_XINDEX` is resolved via `sh_link` of the first section
+		/// header.
+		pub fn shstrtab(&self) -> Option<SectionHeaderRef<'_>> {
+			let shstrndx = match self {
+				Self::Elf32 { header, .. } => header.e_shstrndx,
+				Self::Elf64 { header, .. } => header.e_shstrndx,
+			};
+
+			let index = match shstrndx {
+				SHN_UNDEF => return None,
+				SHN_XINDEX => self.sections().next()?.sh_link(),
+				index => u32::from(index),
+			};
+
+			self.sections().nth(index as usize)
+		}
+
+		/// Returns the data of the section name string table (`e_shstrndx`).
+		fn shstrtab_data(&self) -> Option<&'a [u8]> {
+			let bytes = match self {
+				Self::Elf32 { bytes, .. } => *bytes,
+				Self::Elf64 { bytes, .. } => *bytes,
+			};
+
+			self.shstrtab()?.try_extract_data(bytes)
+		}
+
+		/// Cross-validates the header against the parsed tables and the size
+		/// of the file.
+		///
+		/// All problems found are reported instead of only the first one.
+		pub fn verify(&self) -> core::result::Result<(), Vec<Error>> {
+			let mut errors = Vec::new();
+
+			let (bytes, ph_table, sh_table, shstrndx, segments) = match self {
+				Self::Elf32 { bytes, header, pheaders, sheaders } => {
+					if let Err(err) = header.validate_ehsize() {
+						errors.push(err);
+					}
+
+					for (idx, ph) in pheaders.iter().enumerate() {
+						if let Err(err) = ph.validate() {
+							errors.push(
+								err.with_message(format!("Segment {}", idx)),
+							);
+						}
+					}
+
+					let segments: Vec<_> = pheaders
+						.iter()
+						.map(|ph| (ph.p_offset as u64, ph.p_filesz as u64))
+						.collect();
+
+					(
+						*bytes,
+						(
+							header.e_phnum,
+							header.e_phentsize,
+							pheaders.len(),
+							crate::program_header::elf32::PHENTSIZE as usize,
+						),
+						(
+							header.e_shnum,
+							header.e_shentsize,
+							sheaders.len(),
+							crate::section_header::elf32::SHENTSIZE as usize,
+						),
+						header.e_shstrndx,
+						segments,
+					)
+				}
+				Self::Elf64 { bytes, header, pheaders, sheaders } => {
+					if let Err(err) = header.validate_ehsize() {
+						errors.push(err);
+					}
+
+					for (idx, ph) in pheaders.iter().enumerate() {
+						if let Err(err) = ph.validate() {
+							errors.push(
+								err.with_message(format!("Segment {}", idx)),
+							);
+						}
+					}
+
+					let segments: Vec<_> = pheaders
+						.iter()
+						.map(|ph| (ph.p_offset, ph.p_filesz))
+						.collect();
+
+					(
+						*bytes,
+						(
+							header.e_phnum,
+							header.e_phentsize,
+							pheaders.len(),
+							crate::program_header::elf64::PHENTSIZE as usize,
+						),
+						(
+							header.e_shnum,
+							header.e_shentsize,
+							sheaders.len(),
+							crate::section_header::elf64::SHENTSIZE as usize,
+						),
+						header.e_shstrndx,
+						segments,
+					)
+				}
+			};
+
+			// Field values (`e_*num`, `e_*entsize`) and actual values (parsed
+			// headers, size of a header).
+			let (phnum, phentsize, ph_len, ph_size) = ph_table;
+			let (shnum, shentsize, sh_len, sh_size) = sh_table;
+
+			if phnum as usize != ph_len {
+				errors.push(
+					Error::new(ErrorKind::MalformedHeader).with_message(
+						format!(
+							"`e_phnum` ({}) does not match the number of \
+							 program headers ({})",
+							phnum, ph_len
+						),
+					),
+				);
+			}
+
+			if phnum > 0 && phentsize as usize != ph_size {
+				errors.push(
+					Error::new(ErrorKind::MalformedHeader).with_message(
+						format!(
+							"`e_phentsize` ({}) does not match the size of a \
+							 program header ({})",
+							phentsize, ph_size
+						),
+					),
+				);
+			}
+
+			if shnum as usize != sh_len {
+				errors.push(
+					Error::new(ErrorKind::MalformedHeader).with_message(
+						format!(
+							"`e_shnum` ({}) does not match the number of \
+							 section headers ({})",
+							shnum, sh_len
+						),
+					),
+				);
+			}
+
+			if shnum > 0 && shentsize as usize != sh_size {
+				errors.push(
+					Error::new(ErrorKind::MalformedHeader).with_message(
+						format!(
+							"`e_shentsize` ({}) does not match the size of a \
+							 section header ({})",
+							shentsize, sh_size
+						),
+					),
+				);
+			}
+
+			// `SHN_UNDEF`: No section name string table
+			if shstrndx != 0 && shstrndx as usize >= sh_len {
+				errors.push(
+					Error::new(ErrorKind::MalformedHeader).with_message(
+						format!(
+							"`e_shstrndx` ({}) is out of range of the \
+							 section header table ({})",
+							shstrndx, sh_len
+						),
+					),
+				);
+			}
+
+			let in_file = |offset: u64, size: u64| {
+				offset
+					.checked_add(size)
+					.is_some_and(|end| end <= bytes.len() as u64)
+			};
+
+			for (idx, (offset, size)) in segments.into_iter().enumerate() {
+				if !in_file(offset, size) {
+					errors.push(
+						Error::new(ErrorKind::InsufficantSize).with_message(
+							format!(
+								"Segment {} (offset: {:#x}; size: {:#x}) \
+								 exceeds the file",
+								idx, offset, size
+							),
+						),
+					);
+				}
+			}
+
+			for (idx, sh) in self.sections().enumerate() {
+				// Sections without data in the file
+				if sh.sh_type() == SH_TYPE_SHT_NOBITS {
+					continue;
+				}
+
+				if !in_file(sh.sh_offset(), sh.sh_size()) {
+					errors.push(
+						Error::new(ErrorKind::InsufficantSize).with_message(
+							format!(
+								"Section {} (offset: {:#x}; size: {:#x}) \
+								 exceeds the file",
+								idx,
+								sh.sh_offset(),
+								sh.sh_size()
+							),
+						),
+					);
+				}
+			}
+
+			if errors.is_empty() {
+				Ok(())
+			} else {
+				Err(errors)
+			}
+		}
+
+		pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+			let class = *bytes
+				.get(EI_CLASS)
+				.ok_or(Error::new(ErrorKind::InsufficantSize))?;
+
+			match class {
+				EI_CLASS_32 => Self::from_bytes_c32(bytes),
+				EI_CLASS_64 => Self::from_bytes_c64(bytes),
+				_ => return Err(Error::new(ErrorKind::InvalidClass)),
+			}
+		}
+
+		/// Same as [`Elf::from_bytes`], but also fails on unknown values in
+		/// `e_ident` (see [`crate::header::Ident::validate_strict`]).
+		pub fn from_bytes_strict(bytes: &'a [u8]) -> Result<Self> {
+			let elf = Self::from_bytes(bytes)?;
+
+			match &elf {
+				Self::Elf32 { header, .. } => header.e_ident.validate_strict(),
+				Self::Elf64 { header, .. } => header.e_ident.validate_strict(),
+			}?;
+
+			Ok(elf)
+		}
+
+		fn from_bytes_c32(bytes: &'a [u8]) -> Result<Self> {
+			let header = Header32::from_bytes(bytes)?;
+			assert_eq!(header.e_ident.ei_class(), EI_CLASS_32);
+			let endianness = header.e_ident.endianness()?;
+
+			// ProgramHeader
+			let pheaders = {
 				let ph_offset = header.e_phoff;
 				let ph_count = header.e_phnum;
 				let ph_size = header.e_phentsize;
 
+				let table = table_bytes(
+					bytes,
+					ph_offset,
+					ph_count,
+					ph_size,
+					crate::program_header::elf32::PHENTSIZE,
+				)?;
 				let mut pheaders = Vec::with_capacity(ph_count as usize);
 
 				for idx in 0..ph_count {
-					let start =
-						(ph_offset + (idx as u32 * ph_size as u32)) as usize;
+					let start = idx as usize * ph_size as usize;
 					let ph = ProgramHeader32::from_bytes(
 						endianness,
-						core::ops::Index::index(bytes, start..),
+						core::ops::Index::index(table, start..),
 					)?;
 
 					pheaders.push(ph);
@@ -1457,14 +4914,20 @@ pub mod elf {
 				let sh_count = header.e_shnum;
 				let sh_size = header.e_shentsize;
 
+				let table = table_bytes(
+					bytes,
+					sh_offset,
+					sh_count,
+					sh_size,
+					crate::section_header::elf32::SHENTSIZE,
+				)?;
 				let mut sheaders = Vec::with_capacity(sh_count as usize);
 
 				for idx in 0..sh_count {
-					let start =
-						(sh_offset + (idx as u32 * sh_size as u32)) as usize;
+					let start = idx as usize * sh_size as usize;
 					let sh = SectionHeader32::from_bytes(
 						endianness,
-						core::ops::Index::index(bytes, start..),
+						core::ops::Index::index(table, start..),
 					)?;
 
 					sheaders.push(sh);
@@ -1477,12 +4940,16 @@ pub mod elf {
 		}
 
 		fn from_bytes_c64(bytes: &'a [u8]) -> Result<Self> {
-			#[cfg(not(target_pointer_width = "64"))]
-			compile_error!("Needs 64 bits");
+			// Offsets and sizes are used to index `bytes`
+			if cfg!(not(target_pointer_width = "64")) {
+				return Err(Error::new(ErrorKind::InvalidClass).with_message(
+					"64-bit elfs can only be parsed on 64-bit hosts",
+				));
+			}
 
 			let header = Header64::from_bytes(bytes)?;
 			assert_eq!(header.e_ident.ei_class(), EI_CLASS_64);
-			let endianness = header.e_ident.ei_data();
+			let endianness = header.e_ident.endianness()?;
 
 			// ProgramHeader
 			let pheaders = {
@@ -1490,14 +4957,20 @@ pub mod elf {
 				let ph_count = header.e_phnum;
 				let ph_size = header.e_phentsize;
 
+				let table = table_bytes(
+					bytes,
+					ph_offset,
+					ph_count,
+					ph_size,
+					crate::program_header::elf64::PHENTSIZE,
+				)?;
 				let mut pheaders = Vec::with_capacity(ph_count as usize);
 
 				for idx in 0..ph_count {
-					let start =
-						(ph_offset + (idx as u64 * ph_size as u64)) as usize;
+					let start = idx as usize * ph_size as usize;
 					let ph = ProgramHeader64::from_bytes(
 						endianness,
-						core::ops::Index::index(bytes, start..),
+						core::ops::Index::index(table, start..),
 					)?;
 
 					pheaders.push(ph);
@@ -1512,14 +4985,20 @@ pub mod elf {
 				let sh_count = header.e_shnum;
 				let sh_size = header.e_shentsize;
 
+				let table = table_bytes(
+					bytes,
+					sh_offset,
+					sh_count,
+					sh_size,
+					crate::section_header::elf64::SHENTSIZE,
+				)?;
 				let mut sheaders = Vec::with_capacity(sh_count as usize);
 
 				for idx in 0..sh_count {
-					let start =
-						(sh_offset + (idx as u64 * sh_size as u64)) as usize;
+					let start = idx as usize * sh_size as usize;
 					let sh = SectionHeader64::from_bytes(
 						endianness,
-						core::ops::Index::index(bytes, start..),
+						core::ops::Index::index(table, start..),
 					)?;
 
 					sheaders.push(sh);
@@ -1531,4 +5010,1254 @@ pub mod elf {
 			Ok(Self::Elf64 { bytes, header, pheaders, sheaders })
 		}
 	}
+
+	/// Prints a summary of the header (similar to `readelf -h`) followed by
+	/// one line for each program and section header.
+	impl fmt::Display for Elf<'_> {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			use crate::header::consts::ident::class::ei_class_as_str;
+			use crate::header::consts::ident::data::ei_data_as_str;
+			use crate::header::consts::machine::{
+				e_machine_as_str, E_MACHINE_RISCV,
+			};
+			use crate::header::consts::typ::e_type_as_str;
+			use crate::program_header::consts::typ::p_type_as_str;
+			use crate::section_header::consts::typ::{riscv, sh_type_as_str};
+
+			// Fields shared by both classes, widened to 64 bits
+			let (ident, e_type, e_machine, e_entry, width, pheaders) =
+				match self {
+					Self::Elf32 { header, pheaders, .. } => (
+						header.e_ident,
+						header.e_type,
+						header.e_machine,
+						header.e_entry as u64,
+						8,
+						pheaders
+							.iter()
+							.map(|ph| {
+								(
+									ph.p_type,
+									ph.p_offset as u64,
+									ph.p_vaddr as u64,
+									ph.p_filesz as u64,
+									ph.p_memsz as u64,
+								)
+							})
+							.collect::<Vec<_>>(),
+					),
+					Self::Elf64 { header, pheaders, .. } => (
+						header.e_ident,
+						header.e_type,
+						header.e_machine,
+						header.e_entry,
+						16,
+						pheaders
+							.iter()
+							.map(|ph| {
+								(
+									ph.p_type,
+									ph.p_offset,
+									ph.p_vaddr,
+									ph.p_filesz,
+									ph.p_memsz,
+								)
+							})
+							.collect::<Vec<_>>(),
+					),
+				};
+
+			write!(
+				f,
+				r#"Elf:
+	Class          : {}
+	Data           : {}
+	Type           : {}
+	Machine        : {}
+	Entry          : 0x{:0width$x}
+	Program headers: {}
+	Section headers: {}"#,
+				ei_class_as_str(ident.ei_class()),
+				ei_data_as_str(ident.ei_data()),
+				e_type_as_str(e_type),
+				e_machine_as_str(e_machine),
+				e_entry,
+				pheaders.len(),
+				self.sections().count(),
+				width = width,
+			)?;
+
+			write!(f, "\nProgram headers:")?;
+			for (p_type, p_offset, p_vaddr, p_filesz, p_memsz) in pheaders {
+				write!(
+					f,
+					"\n\t{:<12} offset: 0x{:08x}; vaddr: 0x{:0width$x}; \
+					 filesz: 0x{:x}; memsz: 0x{:x}",
+					p_type_as_str(p_type),
+					p_offset,
+					p_vaddr,
+					p_filesz,
+					p_memsz,
+					width = width,
+				)?;
+			}
+
+			let sh_type_as_str = if e_machine == E_MACHINE_RISCV {
+				riscv::sh_type_as_str
+			} else {
+				sh_type_as_str
+			};
+
+			write!(f, "\nSection headers:")?;
+			for (idx, (name, sh)) in self.sections_with_names().enumerate() {
+				write!(
+					f,
+					"\n\t[{:>2}] {:<18} {:<12} addr: 0x{:0width$x}; offset: \
+					 0x{:08x}; size: 0x{:x}",
+					idx,
+					name,
+					sh_type_as_str(sh.sh_type()),
+					sh.sh_addr(),
+					sh.sh_offset(),
+					sh.sh_size(),
+					width = width,
+				)?;
+			}
+
+			Ok(())
+		}
+	}
+
+	/// Parsed headers of an [`OwnedElf`].
+	#[derive(Debug)]
+	enum Headers {
+		Elf32 {
+			header: Header32,
+			pheaders: Vec<ProgramHeader32>,
+			sheaders: Vec<SectionHeader32>,
+		},
+		Elf64 {
+			header: Header64,
+			pheaders: Vec<ProgramHeader64>,
+			sheaders: Vec<SectionHeader64>,
+		},
+	}
+
+	/// Elf which owns its bytes.
+	///
+	/// Unlike [`Elf`] this is not tied to the lifetime of a borrowed buffer,
+	/// which makes it possible to read and keep a file without managing the
+	/// buffer separately. Use [`OwnedElf::elf`] to access the parsed file.
+	#[derive(Debug)]
+	pub struct OwnedElf {
+		bytes: Vec<u8>,
+		headers: Headers,
+	}
+
+	impl OwnedElf {
+		/// Parses `bytes` and takes ownership of them.
+		pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+			let headers = match Elf::from_bytes(&bytes)? {
+				Elf::Elf32 { header, pheaders, sheaders, .. } => {
+					Headers::Elf32 { header, pheaders, sheaders }
+				}
+				Elf::Elf64 { header, pheaders, sheaders, .. } => {
+					Headers::Elf64 { header, pheaders, sheaders }
+				}
+			};
+
+			Ok(Self { bytes, headers })
+		}
+
+		/// Reads all bytes from `reader` and parses them.
+		///
+		/// Parse errors are returned as [`std::io::ErrorKind::InvalidData`].
+		pub fn from_reader<R: std::io::Read>(
+			mut reader: R,
+		) -> std::io::Result<Self> {
+			let mut bytes = Vec::new();
+			reader.read_to_end(&mut bytes)?;
+
+			Self::from_bytes(bytes).map_err(|err| {
+				std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+			})
+		}
+
+		/// Reads and parses the file at `path`.
+		///
+		/// Parse errors are returned as [`std::io::ErrorKind::InvalidData`].
+		pub fn from_path<P: AsRef<std::path::Path>>(
+			path: P,
+		) -> std::io::Result<Self> {
+			Self::from_reader(std::fs::File::open(path)?)
+		}
+
+		pub fn bytes(&self) -> &[u8] {
+			&self.bytes
+		}
+
+		pub fn into_bytes(self) -> Vec<u8> {
+			self.bytes
+		}
+
+		/// Returns an [`Elf`] borrowing from `self`.
+		///
+		/// The bytes are not parsed again, but the program and section
+		/// header tables are copied on every call, so keep the returned
+		/// [`Elf`] around instead of calling this repeatedly.
+		pub fn elf(&self) -> Elf<'_> {
+			match &self.headers {
+				Headers::Elf32 { header, pheaders, sheaders } => Elf::Elf32 {
+					bytes: &self.bytes,
+					header: *header,
+					pheaders: pheaders.clone(),
+					sheaders: sheaders.clone(),
+				},
+				Headers::Elf64 { header, pheaders, sheaders } => Elf::Elf64 {
+					bytes: &self.bytes,
+					header: *header,
+					pheaders: pheaders.clone(),
+					sheaders: sheaders.clone(),
+				},
+			}
+		}
+	}
+
+	#[cfg(test)]
+	mod tests {
+		use super::*;
+		use crate::header::elf64::EHSIZE as EHSIZE_64;
+
+		const RV32UI_P_ADD: &[u8] =
+			include_bytes!("../../../resources/riscv-tests/rv32ui-p-add");
+		const RV64UI_P_ADD: &[u8] =
+			include_bytes!("../../../resources/riscv-tests/rv64ui-p-add");
+		const HELLO_PIE: &[u8] =
+			include_bytes!("../../../resources/elf/hello-pie");
+
+		#[test]
+		fn load_segments() {
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+
+			let segments: Vec<_> = elf
+				.load_segments()
+				.map(|(addr, data, mem_size)| (addr.0, data.len(), mem_size))
+				.collect();
+
+			assert_eq!(
+				segments,
+				[(0x8000_0000, 0x6bc, 0x6bc), (0x8000_1000, 0x48, 0x48)]
+			);
+		}
+
+		#[test]
+		fn program_headers_consuming() {
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+			let (header, pheaders) = match &elf {
+				Elf::Elf64 { header, pheaders, .. } => (header, pheaders),
+				_ => panic!("Expected 64-bit ELF"),
+			};
+			let endianness = header.e_ident.endianness().unwrap();
+
+			let (_, bytes) =
+				Header64::from_bytes_consuming(RV64UI_P_ADD).unwrap();
+			assert_eq!(header.e_phoff, EHSIZE_64 as u64);
+
+			let (first, bytes) =
+				ProgramHeader64::from_bytes_consuming(endianness, bytes)
+					.unwrap();
+			let (second, _) =
+				ProgramHeader64::from_bytes_consuming(endianness, bytes)
+					.unwrap();
+
+			assert_eq!([first, second], pheaders[..]);
+
+			// A truncated second entry is an error instead of a panic
+			let start = header.e_phoff as usize;
+			let truncated = RV64UI_P_ADD
+				.get(start..start + 2 * header.e_phentsize as usize - 1)
+				.unwrap();
+			let (_, rest) =
+				ProgramHeader64::from_bytes_consuming(endianness, truncated)
+					.unwrap();
+			let err = ProgramHeader64::from_bytes_consuming(endianness, rest)
+				.unwrap_err();
+			assert_eq!(err.kind, ErrorKind::InsufficantSize);
+		}
+
+		#[test]
+		fn section_by_type() {
+			use crate::section_header::consts::typ::{
+				SH_TYPE_SHT_NOBITS, SH_TYPE_SHT_STRTAB, SH_TYPE_SHT_SYMTAB,
+			};
+
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+
+			// `.strtab` and `.shstrtab`
+			let strtabs: Vec<_> = elf
+				.section_by_type(SH_TYPE_SHT_STRTAB)
+				.map(|sh| sh.sh_offset())
+				.collect();
+			assert_eq!(strtabs, [0x25a8, 0x2758]);
+
+			let symtab =
+				elf.first_section_by_type(SH_TYPE_SHT_SYMTAB).unwrap();
+			assert_eq!(symtab.sh_offset(), 0x2080);
+			assert_eq!(symtab.sh_size(), 0x528);
+			assert_eq!(symtab.sh_link(), 5);
+
+			assert_eq!(elf.first_section_by_type(SH_TYPE_SHT_NOBITS), None);
+		}
+
+		#[test]
+		fn section_by_name() {
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+
+			let text = elf.section_by_name(".text.init").unwrap();
+			assert_eq!(text.sh_addr(), 0x8000_0000);
+			assert_eq!(text.sh_size(), 0x6bc);
+
+			let shstrtab = elf.section_by_name(".shstrtab").unwrap();
+			assert_eq!(shstrtab.sh_offset(), 0x2758);
+
+			// Name is only a suffix of `.text.init`
+			assert_eq!(elf.section_by_name(".init"), None);
+			assert_eq!(elf.section_by_name(".data"), None);
+
+			// No section name string table (`e_shstrndx`)
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			bytes[62..64].copy_from_slice(&0_u16.to_le_bytes());
+
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.section_by_name(".text.init"), None);
+		}
+
+		#[test]
+		fn from_bytes_strict() {
+			use crate::header::consts::ident::index::EI_OSABI;
+
+			assert!(Elf::from_bytes_strict(HELLO_PIE).is_ok());
+			assert!(Elf::from_bytes_strict(RV32UI_P_ADD).is_ok());
+
+			// Made-up os abi
+			let mut bytes = HELLO_PIE.to_vec();
+			bytes[EI_OSABI] = 0xaa;
+
+			assert!(Elf::from_bytes(&bytes).is_ok());
+			assert_eq!(
+				Elf::from_bytes_strict(&bytes).err().map(|err| err.kind),
+				Some(ErrorKind::UnknownOsAbi)
+			);
+		}
+
+		#[test]
+		fn symbol_data() {
+			use crate::section_header::consts::shn::SHN_ABS;
+
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+			let symbol =
+				|name| elf.symbols().find(|&(n, _)| n == name).unwrap().1;
+
+			// `int answer = 42;` in `.data`
+			assert_eq!(
+				elf.symbol_data(&symbol("answer")),
+				Some(&42_i32.to_le_bytes()[..])
+			);
+
+			// `int zeroed[16];` in `.bss`
+			assert_eq!(elf.symbol_data(&symbol("zeroed")), None);
+
+			// Undefined and absolute
+			let mut undefined = symbol("answer");
+			undefined.st_shndx = SHN_UNDEF;
+			assert_eq!(elf.symbol_data(&undefined), None);
+			let mut absolute = symbol("answer");
+			absolute.st_shndx = SHN_ABS;
+			assert_eq!(elf.symbol_data(&absolute), None);
+
+			// Section offset overflowing with the symbol offset
+			let Elf::Elf64 { header, .. } = &elf else {
+				panic!("Expected a 64-bit elf");
+			};
+			let answer = symbol("answer");
+			let index = answer.st_shndx as usize;
+			let sh_offset = header.e_shoff as usize
+				+ index * header.e_shentsize as usize
+				+ 0x18;
+
+			let mut bytes = HELLO_PIE.to_vec();
+			bytes[sh_offset..sh_offset + 8]
+				.copy_from_slice(&u64::MAX.to_le_bytes());
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.symbol_data(&answer), None);
+		}
+
+		#[test]
+		fn empty_header_tables() {
+			fn table_lens(elf: &Elf) -> (usize, usize) {
+				match elf {
+					Elf::Elf32 { pheaders, sheaders, .. } => {
+						(pheaders.len(), sheaders.len())
+					}
+					Elf::Elf64 { pheaders, sheaders, .. } => {
+						(pheaders.len(), sheaders.len())
+					}
+				}
+			}
+
+			// (bytes, offset of `e_phnum`, offset of `e_shnum`)
+			for (original, phnum, shnum) in
+				[(RV32UI_P_ADD, 44, 48), (RV64UI_P_ADD, 56, 60)]
+			{
+				// No section headers, but `e_shstrndx` still set
+				let mut bytes = original.to_vec();
+				bytes[shnum..shnum + 2].copy_from_slice(&0_u16.to_le_bytes());
+
+				let elf = Elf::from_bytes(&bytes).unwrap();
+				assert_eq!(table_lens(&elf), (2, 0));
+				assert_eq!(elf.sections().count(), 0);
+				assert_eq!(elf.shstrtab(), None);
+				assert_eq!(elf.section_by_name(".text.init"), None);
+				assert_eq!(elf.sections_with_names().count(), 0);
+				assert_eq!(elf.symbols().count(), 0);
+				assert_eq!(elf.symbol_at_address(0x8000_0000), None);
+				assert!(elf.section_layout().sections.is_empty());
+				// `e_shstrndx` is out of range
+				assert!(elf.verify().is_err());
+				let _ = elf.to_string();
+
+				// Neither program nor section headers
+				bytes[phnum..phnum + 2].copy_from_slice(&0_u16.to_le_bytes());
+
+				let elf = Elf::from_bytes(&bytes).unwrap();
+				assert_eq!(table_lens(&elf), (0, 0));
+				assert_eq!(elf.load_segments().count(), 0);
+				assert_eq!(elf.entry_bytes(4), None);
+				assert_eq!(elf.interpreter(), None);
+				let _ = elf.to_string();
+			}
+		}
+
+		#[test]
+		fn header_counts() {
+			for bytes in [RV32UI_P_ADD, RV64UI_P_ADD, HELLO_PIE] {
+				let elf = Elf::from_bytes(bytes).unwrap();
+				let (e_phnum, e_shnum) = match &elf {
+					Elf::Elf32 { header, .. } => {
+						(header.e_phnum, header.e_shnum)
+					}
+					Elf::Elf64 { header, .. } => {
+						(header.e_phnum, header.e_shnum)
+					}
+				};
+
+				assert_eq!(elf.program_header_count(), e_phnum as usize);
+				assert_eq!(elf.section_header_count(), e_shnum as usize);
+				assert_eq!(elf.program_header(e_phnum as usize), None);
+				assert_eq!(elf.section_header(e_shnum as usize), None);
+				assert_eq!(elf.section_header(1), elf.sections().nth(1),);
+			}
+
+			let elf = Elf::from_bytes(RV32UI_P_ADD).unwrap();
+			let ph = elf.program_header(0).unwrap();
+			assert!(matches!(ph, ProgramHeaderRef::Elf32(_)));
+			assert!(ph.is_load());
+			assert_eq!((ph.p_vaddr(), ph.p_filesz()), (0x8000_0000, 0x6bc));
+			assert_eq!(ph.extract_data(RV32UI_P_ADD).len(), 0x6bc);
+		}
+
+		#[test]
+		fn memory_span() {
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+			assert_eq!(elf.memory_span(), Some((0x8000_0000, 0x8000_1048)));
+
+			let elf = Elf::from_bytes(RV32UI_P_ADD).unwrap();
+			assert_eq!(elf.memory_span(), Some((0x8000_0000, 0x8000_1048)));
+
+			// `.bss` (`p_memsz` > `p_filesz`) is included
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+			assert_eq!(elf.memory_span(), Some((0, 0x1dbc + 0x2c4)));
+
+			// No program headers (`e_phnum` set to 0)
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			bytes[56..58].copy_from_slice(&0_u16.to_le_bytes());
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.memory_span(), None);
+		}
+
+		#[test]
+		fn validate_program_headers() {
+			fn first_pheader_error(bytes: &[u8]) -> Option<ErrorKind> {
+				match Elf::from_bytes(bytes).unwrap() {
+					Elf::Elf64 { pheaders, .. } => {
+						pheaders[0].validate().err().map(|e| e.kind)
+					}
+					_ => panic!("Expected 64-bit ELF"),
+				}
+			}
+
+			for bytes in [RV32UI_P_ADD, RV64UI_P_ADD, HELLO_PIE] {
+				assert!(Elf::from_bytes(bytes).unwrap().verify().is_ok());
+			}
+
+			// First program header of the 64-bit elf
+			let (p_offset, p_vaddr, p_filesz) = (64 + 8, 64 + 16, 64 + 32);
+
+			// `p_filesz` (0x6bc) larger than `p_memsz`
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			bytes[p_filesz..p_filesz + 8]
+				.copy_from_slice(&0x6bd_u64.to_le_bytes());
+			assert_eq!(
+				first_pheader_error(&bytes),
+				Some(ErrorKind::InvalidSegmentSize)
+			);
+			let errors =
+				Elf::from_bytes(&bytes).unwrap().verify().unwrap_err();
+			assert!(errors
+				.iter()
+				.any(|e| e.kind == ErrorKind::InvalidSegmentSize));
+
+			// Only checked for loadable segments (`PT_LOAD` -> `PT_NOTE`)
+			let p_type = 64;
+			bytes[p_type..p_type + 4].copy_from_slice(&4_u32.to_le_bytes());
+			assert_eq!(first_pheader_error(&bytes), None);
+
+			// `p_vaddr` and `p_offset` not congruent modulo `p_align`
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			bytes[p_vaddr..p_vaddr + 8]
+				.copy_from_slice(&0x8000_0004_u64.to_le_bytes());
+			assert_eq!(
+				first_pheader_error(&bytes),
+				Some(ErrorKind::MisalignedSegment)
+			);
+			let errors =
+				Elf::from_bytes(&bytes).unwrap().verify().unwrap_err();
+			assert!(errors
+				.iter()
+				.any(|e| e.kind == ErrorKind::MisalignedSegment));
+
+			// Moving the offset by the same amount keeps them congruent
+			let offset = u64::from_le_bytes(
+				bytes[p_offset..p_offset + 8].try_into().unwrap(),
+			);
+			bytes[p_offset..p_offset + 8]
+				.copy_from_slice(&(offset + 4).to_le_bytes());
+			assert_eq!(first_pheader_error(&bytes), None);
+		}
+
+		#[test]
+		fn is_pie() {
+			// Position-independent executable
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+			assert!(elf.is_pie());
+			assert!(elf.is_executable());
+			assert!(!elf.is_shared_object());
+
+			// Plain executable
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+			assert!(!elf.is_pie());
+			assert!(elf.is_executable());
+			assert!(!elf.is_shared_object());
+
+			// Shared object (`PT_INTERP` of the pie changed to `PT_NULL`)
+			let mut bytes = HELLO_PIE.to_vec();
+			let p_type = 64 + 56;
+			bytes[p_type..p_type + 4].copy_from_slice(&0_u32.to_le_bytes());
+
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.interpreter(), None);
+			assert!(!elf.is_pie());
+			assert!(!elf.is_executable());
+			assert!(elf.is_shared_object());
+		}
+
+		#[test]
+		fn extract_data_nobits() {
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+
+			let bss = elf.section_by_name(".bss").unwrap();
+			assert_eq!(bss.sh_type(), SH_TYPE_SHT_NOBITS);
+			assert_eq!(bss.sh_size(), 0x60);
+			// The bytes at its offset belong to `.comment`
+			let comment = elf.section_by_name(".comment").unwrap();
+			assert_eq!(bss.sh_offset(), comment.sh_offset());
+
+			assert!(bss.extract_data(HELLO_PIE).is_empty());
+			assert!(comment.extract_data(HELLO_PIE).starts_with(b"GCC"));
+		}
+
+		#[test]
+		fn try_extract_data() {
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+			let bss = elf.section_by_name(".bss").unwrap();
+			let comment = elf.section_by_name(".comment").unwrap();
+
+			let truncated =
+				HELLO_PIE.get(..comment.sh_offset() as usize + 1).unwrap();
+			assert_eq!(bss.try_extract_data(truncated), Some(&[][..]));
+			assert_eq!(comment.try_extract_data(truncated), None);
+			assert_eq!(
+				comment.try_extract_data(HELLO_PIE),
+				Some(comment.extract_data(HELLO_PIE))
+			);
+
+			let Elf::Elf64 { header, .. } = &elf else {
+				panic!("Expected a 64-bit elf");
+			};
+			let phoff = header.e_phoff as usize;
+			let phentsize = header.e_phentsize as usize;
+			let shoff = header.e_shoff as usize;
+			let shentsize = header.e_shentsize as usize;
+
+			assert!(elf.interpreter().is_some());
+			assert_ne!(elf.symbols().count(), 0);
+			assert_ne!(elf.load_segments().count(), 0);
+
+			// Offsets of all sections (`u64::MAX` for the section name
+			// string table) and segments beyond the end of the file
+			let mut bytes = HELLO_PIE.to_vec();
+			for idx in 0..header.e_shnum as usize {
+				let sh_offset = shoff + idx * shentsize + 0x18;
+				let offset = if idx == header.e_shstrndx as usize {
+					u64::MAX
+				} else {
+					1 << 40
+				};
+				bytes[sh_offset..sh_offset + 8]
+					.copy_from_slice(&offset.to_le_bytes());
+			}
+			for idx in 0..header.e_phnum as usize {
+				let p_offset = phoff + idx * phentsize + 0x8;
+				bytes[p_offset..p_offset + 8]
+					.copy_from_slice(&(1_u64 << 40).to_le_bytes());
+			}
+
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert!(elf.verify().is_err());
+			assert!(elf.section_by_name(".text").is_none());
+			assert_eq!(elf.entry_bytes(4), None);
+			assert_eq!(elf.interpreter(), None);
+			assert!(elf.tls().is_none());
+			assert_eq!(elf.symbols().count(), 0);
+			assert_eq!(elf.symbol_at_address(header.e_entry), None);
+			assert_eq!(elf.load_segments().count(), 0);
+		}
+
+		#[test]
+		fn sections_with_names() {
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+
+			let names: Vec<_> =
+				elf.sections_with_names().map(|(name, _)| name).collect();
+			assert_eq!(
+				names,
+				[
+					"",
+					".text.init",
+					".tohost",
+					".riscv.attributes",
+					".symtab",
+					".strtab",
+					".shstrtab"
+				]
+			);
+
+			for ((name, sh), expected) in
+				elf.sections_with_names().zip(elf.sections())
+			{
+				assert_eq!(sh, expected, "{name}");
+			}
+
+			let (_, text) = elf.sections_with_names().nth(1).unwrap();
+			assert_eq!(text.sh_addr(), 0x8000_0000);
+
+			// No section name string table (`e_shstrndx`)
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			bytes[62..64].copy_from_slice(&0_u16.to_le_bytes());
+
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.sections_with_names().count(), 7);
+			assert!(elf
+				.sections_with_names()
+				.all(|(name, _)| name.is_empty()));
+		}
+
+		#[test]
+		fn entry_bytes() {
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+			// jal zero, 0x48
+			assert_eq!(
+				elf.entry_bytes(4),
+				Some(&[0x6f, 0x00, 0x80, 0x04][..])
+			);
+			// `.text.init` is 0x6bc bytes large
+			assert_eq!(elf.entry_bytes(0x6bc).map(<[u8]>::len), Some(0x6bc));
+			assert_eq!(elf.entry_bytes(0x6bd), None);
+
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+			assert_eq!(
+				elf.entry_bytes(4),
+				Some(&[0x31, 0xed, 0x49, 0x89][..])
+			);
+
+			// Entry point outside of all segments
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			bytes[24..32].copy_from_slice(&0x1000_u64.to_le_bytes());
+
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.entry_bytes(4), None);
+		}
+
+		#[test]
+		fn vaddr_to_offset() {
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+
+			// `.text` (0x660) of the first segment (offset 0x0, vaddr 0x0)
+			assert_eq!(elf.vaddr_to_offset(0x670), Some(0x670));
+			// `.data` (0x2008) of the second segment (offset 0xdbc, vaddr
+			// 0x1dbc, file size 0x260)
+			assert_eq!(elf.vaddr_to_offset(0x2008), Some(0x1008));
+			assert_eq!(elf.vaddr_to_offset(0x201b), Some(0x101b));
+			// `.bss` (0x2020) is not backed by the file
+			assert_eq!(elf.vaddr_to_offset(0x201c), None);
+			assert_eq!(elf.vaddr_to_offset(0x2020), None);
+			// Between the segments
+			assert_eq!(elf.vaddr_to_offset(0x1000), None);
+
+			let elf = Elf::from_bytes(RV32UI_P_ADD).unwrap();
+			assert_eq!(elf.vaddr_to_offset(0x8000_0000), Some(0x1000));
+			assert_eq!(elf.vaddr_to_offset(0x7fff_ffff), None);
+		}
+
+		#[test]
+		fn riscv_attributes() {
+			let elf = Elf::from_bytes(RV32UI_P_ADD).unwrap();
+			let attributes = elf.riscv_attributes().unwrap();
+			assert_eq!(
+				attributes.riscv_arch(),
+				Some("rv32i2p0_m2p0_a2p0_f2p0_d2p0")
+			);
+			assert!(elf.to_string().contains("SHT_RISCV_ATTRIBUTES"));
+
+			// `sh_offset` beyond the end of the file
+			let Elf::Elf32 { header, .. } = &elf else {
+				panic!("Expected a 32-bit elf");
+			};
+			let idx = elf
+				.sections()
+				.position(|sh| sh.sh_type() == SH_TYPE_SHT_RISCV_ATTRIBUTES)
+				.unwrap();
+			let sh_offset = header.e_shoff as usize
+				+ idx * header.e_shentsize as usize
+				+ 0x10;
+			let mut bytes = RV32UI_P_ADD.to_vec();
+			bytes[sh_offset..sh_offset + 4]
+				.copy_from_slice(&u32::MAX.to_le_bytes());
+			assert_eq!(
+				Elf::from_bytes(&bytes).unwrap().riscv_attributes(),
+				None
+			);
+
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+			assert_eq!(elf.riscv_attributes(), None);
+		}
+
+		#[test]
+		fn symbols() {
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+			let symbols: Vec<_> = elf.symbols().collect();
+
+			assert_eq!(symbols.len(), 55);
+			assert_eq!(symbols[0].0, "");
+			assert_eq!(symbols[5].0, "reset_vector");
+			assert_eq!(symbols[5].1.st_value, 0x8000_0048);
+
+			// Widened from 32-bit
+			let elf = Elf::from_bytes(RV32UI_P_ADD).unwrap();
+			let (_, start) =
+				elf.symbols().find(|(name, _)| *name == "_start").unwrap();
+			assert_eq!(start.st_value, 0x8000_0000);
+
+			// No symbol table (`sh_type` of `.symtab` changed to
+			// `SHT_PROGBITS`)
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			let sh_type = 10136 + 4 * 64 + 4;
+			bytes[sh_type..sh_type + 4].copy_from_slice(&1_u32.to_le_bytes());
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.symbols().count(), 0);
+		}
+
+		#[test]
+		fn symbol_at_address() {
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+
+			// `main`: 0x660 - 0x696
+			let (name, symbol, offset) = elf.symbol_at_address(0x670).unwrap();
+			assert_eq!(
+				(name, symbol.st_value, symbol.st_size),
+				("main", 0x660, 54)
+			);
+			assert_eq!(offset, 0x10);
+
+			// `zeroed` (`.bss`): 0x2040 - 0x2080
+			let (name, _, offset) = elf.symbol_at_address(0x207f).unwrap();
+			assert_eq!((name, offset), ("zeroed", 0x3f));
+			assert_eq!(elf.symbol_at_address(0x2080), None);
+
+			// Only symbols without a size
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+			assert_eq!(elf.symbol_at_address(0x8000_0048), None);
+		}
+
+		#[test]
+		fn display() {
+			let summary = Elf::from_bytes(HELLO_PIE).unwrap().to_string();
+
+			assert!(summary.contains("Machine        : AMD x86-64"));
+			assert!(summary.contains("Entry          : 0x00000000000006a0"));
+			assert!(summary.contains("Program headers: 12"));
+			assert!(summary.contains("[16] .text"));
+
+			let summary = Elf::from_bytes(RV32UI_P_ADD).unwrap().to_string();
+
+			assert!(summary.contains("Machine        : RISC-V"));
+			assert!(summary.contains("Entry          : 0x80000000"));
+		}
+
+		#[test]
+		fn section_header_display() {
+			use crate::section_header::consts::flags::FlagLetters;
+
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+
+			let data = elf.section_by_name(".data").unwrap();
+			let SectionHeaderRef::Elf64(sh) = data else {
+				panic!("Expected elf to be 64-bit");
+			};
+			assert_eq!(data.sh_flags(), 0x3);
+			assert!(sh
+				.to_string()
+				.contains("sh_flags    : WA (0x0000000000000003)"));
+
+			let text = elf.section_by_name(".text").unwrap();
+			assert_eq!(FlagLetters(text.sh_flags()).to_string(), "AX");
+
+			let elf = Elf::from_bytes(RV32UI_P_ADD).unwrap();
+			let Elf::Elf32 { sheaders, .. } = &elf else {
+				panic!("Expected elf to be 32-bit");
+			};
+			assert!(sheaders[1]
+				.to_string()
+				.contains("sh_flags    : AX (0x00000006)"));
+
+			assert_eq!(FlagLetters(0).to_string(), "");
+			assert_eq!(FlagLetters(0x30).to_string(), "MS");
+			assert_eq!(FlagLetters(0x8000_0442).to_string(), "AITE");
+			assert_eq!(FlagLetters(0x0800_0000).to_string(), "o");
+			assert_eq!(FlagLetters(0x1010_1000).to_string(), "opx");
+		}
+
+		#[test]
+		fn interpreter() {
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+			assert_eq!(elf.interpreter(), Some("/lib64/ld-linux-x86-64.so.2"));
+
+			// Statically linked
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+			assert_eq!(elf.interpreter(), None);
+		}
+
+		#[test]
+		fn verify() {
+			assert_eq!(
+				Elf::from_bytes(RV32UI_P_ADD).unwrap().verify(),
+				Ok(())
+			);
+			assert_eq!(
+				Elf::from_bytes(RV64UI_P_ADD).unwrap().verify(),
+				Ok(())
+			);
+
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			// `e_ehsize`
+			bytes[52..54].copy_from_slice(&0x20_u16.to_le_bytes());
+			// `e_shstrndx`
+			bytes[62..64].copy_from_slice(&9_u16.to_le_bytes());
+			// `sh_size` of section 1 (`.text.init`)
+			let sh_size = 0x2798 + 64 + 32;
+			bytes[sh_size..sh_size + 8]
+				.copy_from_slice(&0x10_0000_u64.to_le_bytes());
+
+			let errors =
+				Elf::from_bytes(&bytes).unwrap().verify().unwrap_err();
+			let errors: Vec<_> = errors
+				.iter()
+				.map(|err| (err.kind, err.message.as_deref()))
+				.collect();
+
+			assert_eq!(
+				errors,
+				[
+					(ErrorKind::InvalidHeaderSize, None),
+					(
+						ErrorKind::MalformedHeader,
+						Some(
+							"`e_shstrndx` (9) is out of range of the section \
+							 header table (7)"
+						)
+					),
+					(
+						ErrorKind::InsufficantSize,
+						Some(
+							"Section 1 (offset: 0x1000; size: 0x100000) \
+							 exceeds the file"
+						)
+					),
+				]
+			);
+		}
+
+		#[test]
+		fn hand_built_headers_equal() {
+			use crate::program_header::consts::flags::{
+				P_FLAGS_PF_R, P_FLAGS_PF_X,
+			};
+			use crate::program_header::consts::typ::P_TYPE_PT_LOAD;
+			use crate::section_header::consts::flags::elf64::{
+				SH_FLAG_SHF_ALLOC, SH_FLAG_SHF_EXECINSTR,
+			};
+			use crate::section_header::consts::typ::SH_TYPE_SHT_PROGBITS;
+
+			// The derived `PartialEq` compares field by field, so padding
+			// (of which the headers have none, see `too_small_entsize`) can
+			// not cause spurious inequality
+			let Elf::Elf64 { pheaders, sheaders, .. } =
+				Elf::from_bytes(RV64UI_P_ADD).unwrap()
+			else {
+				panic!("Expected a 64-bit elf");
+			};
+
+			let text = ProgramHeader64 {
+				p_type: P_TYPE_PT_LOAD,
+				p_flags: P_FLAGS_PF_R | P_FLAGS_PF_X,
+				p_offset: 0x1000,
+				p_vaddr: 0x8000_0000,
+				p_paddr: 0x8000_0000,
+				p_filesz: 0x6bc,
+				p_memsz: 0x6bc,
+				p_align: 0x1000,
+			};
+			assert_eq!(pheaders[0], text);
+
+			let text_init = SectionHeader64 {
+				sh_name: sheaders[1].sh_name,
+				sh_type: SH_TYPE_SHT_PROGBITS,
+				sh_flags: SH_FLAG_SHF_ALLOC | SH_FLAG_SHF_EXECINSTR,
+				sh_addr: 0x8000_0000,
+				sh_offset: 0x1000,
+				sh_size: 0x6bc,
+				sh_link: 0,
+				sh_info: 0,
+				sh_addralign: 64,
+				sh_entsize: 0,
+			};
+			assert_eq!(sheaders[1], text_init);
+		}
+
+		#[test]
+		fn too_small_entsize() {
+			use crate::program_header::{elf32, elf64};
+			use crate::section_header::{elf32 as sh32, elf64 as sh64};
+
+			assert_eq!(
+				elf32::PHENTSIZE as usize,
+				core::mem::size_of::<ProgramHeader32>()
+			);
+			assert_eq!(
+				elf64::PHENTSIZE as usize,
+				core::mem::size_of::<ProgramHeader64>()
+			);
+			assert_eq!(
+				sh32::SHENTSIZE as usize,
+				core::mem::size_of::<SectionHeader32>()
+			);
+			assert_eq!(
+				sh64::SHENTSIZE as usize,
+				core::mem::size_of::<SectionHeader64>()
+			);
+
+			// (bytes, offset of `e_phentsize`, offset of `e_shentsize`)
+			for (original, phentsize, shentsize) in
+				[(RV32UI_P_ADD, 42, 46), (RV64UI_P_ADD, 54, 58)]
+			{
+				let mut bytes = original.to_vec();
+				bytes[phentsize..phentsize + 2]
+					.copy_from_slice(&16_u16.to_le_bytes());
+
+				let err = Elf::from_bytes(&bytes).err().unwrap();
+				assert_eq!(err.kind, ErrorKind::MalformedHeader);
+
+				let mut bytes = original.to_vec();
+				bytes[shentsize..shentsize + 2]
+					.copy_from_slice(&16_u16.to_le_bytes());
+
+				let err = Elf::from_bytes(&bytes).err().unwrap();
+				assert_eq!(err.kind, ErrorKind::MalformedHeader);
+			}
+		}
+
+		#[test]
+		fn shstrtab() {
+			use crate::section_header::consts::typ::SH_TYPE_SHT_STRTAB;
+
+			// `e_shstrndx` of the 64-bit header
+			const E_SHSTRNDX: core::ops::Range<usize> = 62..64;
+
+			let elf = Elf::from_bytes(RV64UI_P_ADD).unwrap();
+			let shstrtab = elf.shstrtab().unwrap();
+			assert_eq!(shstrtab.sh_type(), SH_TYPE_SHT_STRTAB);
+			assert!(elf.section_by_name(".text.init").is_some());
+
+			// `SHN_UNDEF`: No section name string table
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			bytes[E_SHSTRNDX].copy_from_slice(&SHN_UNDEF.to_le_bytes());
+
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.shstrtab(), None);
+			assert_eq!(elf.section_by_name(".text.init"), None);
+			assert!(elf.to_string().contains("Section headers:"));
+
+			// Out of range
+			bytes[E_SHSTRNDX].copy_from_slice(&100_u16.to_le_bytes());
+
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.shstrtab(), None);
+			assert_eq!(elf.section_by_name(".text.init"), None);
+		}
+
+		#[test]
+		fn to_bytes_round_trip() {
+			use crate::header::consts::ident::data::Endianness;
+			use crate::header::consts::ident::index::EI_DATA;
+
+			macro_rules! round_trip {
+				( $bytes:expr, $class:ident, $c:ident ) => {{
+					use crate::header::$c::Header;
+					use crate::program_header::$c::ProgramHeader;
+					use crate::section_header::$c::SectionHeader;
+
+					let bytes = $bytes.to_vec();
+					let Ok(Elf::$class { header, pheaders, sheaders, .. }) =
+						Elf::from_bytes(&bytes)
+					else {
+						panic!("Expected a {}", stringify!($class));
+					};
+
+					let mut buf = [0u8; 128];
+
+					for endianness in [Endianness::Little, Endianness::Big] {
+						let mut expected = header;
+						expected.e_ident.0[EI_DATA] = endianness.ei_data();
+
+						let rest =
+							header.to_bytes(endianness, &mut buf).unwrap();
+						assert_eq!(
+							rest.len(),
+							buf.len() - header.e_ehsize as usize
+						);
+						assert_eq!(
+							Header::from_bytes(&buf).unwrap(),
+							expected
+						);
+
+						for ph in &pheaders {
+							ph.to_bytes(endianness, &mut buf).unwrap();
+							let parsed =
+								ProgramHeader::from_bytes(endianness, &buf)
+									.unwrap();
+							assert_eq!(&parsed, ph);
+						}
+
+						for sh in &sheaders {
+							sh.to_bytes(endianness, &mut buf).unwrap();
+							let parsed =
+								SectionHeader::from_bytes(endianness, &buf)
+									.unwrap();
+							assert_eq!(&parsed, sh);
+						}
+					}
+
+					// Little endian is the on-disk representation of the test
+					// files
+					let size = header.e_ehsize as usize;
+					header.to_bytes(Endianness::Little, &mut buf).unwrap();
+					assert_eq!(buf[..size], bytes[..size]);
+
+					let offset = header.e_phoff as usize;
+					let size = header.e_phentsize as usize;
+					pheaders[0]
+						.to_bytes(Endianness::Little, &mut buf)
+						.unwrap();
+					assert_eq!(buf[..size], bytes[offset..offset + size]);
+
+					let size = header.e_shentsize as usize;
+					let offset = header.e_shoff as usize + size;
+					sheaders[1]
+						.to_bytes(Endianness::Little, &mut buf)
+						.unwrap();
+					assert_eq!(buf[..size], bytes[offset..offset + size]);
+
+					// Buffer too small
+					let too_small = &mut buf[..header.e_ehsize as usize - 1];
+					let err = header
+						.to_bytes(Endianness::Little, too_small)
+						.err()
+						.unwrap();
+					assert_eq!(err.kind, ErrorKind::InsufficantSize);
+				}};
+			}
+
+			round_trip!(RV32UI_P_ADD, Elf32, elf32);
+			round_trip!(RV64UI_P_ADD, Elf64, elf64);
+		}
+
+		#[test]
+		fn tls() {
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+
+			// `__thread int counter = 1;`
+			let tls = elf.tls().unwrap();
+			assert_eq!(tls.data, 1_i32.to_le_bytes());
+			assert_eq!(tls.mem_size, 4);
+			assert_eq!(tls.align, 4);
+			assert_eq!(tls.bss_size(), 0);
+
+			// Grow `p_memsz` of the `PT_TLS` segment to add a `.tbss` part
+			let index = match &elf {
+				Elf::Elf64 { pheaders, .. } => pheaders
+					.iter()
+					.position(|ph| ph.p_type == P_TYPE_PT_TLS)
+					.unwrap(),
+				_ => panic!("Expected a 64-bit elf"),
+			};
+			// `e_phoff` + index * `e_phentsize` + offset of `p_memsz`
+			let offset = 64 + index * 56 + 40;
+
+			let mut bytes = HELLO_PIE.to_vec();
+			bytes[offset..offset + 8].copy_from_slice(&0x24_u64.to_le_bytes());
+
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			let tls = elf.tls().unwrap();
+			assert_eq!(tls.data.len(), 4);
+			assert_eq!(tls.mem_size, 0x24);
+			assert_eq!(tls.bss_size(), 0x20);
+
+			// No thread-local storage
+			assert_eq!(Elf::from_bytes(RV64UI_P_ADD).unwrap().tls(), None);
+		}
+
+		#[test]
+		fn section_layout() {
+			let elf = Elf::from_bytes(HELLO_PIE).unwrap();
+			let layout = elf.section_layout();
+
+			assert_eq!(layout.sections.len(), 33);
+			assert!(layout
+				.sections
+				.windows(2)
+				.all(|w| w[0].1.sh_offset() <= w[1].1.sh_offset()));
+
+			// `.bss` (28) shares its offset with `.comment` (29) but
+			// occupies no file space
+			let bss = layout.sections.iter().find(|(idx, _)| *idx == 28);
+			assert_eq!(bss.unwrap().1.sh_type(), SH_TYPE_SHT_NOBITS);
+			assert_eq!(bss.unwrap().1.file_range(), None);
+			assert!(bss.unwrap().1.extract_data(HELLO_PIE).is_empty());
+			assert_eq!(layout.overlaps, []);
+
+			// Padding between `.interp` and `.note.gnu.property`
+			assert_eq!(layout.gaps[0], 0x2fc..0x300);
+			// Not loaded between the segments
+			assert!(layout.gaps.contains(&(0x8a0..0xdbc)));
+			for gap in &layout.gaps {
+				assert!(gap.start < gap.end);
+				assert!(elf.sections().all(|sh| sh
+					.file_range()
+					.is_none_or(|range| range.end <= gap.start
+						|| range.start >= gap.end)));
+			}
+
+			// Move `.note.ABI-tag` (4) into `.note.gnu.build-id` (3)
+			let offset = 0x1720 + 4 * 64 + 24;
+			let mut bytes = HELLO_PIE.to_vec();
+			bytes[offset..offset + 8]
+				.copy_from_slice(&0x330_u64.to_le_bytes());
+
+			let elf = Elf::from_bytes(&bytes).unwrap();
+			assert_eq!(elf.section_layout().overlaps, [(3, 4)]);
+		}
+
+		#[test]
+		fn owned_elf() {
+			let path = std::env::temp_dir().join(format!(
+				"elf-owned-elf-{}-rv64ui-p-add",
+				std::process::id()
+			));
+			std::fs::write(&path, RV64UI_P_ADD).unwrap();
+
+			let owned = OwnedElf::from_path(&path);
+			std::fs::remove_file(&path).unwrap();
+			let owned = owned.unwrap();
+
+			assert_eq!(owned.bytes(), RV64UI_P_ADD);
+
+			let (expected, elf) =
+				match (Elf::from_bytes(RV64UI_P_ADD).unwrap(), owned.elf()) {
+					(
+						Elf::Elf64 { header, pheaders, sheaders, .. },
+						Elf::Elf64 {
+							header: h, pheaders: p, sheaders: s, ..
+						},
+					) => ((header, pheaders, sheaders), (h, p, s)),
+					_ => panic!("Expected a 64-bit elf"),
+				};
+			assert_eq!(elf, expected);
+
+			// Returns a parse error as invalid data
+			let err =
+				OwnedElf::from_reader(core::ops::Index::index(HELLO_PIE, 1..))
+					.err()
+					.unwrap();
+			assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+			// Too short to even contain the class
+			let err = OwnedElf::from_reader(std::io::empty()).err().unwrap();
+			assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+			let err = Elf::from_bytes_strict(&[0x7f, 0x45, 0x4c, 0x46])
+				.err()
+				.unwrap();
+			assert_eq!(err.kind, ErrorKind::InsufficantSize);
+
+			let owned = OwnedElf::from_reader(RV32UI_P_ADD).unwrap();
+			assert!(matches!(owned.elf(), Elf::Elf32 { .. }));
+			assert_eq!(owned.into_bytes(), RV32UI_P_ADD);
+		}
+
+		#[test]
+		fn overflowing_table_offset() {
+			// `e_phoff` of the 32-bit header
+			let mut bytes = RV32UI_P_ADD.to_vec();
+			bytes[28..32].copy_from_slice(&0xffff_fff0_u32.to_le_bytes());
+
+			let err = Elf::from_bytes(&bytes).err().unwrap();
+			assert_eq!(err.kind, ErrorKind::InsufficantSize);
+
+			// `e_phoff` of the 64-bit header
+			let mut bytes = RV64UI_P_ADD.to_vec();
+			bytes[32..40].copy_from_slice(&(u64::MAX - 0x10).to_le_bytes());
+
+			let err = Elf::from_bytes(&bytes).err().unwrap();
+			assert_eq!(err.kind, ErrorKind::MalformedHeader);
+		}
+	}
 }
@@ -64,6 +64,30 @@ pub mod format {
 					}
 				}
 			}
+
+			// Reject reversed or out of bounds bit ranges at compile time.
+			const _: () = {
+				$(
+					$(
+						assert!(
+							$hi >= $lo,
+							concat!(
+								"`", stringify!($format), ".", stringify!($field),
+								"`: bit range ", stringify!($lo), ":", stringify!($hi),
+								" is reversed"
+							)
+						);
+						assert!(
+							$hi < <$size>::BITS,
+							concat!(
+								"`", stringify!($format), ".", stringify!($field),
+								"`: bit range ", stringify!($lo), ":", stringify!($hi),
+								" exceeds the instruction size"
+							)
+						);
+					)+
+				)+
+			};
 		}
 	}
 
@@ -73,30 +97,513 @@ pub mod format {
 	instruction_format!(u32 => FormatI( rd[7:11]: u8, rs1[15:19]: u8,                 imm[sign@31 => 20:31]: as i32 => i64));
 	instruction_format!(u32 => FormatS(               rs1[15:19]: u8, rs2[20:24]: u8, imm[sign@31 => 7:11 @ 0 | 25:31 @ 5]: as i32 => i64));
 	instruction_format!(u32 => FormatB(               rs1[15:19]: u8, rs2[20:24]: u8, imm[sign@31 => shl 1 => 8:11 @ 1 | 25:30 @ 5 | 7:7 @ 11 | 31:31 @ 12]: as i32 as i64 => u64));
-	instruction_format!(u32 => FormatU( rd[7:11]: u8,                                 imm[sign@31 => shl 12 => 12:31]: as i32 as i64 => u64));
+	instruction_format!(u32 => FormatU( rd[7:11]: u8,                                 imm[sign@31 => shl 12 => 12:31 @ 12]: as i32 as i64 => u64));
 	instruction_format!(u32 => FormatJ( rd[7:11]: u8,                                 imm[sign@31 => shl 1 => 21:30 @ 1 | 20:20 @ 11 | 12:19 @ 12 | 31:31 @ 20]: as i32 as i64 => u64));
 
 	instruction_format!(u32 => FormatR4(rd[7:11]: u8, rs1[15:19]: u8, rs2[20:24]: u8, rs3[27:31]: u8));
+
+	// Atomic memory operation (special `FormatR`): The upper bits of
+	// `funct7` select the operation, the lower two the memory ordering.
+	instruction_format!(u32 => FormatAmo(rd[7:11]: u8, rs1[15:19]: u8, rs2[20:24]: u8, rl[25:25]: u8, aq[26:26]: u8));
+
+	// Shift by immediate (special `FormatI`): The lower 6 bits of the
+	// immediate hold the shift amount (RV64) and are not sign extended; the
+	// upper 6 bits select the kind of shift.
+	// Control and status register access (special `FormatI`): The
+	// immediate holds the unsigned address of the csr. For the immediate
+	// variants `rs1` holds a 5-bit unsigned immediate.
+	instruction_format!(u32 => FormatCsr(rd[7:11]: u8, rs1[15:19]: u8, csr[20:31]: u16));
+
+	instruction_format!(u32 => FormatShift(rd[7:11]: u8, rs1[15:19]: u8, shamt[20:25]: u8, funct6[26:31]: u8));
 }
 
-use self::format::{FormatB, FormatI, FormatJ, FormatR, FormatS};
+/// Helpers which pack the fields of the base instruction formats into an
+/// instruction word (e.g. for tests).
+///
+/// Fields are truncated to their width, immediates are given as the value of
+/// the immediate (not as the raw bits).
+pub mod asm {
+	const fn base(opcode: u8, rd: u8, funct3: u8, rs1: u8, rs2: u8) -> u32 {
+		(opcode as u32 & 0x7f)
+			| ((rd as u32 & 0x1f) << 7)
+			| ((funct3 as u32 & 0x7) << 12)
+			| ((rs1 as u32 & 0x1f) << 15)
+			| ((rs2 as u32 & 0x1f) << 20)
+	}
+
+	pub const fn r_type(
+		opcode: u8,
+		rd: u8,
+		funct3: u8,
+		rs1: u8,
+		rs2: u8,
+		funct7: u8,
+	) -> u32 {
+		base(opcode, rd, funct3, rs1, rs2) | ((funct7 as u32 & 0x7f) << 25)
+	}
+
+	/// `imm`: 12 bit signed immediate.
+	pub const fn i_type(
+		opcode: u8,
+		rd: u8,
+		funct3: u8,
+		rs1: u8,
+		imm: i32,
+	) -> u32 {
+		base(opcode, rd, funct3, rs1, 0) | ((imm as u32 & 0xfff) << 20)
+	}
+
+	/// `imm`: 12 bit signed immediate.
+	pub const fn s_type(
+		opcode: u8,
+		funct3: u8,
+		rs1: u8,
+		rs2: u8,
+		imm: i32,
+	) -> u32 {
+		let imm = imm as u32;
+
+		base(opcode, 0, funct3, rs1, rs2)
+			| ((imm & 0x1f) << 7)
+			| (((imm >> 5) & 0x7f) << 25)
+	}
+
+	/// `imm`: 13 bit signed branch offset (multiple of 2).
+	pub const fn b_type(
+		opcode: u8,
+		funct3: u8,
+		rs1: u8,
+		rs2: u8,
+		imm: i32,
+	) -> u32 {
+		let imm = imm as u32;
+
+		base(opcode, 0, funct3, rs1, rs2)
+			| (((imm >> 11) & 0x1) << 7)
+			| (((imm >> 1) & 0xf) << 8)
+			| (((imm >> 5) & 0x3f) << 25)
+			| (((imm >> 12) & 0x1) << 31)
+	}
+
+	/// `imm`: Value of the upper 20 bits (`imm[31:12]`), the lower 12 bits
+	/// are ignored.
+	pub const fn u_type(opcode: u8, rd: u8, imm: i32) -> u32 {
+		base(opcode, rd, 0, 0, 0) | (imm as u32 & 0xffff_f000)
+	}
+
+	/// `imm`: 21 bit signed jump offset (multiple of 2).
+	pub const fn j_type(opcode: u8, rd: u8, imm: i32) -> u32 {
+		let imm = imm as u32;
+
+		base(opcode, rd, 0, 0, 0)
+			| (((imm >> 12) & 0xff) << 12)
+			| (((imm >> 11) & 0x1) << 20)
+			| (((imm >> 1) & 0x3ff) << 21)
+			| (((imm >> 20) & 0x1) << 31)
+	}
+}
+
+/// Returns `true` if `parcel` is the defined illegal instruction with
+/// `[15:0]` all zero.
+///
+/// Zeroed or uninitialized memory decodes to it, so fetching from such a
+/// region traps instead of executing anything.
+pub const fn is_illegal_parcel(parcel: u16) -> bool {
+	parcel == 0
+}
+
+/// Returns `true` if `word` is the defined illegal instruction with
+/// `[ILEN-1:0]` all one.
+pub const fn is_illegal_word(word: u32) -> bool {
+	word == u32::MAX
+}
+
+/// Expansion of compressed (`C` extension) instructions into their base
+/// instruction words.
+///
+/// Compressed instructions are 16 bit `parcels` whose lowest two bits are not
+/// `0b11`. Each of them has an equivalent base instruction, so they are
+/// executed by expanding them first.
+pub mod rvc {
+	use super::asm::{i_type, s_type};
+
+	const OP_IMM: u8 = 0b0010011;
+	const LOAD: u8 = 0b0000011;
+	const STORE: u8 = 0b0100011;
+	const JALR: u8 = 0b1100111;
+
+	/// Stack pointer (`x2`), implicit operand of the stack-relative
+	/// instructions.
+	const SP: u8 = 2;
+
+	/// Returns `true` if `parcel` is the start of a compressed instruction.
+	pub const fn is_compressed(parcel: u16) -> bool {
+		parcel & 0b11 != 0b11
+	}
+
+	/// Returns bits `hi..=lo` of `parcel`.
+	const fn bits(parcel: u16, hi: u32, lo: u32) -> u32 {
+		(parcel as u32 >> lo) & ((1 << (hi - lo + 1)) - 1)
+	}
+
+	/// Expands the compressed instruction `parcel` into its base instruction
+	/// word.
+	///
+	/// Returns `None` for unsupported or reserved encodings (e.g. the
+	/// all-zero parcel).
+	pub const fn expand(parcel: u16) -> Option<u32> {
+		let quadrant = bits(parcel, 1, 0);
+		let funct3 = bits(parcel, 15, 13);
+
+		match (quadrant, funct3) {
+			// C.ADDI4SPN: addi rd', x2, nzuimm[9:2]
+			(0b00, 0b000) => {
+				let rd = bits(parcel, 4, 2) as u8 + 8;
+				let imm = (bits(parcel, 12, 11) << 4)
+					| (bits(parcel, 10, 7) << 6)
+					| (bits(parcel, 6, 6) << 2)
+					| (bits(parcel, 5, 5) << 3);
+
+				// Reserved (also covers the all-zero parcel)
+				if imm == 0 {
+					return None;
+				}
+
+				Some(i_type(OP_IMM, rd, 0b000, SP, imm as i32))
+			}
+			// C.LWSP: lw rd, offset[7:2](x2)
+			(0b10, 0b010) => {
+				let rd = bits(parcel, 11, 7) as u8;
+				let imm = (bits(parcel, 12, 12) << 5)
+					| (bits(parcel, 6, 4) << 2)
+					| (bits(parcel, 3, 2) << 6);
+
+				// Reserved
+				if rd == 0 {
+					return None;
+				}
+
+				Some(i_type(LOAD, rd, 0b010, SP, imm as i32))
+			}
+			// C.LDSP (RV64): ld rd, offset[8:3](x2)
+			(0b10, 0b011) => {
+				let rd = bits(parcel, 11, 7) as u8;
+				let imm = (bits(parcel, 12, 12) << 5)
+					| (bits(parcel, 6, 5) << 3)
+					| (bits(parcel, 4, 2) << 6);
+
+				// Reserved
+				if rd == 0 {
+					return None;
+				}
+
+				Some(i_type(LOAD, rd, 0b011, SP, imm as i32))
+			}
+			// C.JR: jalr x0, 0(rs1)
+			// C.JALR: jalr x1, 0(rs1)
+			(0b10, 0b100) if bits(parcel, 6, 2) == 0 => {
+				let rs1 = bits(parcel, 11, 7) as u8;
+				let rd = bits(parcel, 12, 12) as u8;
+
+				// Reserved (`C.JR`) or `C.EBREAK` (`C.JALR`)
+				if rs1 == 0 {
+					return None;
+				}
+
+				Some(i_type(JALR, rd, 0b000, rs1, 0))
+			}
+			// C.SWSP: sw rs2, offset[7:2](x2)
+			(0b10, 0b110) => {
+				let rs2 = bits(parcel, 6, 2) as u8;
+				let imm =
+					(bits(parcel, 12, 9) << 2) | (bits(parcel, 8, 7) << 6);
+
+				Some(s_type(STORE, 0b010, SP, rs2, imm as i32))
+			}
+			// C.SDSP (RV64): sd rs2, offset[8:3](x2)
+			(0b10, 0b111) => {
+				let rs2 = bits(parcel, 6, 2) as u8;
+				let imm =
+					(bits(parcel, 12, 10) << 3) | (bits(parcel, 9, 7) << 6);
+
+				Some(s_type(STORE, 0b011, SP, rs2, imm as i32))
+			}
+			_ => None,
+		}
+	}
+}
+
+use std::collections::BTreeMap;
+
+use elf::elf::Elf;
+
+use self::format::{
+	FormatAmo, FormatB, FormatCsr, FormatI, FormatJ, FormatR, FormatR4,
+	FormatS, FormatShift,
+};
 use crate::adr::Addressable;
 use crate::cpu::Cpu;
+use crate::csr;
 use crate::ins::format::FormatU;
-use crate::reg::IntReg;
-use crate::shared::Address;
+use crate::reg::{FloatReg, IntReg, IntRegisters};
+use crate::shared::{Address, IntWidthU};
 use crate::tra::Trap;
 
 fn resolve_xreg(cpu: &mut Cpu, reg: u8) -> IntReg {
 	IntReg::try_from(reg).unwrap()
 }
 
+fn resolve_freg(cpu: &mut Cpu, reg: u8) -> FloatReg {
+	FloatReg::try_from(reg).unwrap()
+}
+
+/// Rounds `value` to an integral value with the rounding mode (`rm`) of
+/// `word`.
+///
+/// The dynamic rounding mode (`DYN`) uses the mode of the `frm` csr. Raises
+/// an illegal instruction trap for reserved rounding modes.
+fn round(cpu: &Cpu, word: u32, value: f64) -> Result<f64, Trap> {
+	let rm = match (word >> 12) & 0b111 {
+		0b111 => cpu.read_csr(csr::FRM) as u32 & 0b111,
+		rm => rm,
+	};
+
+	match rm {
+		// Round to nearest, ties to even
+		0b000 => Ok(value.round_ties_even()),
+		// Round towards zero
+		0b001 => Ok(value.trunc()),
+		// Round down
+		0b010 => Ok(value.floor()),
+		// Round up
+		0b011 => Ok(value.ceil()),
+		// Round to nearest, ties to max magnitude
+		0b100 => Ok(value.round()),
+		_ => Err(Trap::IllegalInstruction(word)),
+	}
+}
+
+/// Shared implementation of the float to integer conversions
+/// (`FCVT.{W,WU,L,LU}.{S,D}`) to an integer in `min..=max` which is `bits`
+/// wide.
+///
+/// Unlike `as` (which converts NaN to `0`), NaN converts to `max` and values
+/// out of range saturate to `min`/`max`, raising the invalid flag. 32-bit
+/// results are sign-extended, even for the unsigned conversions.
+///
+/// Single-precision values are stored widened to `f64` in the registers, so
+/// both precisions share this implementation.
+fn fcvt_to_int(
+	cpu: &mut Cpu,
+	word: u32,
+	min: i128,
+	max: i128,
+	bits: u32,
+) -> Result<(), Trap> {
+	let FormatR { rd, rs1, .. } = FormatR::parse(word);
+
+	let rs1 = resolve_freg(cpu, rs1);
+	let value = cpu.fregs.get(rs1);
+	let rounded = round(cpu, word, value)?;
+
+	let (result, flags) = if value.is_nan() {
+		(max, csr::FFLAGS_NV)
+	} else {
+		// Saturates at the bounds of `i128` (e.g. for infinity)
+		let result = rounded as i128;
+
+		if result < min || result > max {
+			(result.clamp(min, max), csr::FFLAGS_NV)
+		} else if rounded != value {
+			(result, csr::FFLAGS_NX)
+		} else {
+			(result, 0)
+		}
+	};
+
+	let fflags = cpu.read_csr(csr::FFLAGS);
+	// `fflags` is never read-only
+	let _ = cpu.write_csr(csr::FFLAGS, fflags | flags);
+
+	// Truncate to `bits` and sign-extend
+	let shift = 64 - bits;
+	let rd = resolve_xreg(cpu, rd);
+	cpu.xregs[rd] = ((result as u64) << shift) as i64 >> shift;
+
+	Ok(())
+}
+
+/// Computes the effective address `rs1 + imm` of a load, store, `AMO` or
+/// `JALR`.
+///
+/// The addition wraps around at the bounds of the address space (e.g.
+/// `0xffff_ffff_ffff_fffc + 8` is `0x4`), with negative immediates
+/// subtracting from the base address.
+pub const fn compute_address(rs1_value: i64, imm: i64) -> Address {
+	(rs1_value as Address).wrapping_add(imm as Address)
+}
+
+/// Shared implementation of the `CSRR*` instructions.
+///
+/// Writes `op(old, src)` to the csr, with `src` being the value of `rs1`
+/// (`uimm` for the immediate variants), and the old value of the csr to
+/// `rd`. The set/clear variants don't write the csr if `rs1` (`uimm`) is
+/// zero, so reading a read-only csr does not trap.
+///
+/// The access is checked against the permissions encoded in the csr address
+/// (see [`csr::check_access`]) before it is performed.
+fn csr_op(
+	cpu: &mut Cpu,
+	word: u32,
+	immediate: bool,
+	always_write: bool,
+	op: fn(u64, u64) -> u64,
+) -> Result<(), Trap> {
+	let FormatCsr { rd, rs1, csr } = FormatCsr::parse(word);
+
+	let src = if immediate {
+		rs1 as u64
+	} else {
+		let rs1 = resolve_xreg(cpu, rs1);
+		cpu.xregs[rs1] as u64
+	};
+
+	let write = always_write || rs1 != 0;
+	csr::check_access(csr, cpu.privilege(), write)
+		.map_err(|_| Trap::IllegalInstruction(word))?;
+
+	let old = cpu.read_csr(csr);
+
+	if write {
+		cpu.write_csr(csr, op(old, src))
+			.map_err(|_| Trap::IllegalInstruction(word))?;
+	}
+
+	let rd = resolve_xreg(cpu, rd);
+	cpu.xregs[rd] = old as i64;
+
+	Ok(())
+}
+
+/// Parses an `AMO` and returns `rd`, the source value and the address of the
+/// memory operand.
+///
+/// Raises a store/AMO address misaligned trap if the address is not aligned
+/// to `align` bytes.
+fn prepare_amo(
+	cpu: &mut Cpu,
+	word: u32,
+	align: Address,
+) -> Result<(IntReg, i64, Address), Trap> {
+	// With only a single hart all memory accesses are already ordered, so
+	// the ordering bits (`aq`/`rl`) can be ignored until multiple harts are
+	// supported.
+	let FormatAmo { rd, rs1, rs2, .. } = FormatAmo::parse(word);
+
+	let rd = resolve_xreg(cpu, rd);
+	let rs1 = resolve_xreg(cpu, rs1);
+	let rs2 = resolve_xreg(cpu, rs2);
+
+	let addr = compute_address(cpu.xregs[rs1], 0);
+
+	if !addr.is_multiple_of(align) {
+		return Err(Trap::StoreAddressMisaligned(addr));
+	}
+
+	Ok((rd, cpu.xregs[rs2], addr))
+}
+
+/// Loads the `size` bytes at `rs1` into `rd` (words are sign extended) and
+/// reserves them for a following store-conditional.
+fn load_reserved(cpu: &mut Cpu, word: u32, size: Address) -> Result<(), Trap> {
+	let FormatAmo { rd, rs1, .. } = FormatAmo::parse(word);
+
+	let rd = resolve_xreg(cpu, rd);
+	let rs1 = resolve_xreg(cpu, rs1);
+
+	let addr = compute_address(cpu.xregs[rs1], 0);
+
+	if !addr.is_multiple_of(size) {
+		return Err(Trap::LoadAddressMisaligned(addr));
+	}
+
+	let value = match size {
+		4 => cpu.load_u32(addr)? as i32 as i64,
+		_ => cpu.load_u64(addr)? as i64,
+	};
+
+	cpu.mmu.reserve(addr, size);
+	cpu.xregs[rd] = value;
+
+	Ok(())
+}
+
+/// Stores the lower `size` bytes of `rs2` at `rs1` if the reservation of a
+/// preceding load-reserved is still valid, writing `0` to `rd` on success
+/// and `1` on failure.
+///
+/// The reservation is invalidated in both cases.
+fn store_conditional(
+	cpu: &mut Cpu,
+	word: u32,
+	size: Address,
+) -> Result<(), Trap> {
+	let (rd, src, addr) = prepare_amo(cpu, word, size)?;
+
+	let success = cpu.mmu.take_reservation(addr, size);
+
+	if success {
+		match size {
+			4 => cpu.store_u32(addr, src as u32)?,
+			_ => cpu.store_u64(addr, src as u64)?,
+		}
+	}
+
+	cpu.xregs[rd] = if success { 0 } else { 1 };
+
+	Ok(())
+}
+
+/// Atomically applies `op` to the word at `rs1` and `rs2`, stores the result
+/// back and writes the original (sign extended) word to `rd`.
+fn amo_w(
+	cpu: &mut Cpu,
+	word: u32,
+	op: fn(i32, i32) -> i32,
+) -> Result<(), Trap> {
+	let (rd, src, addr) = prepare_amo(cpu, word, 4)?;
+
+	let value = cpu.load_u32(addr)? as i32;
+	cpu.store_u32(addr, op(value, src as i32) as u32)?;
+
+	cpu.xregs[rd] = value as i64;
+
+	Ok(())
+}
+
+/// Atomically applies `op` to the double word at `rs1` and `rs2`, stores the
+/// result back and writes the original double word to `rd`.
+fn amo_d(
+	cpu: &mut Cpu,
+	word: u32,
+	op: fn(i64, i64) -> i64,
+) -> Result<(), Trap> {
+	let (rd, src, addr) = prepare_amo(cpu, word, 8)?;
+
+	let value = cpu.load_u64(addr)? as i64;
+	cpu.store_u64(addr, op(value, src) as u64)?;
+
+	cpu.xregs[rd] = value;
+
+	Ok(())
+}
+
 // Currently either 32 or 16 bits
 //
 // # Illegal instructions:
 //
-// - [15:0] all 0
-// - [ILEN-1:0] all 1
+// - [15:0] all 0 (see `is_illegal_parcel`)
+// - [ILEN-1:0] all 1 (see `is_illegal_word`)
 //
 // # Construction
 //
@@ -116,8 +623,351 @@ pub struct Instruction {
 	pub(crate) reqd: u32,
 	pub(crate) name: &'static str,
 	pub(crate) extension: &'static str,
-	pub(crate) op:
-		fn(cpu: &mut Cpu, word: u32, address: Address) -> Result<(), Trap>,
+	pub(crate) format: InstructionFormat,
+	/// Runs the instruction `word` located at `address`, with `len` being
+	/// the length of the (possibly compressed) instruction in memory.
+	pub(crate) op: fn(
+		cpu: &mut Cpu,
+		word: u32,
+		address: Address,
+		len: Address,
+	) -> Result<(), Trap>,
+}
+
+/// Encoding format of an [`Instruction`] (see [`format`] for the parsers).
+///
+/// Variants of a format with a special meaning of some fields (e.g. shifts by
+/// immediate or csr accesses) are reported as the format they are based on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InstructionFormat {
+	R,
+	I,
+	S,
+	B,
+	U,
+	J,
+	R4,
+}
+
+impl Instruction {
+	pub const fn name(&self) -> &'static str {
+		self.name
+	}
+
+	pub const fn extension(&self) -> &'static str {
+		self.extension
+	}
+
+	pub const fn format(&self) -> InstructionFormat {
+		self.format
+	}
+
+	/// Returns `true` if there is any word which is matched by both `self`
+	/// and `other`.
+	///
+	/// This is the case if the required bits agree on all bits which are
+	/// constrained by both masks.
+	pub const fn overlaps(&self, other: &Self) -> bool {
+		let common = self.mask & other.mask;
+		self.reqd & common == other.reqd & common
+	}
+}
+
+/// Returns every entry of [`INSTRUCTIONS`] which matches `word`.
+///
+/// Decoding only uses the first match, this is meant for diagnosing
+/// ambiguities in the table.
+pub fn decode_all(word: u32) -> Vec<&'static Instruction> {
+	let instructions: &'static [Instruction] = &INSTRUCTIONS;
+
+	instructions.iter().filter(|inst| word & inst.mask == inst.reqd).collect()
+}
+
+/// Disassembles the (non-compressed) instruction `word` located at `addr`,
+/// e.g. `addi x1, x0, 5` or `jal x0, 0x80000048`.
+///
+/// The output follows `objdump -M no-aliases,numeric`: Registers are printed
+/// by number (`x`/`f`), csrs by address and branch/jump targets as absolute
+/// addresses. Operands fixed by the encoding (e.g. `rs2` of `FSQRT.S`) and
+/// rounding modes are omitted. Returns `None` if no instruction matches.
+pub fn disassemble(word: u32, addr: Address) -> Option<String> {
+	let instructions: &'static [Instruction] = &INSTRUCTIONS;
+	let inst =
+		instructions.iter().find(|inst| word & inst.mask == inst.reqd)?;
+
+	let name = inst.name.to_lowercase();
+	let operands = operands(inst, word, addr);
+
+	Some(if operands.is_empty() {
+		name
+	} else {
+		format!("{} {}", name, operands.join(", "))
+	})
+}
+
+/// Disassembles the section `name` of `elf` (e.g. `.text`) and returns the
+/// address and disassembly of each instruction (see [`disassemble`]).
+///
+/// Compressed instructions are shown as the base instruction they expand to.
+/// Undecodable parcels are shown as data (`.2byte`/`.4byte`). Returns an
+/// empty list if the section does not exist or has no data in the file.
+pub fn disassemble_section(elf: &Elf, name: &str) -> Vec<(u64, String)> {
+	let bytes = match elf {
+		Elf::Elf32 { bytes, .. } | Elf::Elf64 { bytes, .. } => *bytes,
+	};
+	let Some(sh) = elf.section_by_name(name) else {
+		return Vec::new();
+	};
+	let Some(data) = sh.try_extract_data(bytes) else {
+		return Vec::new();
+	};
+
+	let mut lines = Vec::new();
+	let mut offset = 0;
+
+	while offset < data.len() {
+		let addr = sh.sh_addr().wrapping_add(offset as u64);
+
+		let (line, len) = match core::ops::Index::index(data, offset..) {
+			&[lo, hi, ..]
+				if rvc::is_compressed(u16::from_le_bytes([lo, hi])) =>
+			{
+				let parcel = u16::from_le_bytes([lo, hi]);
+				let line = rvc::expand(parcel)
+					.and_then(|word| disassemble(word, addr))
+					.unwrap_or_else(|| format!(".2byte {:#06x}", parcel));
+				(line, 2)
+			}
+			&[b0, b1, b2, b3, ..] => {
+				let word = u32::from_le_bytes([b0, b1, b2, b3]);
+				let line = disassemble(word, addr)
+					.unwrap_or_else(|| format!(".4byte {:#010x}", word));
+				(line, 4)
+			}
+			&[lo, hi, ..] => {
+				(format!(".2byte {:#06x}", u16::from_le_bytes([lo, hi])), 2)
+			}
+			&[byte, ..] => (format!(".byte {:#04x}", byte), 1),
+			[] => break,
+		};
+
+		lines.push((addr, line));
+		offset += len;
+	}
+
+	lines
+}
+
+/// Formats the operands of `inst` for `word` located at `addr` (see
+/// [`disassemble`]).
+fn operands(inst: &Instruction, word: u32, addr: Address) -> Vec<String> {
+	const RD: u32 = 0x1f << 7;
+	const RS1: u32 = 0x1f << 15;
+	const RS2: u32 = 0x1f << 20;
+
+	let opcode = word & 0x7f;
+	let funct3 = (word >> 12) & 0b111;
+	let (rd_float, rs1_float, rs2_float) = float_operands(inst);
+	let reg = |float: bool, reg: u8| {
+		format!("{}{}", if float { 'f' } else { 'x' }, reg)
+	};
+	// Registers constrained by the encoding are not operands
+	let free = |field: u32| inst.mask & field != field;
+
+	match inst.format {
+		_ if inst.mask == u32::MAX => Vec::new(),
+		InstructionFormat::R if opcode == 0b0101111 => {
+			// Atomics: `rd, rs2, (rs1)` (`LR` has no `rs2`)
+			let FormatAmo { rd, rs1, rs2, .. } = FormatAmo::parse(word);
+			let mut operands = vec![reg(false, rd)];
+			if free(RS2) {
+				operands.push(reg(false, rs2));
+			}
+			operands.push(format!("({})", reg(false, rs1)));
+			operands
+		}
+		InstructionFormat::R => {
+			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
+			[(RD, rd_float, rd), (RS1, rs1_float, rs1), (RS2, rs2_float, rs2)]
+				.into_iter()
+				.filter(|&(field, _, _)| free(field))
+				.map(|(_, float, r)| reg(float, r))
+				.collect()
+		}
+		InstructionFormat::R4 => {
+			let FormatR4 { rd, rs1, rs2, rs3 } = FormatR4::parse(word);
+			[rd, rs1, rs2, rs3].into_iter().map(|r| reg(true, r)).collect()
+		}
+		InstructionFormat::I if opcode == 0b0001111 => Vec::new(),
+		InstructionFormat::I if opcode == 0b1110011 => {
+			let FormatCsr { rd, rs1, csr } = FormatCsr::parse(word);
+			// `funct3[2]` selects the 5-bit immediate variants
+			let src = if funct3 & 0b100 != 0 {
+				rs1.to_string()
+			} else {
+				reg(false, rs1)
+			};
+			vec![reg(false, rd), format!("{:#x}", csr), src]
+		}
+		InstructionFormat::I
+			if matches!(opcode, 0b0000011 | 0b0000111 | 0b1100111) =>
+		{
+			// Loads and `JALR`: `rd, imm(rs1)`
+			let FormatI { rd, rs1, imm } = FormatI::parse(word);
+			vec![reg(rd_float, rd), format!("{}({})", imm, reg(false, rs1))]
+		}
+		InstructionFormat::I if matches!(funct3, 0b001 | 0b101) => {
+			// Shifts by immediate
+			let FormatShift { rd, rs1, shamt, .. } = FormatShift::parse(word);
+			vec![reg(false, rd), reg(false, rs1), shamt.to_string()]
+		}
+		InstructionFormat::I => {
+			let FormatI { rd, rs1, imm } = FormatI::parse(word);
+			vec![reg(false, rd), reg(false, rs1), imm.to_string()]
+		}
+		InstructionFormat::S => {
+			let FormatS { rs1, rs2, imm } = FormatS::parse(word);
+			vec![reg(rs2_float, rs2), format!("{}({})", imm, reg(false, rs1))]
+		}
+		InstructionFormat::B => {
+			let FormatB { rs1, rs2, imm } = FormatB::parse(word);
+			let target = addr.wrapping_add(imm);
+			vec![reg(false, rs1), reg(false, rs2), format!("{:#x}", target)]
+		}
+		InstructionFormat::U => {
+			let FormatU { rd, imm } = FormatU::parse(word);
+			vec![reg(false, rd), ((imm >> 12) & 0xfffff).to_string()]
+		}
+		InstructionFormat::J => {
+			let FormatJ { rd, imm } = FormatJ::parse(word);
+			vec![reg(false, rd), format!("{:#x}", addr.wrapping_add(imm))]
+		}
+	}
+}
+
+/// Returns which of `rd`, `rs1` and `rs2` of `inst` are float registers.
+fn float_operands(inst: &Instruction) -> (bool, bool, bool) {
+	let name = inst.name;
+
+	if !inst.extension.ends_with('F') && !inst.extension.ends_with('D') {
+		return (false, false, false);
+	}
+
+	match name {
+		// Loads and stores use an integer base
+		"FLW" | "FLD" => (true, false, false),
+		"FSW" | "FSD" => (false, false, true),
+		// Integer results
+		_ if [
+			"FMV.X.", "FCVT.W", "FCVT.L", "FEQ.", "FLT.", "FLE.", "FCLASS.",
+		]
+		.iter()
+		.any(|prefix| name.starts_with(prefix)) =>
+		{
+			(false, true, true)
+		}
+		// Integer sources
+		_ if [
+			"FMV.W.X", "FMV.D.X", "FCVT.S.W", "FCVT.S.L", "FCVT.D.W",
+			"FCVT.D.L",
+		]
+		.iter()
+		.any(|prefix| name.starts_with(prefix)) =>
+		{
+			(true, false, false)
+		}
+		_ => (true, true, true),
+	}
+}
+
+/// Returns the entries of [`INSTRUCTIONS`] grouped by their extension (in
+/// table order).
+pub fn instructions_by_extension(
+) -> BTreeMap<&'static str, Vec<&'static Instruction>> {
+	let instructions: &'static [Instruction] = &INSTRUCTIONS;
+	let mut extensions: BTreeMap<_, Vec<_>> = BTreeMap::new();
+
+	for inst in instructions {
+		extensions.entry(inst.extension).or_default().push(inst);
+	}
+
+	extensions
+}
+
+/// Returns all pairs of `instructions` which can match the same word.
+///
+/// This is a structural check of the `mask`/`reqd` constraints and does not
+/// need to try every possible word.
+pub fn find_overlaps(
+	instructions: &[Instruction],
+) -> impl Iterator<Item = (&Instruction, &Instruction)> {
+	instructions.iter().enumerate().flat_map(move |(i, a)| {
+		instructions[i + 1..]
+			.iter()
+			.filter(move |b| a.overlaps(b))
+			.map(move |b| (a, b))
+	})
+}
+
+/// Reference implementation of the conditional branches (`BEQ` to `BGEU`).
+///
+/// Returns the `pc` expected after executing the branch `word` at `addr`
+/// with `next` as the address of the following instruction or `None` if
+/// `word` is not a conditional branch. Used to cross-check the branch
+/// handlers in debug builds.
+#[cfg(debug_assertions)]
+pub(crate) fn expected_branch_pc(
+	xregs: &IntRegisters,
+	word: u32,
+	addr: Address,
+	next: Address,
+) -> Option<Address> {
+	if word & 0b1111111 != 0b1100011 {
+		return None;
+	}
+
+	let FormatB { rs1, rs2, imm } = FormatB::parse(word);
+	let lhs = xregs.get(IntReg::try_from(rs1).ok()?);
+	let rhs = xregs.get(IntReg::try_from(rs2).ok()?);
+
+	let taken = match (word >> 12) & 0b111 {
+		0b000 => lhs == rhs,
+		0b001 => lhs != rhs,
+		0b100 => lhs < rhs,
+		0b101 => lhs >= rhs,
+		0b110 => (lhs as IntWidthU) < (rhs as IntWidthU),
+		0b111 => (lhs as IntWidthU) >= (rhs as IntWidthU),
+		_ => return None,
+	};
+
+	Some(if taken { addr.wrapping_add(imm) } else { next })
+}
+
+/// Returns the target of the branch or jump `word` located at `addr`
+/// (regardless of whether a conditional branch is taken) or `None` if `word`
+/// is no branch or jump.
+///
+/// The target of `JALR` depends on `xregs`, so it is only correct before the
+/// instruction is executed.
+pub fn jump_target(
+	xregs: &IntRegisters,
+	word: u32,
+	addr: Address,
+) -> Option<Address> {
+	match word & 0b1111111 {
+		// BRANCH
+		0b1100011 => Some(addr.wrapping_add(FormatB::parse(word).imm)),
+		// JAL
+		0b1101111 => Some(addr.wrapping_add(FormatJ::parse(word).imm)),
+		// JALR
+		0b1100111 => {
+			let FormatI { rs1, imm, .. } = FormatI::parse(word);
+			let base = xregs.get(IntReg::try_from(rs1).ok()?);
+
+			Some(compute_address(base, imm) & !1)
+		}
+		_ => None,
+	}
 }
 
 #[allow(
@@ -134,7 +984,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Load upper immediate
 		name: "LUI",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::U,
+		op: |cpu, word, _addr, _len| {
 			let FormatU { rd, imm } = FormatU::parse(word);
 			let rd = resolve_xreg(cpu, rd);
 
@@ -150,11 +1001,14 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Add upper immediate to pc
 		name: "AUIPC",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::U,
+		op: |cpu, word, addr, _len| {
 			let FormatU { rd, imm } = FormatU::parse(word);
 			let rd = resolve_xreg(cpu, rd);
 
-			cpu.xregs[rd] = cpu.pc.wrapping_add(imm) as i64;
+			// Relative to the address of the `AUIPC` itself (`cpu.pc`
+			// already points to the next instruction)
+			cpu.xregs[rd] = addr.wrapping_add(imm) as i64;
 
 			Ok(())
 		},
@@ -166,14 +1020,14 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Jump and link
 		name: "JAL",
 		extension: "RV32I",
-		op: |cpu, word, addr| {
+		format: InstructionFormat::J,
+		op: |cpu, word, addr, len| {
 			let FormatJ { rd, imm } = FormatJ::parse(word);
 			let rd = resolve_xreg(cpu, rd);
 
 			// TODO: add return-address prediciton? See spec page 21/39 bottom.
 
-			// TODO: Check that pc advanced (should be instr + 4).
-			cpu.xregs[rd] = cpu.pc as i64;
+			cpu.xregs[rd] = addr.wrapping_add(len) as i64;
 			cpu.pc = addr.wrapping_add(imm);
 
 			Ok(())
@@ -186,12 +1040,13 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Jump and link register
 		name: "JALR",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, addr, len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 			let rd = resolve_xreg(cpu, rd);
 			let rs1 = resolve_xreg(cpu, rs1);
 
-			let addr = (imm as u64).wrapping_add(cpu.xregs[rs1] as u64)
+			let target = compute_address(cpu.xregs[rs1], imm)
 				// Set least-significant bit to `0`
 				& !1;
 
@@ -199,9 +1054,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 			// when also processing the `C` extension. That's why the check is
 			// skipped here.
 
-			// TODO: Check that pc advanced (should be instr + 4).
-			cpu.xregs[rd] = cpu.pc as i64;
-			cpu.pc = addr;
+			cpu.xregs[rd] = addr.wrapping_add(len) as i64;
+			cpu.pc = target;
 
 			Ok(())
 		},
@@ -213,7 +1067,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Branch equal
 		name: "BEQ",
 		extension: "RV32I",
-		op: |cpu, word, addr| {
+		format: InstructionFormat::B,
+		op: |cpu, word, addr, _len| {
 			let FormatB { rs1, rs2, imm } = FormatB::parse(word);
 			let rs1 = resolve_xreg(cpu, rs1);
 			let rs2 = resolve_xreg(cpu, rs2);
@@ -232,7 +1087,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Branch not equal
 		name: "BNQ",
 		extension: "RV32I",
-		op: |cpu, word, addr| {
+		format: InstructionFormat::B,
+		op: |cpu, word, addr, _len| {
 			let FormatB { rs1, rs2, imm } = FormatB::parse(word);
 			let rs1 = resolve_xreg(cpu, rs1);
 			let rs2 = resolve_xreg(cpu, rs2);
@@ -251,7 +1107,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Branch less than
 		name: "BLT",
 		extension: "RV32I",
-		op: |cpu, word, addr| {
+		format: InstructionFormat::B,
+		op: |cpu, word, addr, _len| {
 			let FormatB { rs1, rs2, imm } = FormatB::parse(word);
 			let rs1 = resolve_xreg(cpu, rs1);
 			let rs2 = resolve_xreg(cpu, rs2);
@@ -267,15 +1124,16 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		//      imm     rs2   rs1   fn3 imm   op
 		mask: 0b0000000_00000_00000_111_00000_1111111,
 		reqd: 0b0000000_00000_00000_101_00000_1100011,
-		// Branch greater than
+		// Branch greater or equal
 		name: "BGE",
 		extension: "RV32I",
-		op: |cpu, word, addr| {
+		format: InstructionFormat::B,
+		op: |cpu, word, addr, _len| {
 			let FormatB { rs1, rs2, imm } = FormatB::parse(word);
 			let rs1 = resolve_xreg(cpu, rs1);
 			let rs2 = resolve_xreg(cpu, rs2);
 
-			if (cpu.xregs[rs1] as u64) > (cpu.xregs[rs2] as u64) {
+			if cpu.xregs[rs1] >= cpu.xregs[rs2] {
 				cpu.pc = addr.wrapping_add(imm);
 			}
 
@@ -289,12 +1147,13 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Branch less than unsigned
 		name: "BLTU",
 		extension: "RV32I",
-		op: |cpu, word, addr| {
+		format: InstructionFormat::B,
+		op: |cpu, word, addr, _len| {
 			let FormatB { rs1, rs2, imm } = FormatB::parse(word);
 			let rs1 = resolve_xreg(cpu, rs1);
 			let rs2 = resolve_xreg(cpu, rs2);
 
-			if (cpu.xregs[rs1] as u64) < (cpu.xregs[rs2] as u64) {
+			if (cpu.xregs[rs1] as IntWidthU) < (cpu.xregs[rs2] as IntWidthU) {
 				cpu.pc = addr.wrapping_add(imm);
 			}
 
@@ -305,15 +1164,16 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		//      imm     rs2   rs1   fn3 imm   op
 		mask: 0b0000000_00000_00000_111_00000_1111111,
 		reqd: 0b0000000_00000_00000_111_00000_1100011,
-		// Branch greater than unsigned
+		// Branch greater or equal unsigned
 		name: "BGEU",
 		extension: "RV32I",
-		op: |cpu, word, addr| {
+		format: InstructionFormat::B,
+		op: |cpu, word, addr, _len| {
 			let FormatB { rs1, rs2, imm } = FormatB::parse(word);
 			let rs1 = resolve_xreg(cpu, rs1);
 			let rs2 = resolve_xreg(cpu, rs2);
 
-			if (cpu.xregs[rs1] as u64) > (cpu.xregs[rs2] as u64) {
+			if (cpu.xregs[rs1] as IntWidthU) >= (cpu.xregs[rs2] as IntWidthU) {
 				cpu.pc = addr.wrapping_add(imm);
 			}
 
@@ -327,7 +1187,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Load byte
 		name: "LB",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -335,9 +1196,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 
 			let rs1_value = cpu.xregs[rs1];
 
-			let addr = (rs1_value as u64).wrapping_add(imm as u64);
+			let addr = compute_address(rs1_value, imm);
 
-			cpu.xregs[rd] = cpu.mmu.read_u8(addr)? as i8 as i64;
+			cpu.xregs[rd] = cpu.mmu.read_i8(addr)? as i64;
 
 			Ok(())
 		},
@@ -349,7 +1210,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Load half-word
 		name: "LH",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -357,9 +1219,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 
 			let rs1_value = cpu.xregs[rs1];
 
-			let addr = (rs1_value as u64).wrapping_add(imm as u64);
+			let addr = compute_address(rs1_value, imm);
 
-			cpu.xregs[rd] = cpu.mmu.read_u16_le(addr)? as i16 as i64;
+			cpu.xregs[rd] = cpu.load_u16(addr)? as i16 as i64;
 
 			Ok(())
 		},
@@ -371,7 +1233,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Load word
 		name: "LW",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -379,9 +1242,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 
 			let rs1_value = cpu.xregs[rs1];
 
-			let addr = (rs1_value as u64).wrapping_add(imm as u64);
+			let addr = compute_address(rs1_value, imm);
 
-			cpu.xregs[rd] = cpu.mmu.read_u32_le(addr)? as i32 as i64;
+			cpu.xregs[rd] = cpu.load_u32(addr)? as i32 as i64;
 
 			Ok(())
 		},
@@ -393,7 +1256,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Load byte unsigned
 		name: "LBU",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -401,7 +1265,7 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 
 			let rs1_value = cpu.xregs[rs1];
 
-			let addr = (rs1_value as u64).wrapping_add(imm as u64);
+			let addr = compute_address(rs1_value, imm);
 
 			cpu.xregs[rd] = cpu.mmu.read_u8(addr)? as i64;
 
@@ -415,7 +1279,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Load half-word unsigned
 		name: "LHU",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -423,9 +1288,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 
 			let rs1_value = cpu.xregs[rs1];
 
-			let addr = (rs1_value as u64).wrapping_add(imm as u64);
+			let addr = compute_address(rs1_value, imm);
 
-			cpu.xregs[rd] = cpu.mmu.read_u16_le(addr)? as i64;
+			cpu.xregs[rd] = cpu.load_u16(addr)? as i64;
 
 			Ok(())
 		},
@@ -437,7 +1302,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Store byte
 		name: "SB",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::S,
+		op: |cpu, word, _addr, _len| {
 			let FormatS { rs1, rs2, imm } = FormatS::parse(word);
 
 			let rs1 = resolve_xreg(cpu, rs1);
@@ -445,7 +1311,7 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 
 			let rs1_value = cpu.xregs[rs1];
 
-			let addr = (rs1_value as u64).wrapping_add(imm as u64);
+			let addr = compute_address(rs1_value, imm);
 
 			cpu.mmu.write_u8(addr, cpu.xregs[rs2] as u8)?;
 
@@ -459,7 +1325,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Store half-word
 		name: "SH",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::S,
+		op: |cpu, word, _addr, _len| {
 			let FormatS { rs1, rs2, imm } = FormatS::parse(word);
 
 			let rs1 = resolve_xreg(cpu, rs1);
@@ -467,9 +1334,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 
 			let rs1_value = cpu.xregs[rs1];
 
-			let addr = (rs1_value as u64).wrapping_add(imm as u64);
+			let addr = compute_address(rs1_value, imm);
 
-			cpu.mmu.write_u16_le(addr, cpu.xregs[rs2] as u16)?;
+			cpu.store_u16(addr, cpu.xregs[rs2] as u16)?;
 
 			Ok(())
 		},
@@ -481,7 +1348,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Store word
 		name: "SW",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::S,
+		op: |cpu, word, _addr, _len| {
 			let FormatS { rs1, rs2, imm } = FormatS::parse(word);
 
 			let rs1 = resolve_xreg(cpu, rs1);
@@ -489,9 +1357,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 
 			let rs1_value = cpu.xregs[rs1];
 
-			let addr = (rs1_value as u64).wrapping_add(imm as u64);
+			let addr = compute_address(rs1_value, imm);
 
-			cpu.mmu.write_u32_le(addr, cpu.xregs[rs2] as u32)?;
+			cpu.store_u32(addr, cpu.xregs[rs2] as u32)?;
 
 			Ok(())
 		},
@@ -503,7 +1371,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Add immediate
 		name: "ADDI",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -523,7 +1392,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Set less than immediate
 		name: "SLTI",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -547,7 +1417,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Set less than immediate unsigned
 		name: "SLTIU",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -555,7 +1426,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 
 			let rs1_value = cpu.xregs[rs1];
 
-			if (rs1_value as u64) < (imm as u64) {
+			// The immediate is sign extended first and then compared as
+			// unsigned.
+			if (rs1_value as IntWidthU) < (imm as IntWidthU) {
 				cpu.xregs[rd] = 1;
 			} else {
 				cpu.xregs[rd] = 0;
@@ -571,7 +1444,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Xor immediate
 		name: "XORI",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -591,7 +1465,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Or immediate
 		name: "ORI",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -611,7 +1486,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// And immediate
 		name: "ANDI",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -632,7 +1508,7 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000000_00000_00000_001_00000_0010011,
 		name: "SLLI",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		op: |cpu, word, _addr, _len| {
 			// FormatI (special)
 			Ok(())
 		},
@@ -643,7 +1519,7 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000000_00000_00000_101_00000_0010011,
 		name: "SRLI",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		op: |cpu, word, _addr, _len| {
 			// FormatI (special)
 			Ok(())
 		},
@@ -654,7 +1530,7 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0100000_00000_00000_101_00000_0010011,
 		name: "SRAI",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		op: |cpu, word, _addr, _len| {
 			// FormatI (special)
 			Ok(())
 		},
@@ -666,7 +1542,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000000_00000_00000_000_00000_0110011,
 		name: "ADD",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -687,7 +1564,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0100000_00000_00000_000_00000_0110011,
 		name: "SUB",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -709,7 +1587,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Shift left logical
 		name: "SLL",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -733,7 +1612,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Signed less than
 		name: "SLT",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -759,7 +1639,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Signed less than (unsigned)
 		name: "SLTU",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -769,7 +1650,7 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 			let rs1_value = cpu.xregs[rs1];
 			let rs2_value = cpu.xregs[rs2];
 
-			if (rs1_value as u64) < (rs2_value as u64) {
+			if (rs1_value as IntWidthU) < (rs2_value as IntWidthU) {
 				cpu.xregs[rd] = 1;
 			} else {
 				cpu.xregs[rd] = 0;
@@ -785,7 +1666,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Xor
 		name: "XOR",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -807,7 +1689,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Shift right logical
 		name: "SRL",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -831,7 +1714,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Shift right arithmetic (fill with sign bit instead of `0`)
 		name: "SRA",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -841,9 +1725,11 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 			let rs1_value = cpu.xregs[rs1];
 			let rs2_value = cpu.xregs[rs2];
 
-			// `rs2` is 5-bit long and thus can not exceed u32.
-			// TODO: check arithmetic shift
-			cpu.xregs[rd] = rs1_value.wrapping_shr(rs2_value as u32);
+			// Only the lower 6 bits of `rs2` hold the shift amount (RV64).
+			let shamt = (rs2_value & 0x3f) as u32;
+
+			// Arithmetic shift as `rs1_value` is signed.
+			cpu.xregs[rd] = rs1_value >> shamt;
 
 			Ok(())
 		},
@@ -855,7 +1741,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// Or
 		name: "OR",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -877,7 +1764,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		// And
 		name: "AND",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
 
 			let rd = resolve_xreg(cpu, rd);
@@ -898,7 +1786,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000_0000_0000_00000_000_00000_0001111,
 		name: "FENCE",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			// TODO: Impl (with one hart not needed)
 			Ok(())
 		},
@@ -909,13 +1798,10 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_000_00000_1110011,
 		name: "ECALL",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
-			// FormatI
-			let FormatI { rd, rs1, imm } = FormatI::parse(word);
-
-			// TODO: return trap depending on eei
-
-			Ok(())
+		format: InstructionFormat::I,
+		op: |_cpu, _word, _addr, _len| {
+			// Only machine mode is supported
+			Err(Trap::EnvironmentCallFromMMode)
 		},
 	},
 	Instruction {
@@ -924,7 +1810,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000001_00000_000_00000_1110011,
 		name: "EBREAK",
 		extension: "RV32I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			let FormatI { rd, rs1, imm } = FormatI::parse(word);
 
 			// TODO: return trap depending on eei
@@ -939,19 +1826,32 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_110_00000_0000011,
 		name: "LWU",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			// FormatI
 			Ok(())
 		},
 	},
 	Instruction {
 		//      imm          rs1   fn3 rd    op
-		mask: 0b111111111111_11111_111_11111_1111111,
+		mask: 0b000000000000_00000_111_00000_1111111,
 		reqd: 0b000000000000_00000_011_00000_0000011,
+		// Load double word
 		name: "LD",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatI
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			let FormatI { rd, rs1, imm } = FormatI::parse(word);
+
+			let rd = resolve_xreg(cpu, rd);
+			let rs1 = resolve_xreg(cpu, rs1);
+
+			let rs1_value = cpu.xregs[rs1];
+
+			let addr = compute_address(rs1_value, imm);
+
+			cpu.xregs[rd] = cpu.load_u64(addr)? as i64;
+
 			Ok(())
 		},
 	},
@@ -959,10 +1859,22 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		//      imm      rs2   rs1   fn3 imm   op
 		mask: 0b00000000_00000_00000_111_00000_1111111,
 		reqd: 0b00000000_00000_00000_011_00000_0100011,
+		// Store double word
 		name: "SD",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatS
+		format: InstructionFormat::S,
+		op: |cpu, word, _addr, _len| {
+			let FormatS { rs1, rs2, imm } = FormatS::parse(word);
+
+			let rs1 = resolve_xreg(cpu, rs1);
+			let rs2 = resolve_xreg(cpu, rs2);
+
+			let rs1_value = cpu.xregs[rs1];
+
+			let addr = compute_address(rs1_value, imm);
+
+			cpu.store_u64(addr, cpu.xregs[rs2] as u64)?;
+
 			Ok(())
 		},
 	},
@@ -972,8 +1884,18 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000_000000_00000_001_00000_0010011,
 		name: "SLLI",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatR
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			let FormatShift { rd, rs1, shamt, .. } = FormatShift::parse(word);
+
+			let rd = resolve_xreg(cpu, rd);
+			let rs1 = resolve_xreg(cpu, rs1);
+
+			let rs1_value = cpu.xregs[rs1];
+
+			cpu.xregs[rd] =
+				(rs1_value as u64).wrapping_shl(shamt as u32) as i64;
+
 			Ok(())
 		},
 	},
@@ -983,8 +1905,18 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000_000000_00000_101_00000_0010011,
 		name: "SRLI",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatR
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			let FormatShift { rd, rs1, shamt, .. } = FormatShift::parse(word);
+
+			let rd = resolve_xreg(cpu, rd);
+			let rs1 = resolve_xreg(cpu, rs1);
+
+			let rs1_value = cpu.xregs[rs1];
+
+			cpu.xregs[rd] =
+				(rs1_value as u64).wrapping_shr(shamt as u32) as i64;
+
 			Ok(())
 		},
 	},
@@ -994,8 +1926,18 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b010000_000000_00000_101_00000_0010011,
 		name: "SRAI",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatR
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			let FormatShift { rd, rs1, shamt, .. } = FormatShift::parse(word);
+
+			let rd = resolve_xreg(cpu, rd);
+			let rs1 = resolve_xreg(cpu, rs1);
+
+			let rs1_value = cpu.xregs[rs1];
+
+			// Arithmetic shift as `rs1_value` is signed.
+			cpu.xregs[rd] = rs1_value.wrapping_shr(shamt as u32);
+
 			Ok(())
 		},
 	},
@@ -1005,63 +1947,131 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_000_00000_0011011,
 		name: "ADDIW",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			// FormatI
 			Ok(())
 		},
 	},
 	Instruction {
-		//      fn7     shamt rs1   fn3 rd    op
-		mask: 0b1111111_00000_00000_111_00000_1111111,
-		reqd: 0b0000000_00000_00000_001_00000_0011011,
+		//      fn6    shamt  rs1   fn3 rd    op
+		mask: 0b111111_000000_00000_111_00000_1111111,
+		reqd: 0b000000_000000_00000_001_00000_0011011,
 		name: "SLLIW",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatR
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			let FormatShift { rd, rs1, shamt, .. } = FormatShift::parse(word);
+
+			// `shamt[5]` is reserved for the word shifts
+			if shamt & 0b100000 != 0 {
+				return Err(Trap::IllegalInstruction(word));
+			}
+
+			let rd = resolve_xreg(cpu, rd);
+			let rs1 = resolve_xreg(cpu, rs1);
+
+			let rs1_value = cpu.xregs[rs1];
+
+			cpu.xregs[rd] = ((rs1_value as u32) << shamt) as i32 as i64;
+
 			Ok(())
 		},
 	},
 	Instruction {
-		//      fn7     shamt rs1   fn3 rd    op
-		mask: 0b1111111_00000_00000_111_00000_1111111,
-		reqd: 0b0000000_00000_00000_101_00000_0011011,
+		//      fn6    shamt  rs1   fn3 rd    op
+		mask: 0b111111_000000_00000_111_00000_1111111,
+		reqd: 0b000000_000000_00000_101_00000_0011011,
 		name: "SRLIW",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatR
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			let FormatShift { rd, rs1, shamt, .. } = FormatShift::parse(word);
+
+			// `shamt[5]` is reserved for the word shifts
+			if shamt & 0b100000 != 0 {
+				return Err(Trap::IllegalInstruction(word));
+			}
+
+			let rd = resolve_xreg(cpu, rd);
+			let rs1 = resolve_xreg(cpu, rs1);
+
+			let rs1_value = cpu.xregs[rs1];
+
+			cpu.xregs[rd] = ((rs1_value as u32) >> shamt) as i32 as i64;
+
 			Ok(())
 		},
 	},
 	Instruction {
-		//      fn7     shamt rs1   fn3 rd    op
-		mask: 0b1111111_00000_00000_111_00000_1111111,
-		reqd: 0b0100000_00000_00000_101_00000_0011011,
+		//      fn6    shamt  rs1   fn3 rd    op
+		mask: 0b111111_000000_00000_111_00000_1111111,
+		reqd: 0b010000_000000_00000_101_00000_0011011,
 		name: "SRAIW",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatR
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			let FormatShift { rd, rs1, shamt, .. } = FormatShift::parse(word);
+
+			// `shamt[5]` is reserved for the word shifts
+			if shamt & 0b100000 != 0 {
+				return Err(Trap::IllegalInstruction(word));
+			}
+
+			let rd = resolve_xreg(cpu, rd);
+			let rs1 = resolve_xreg(cpu, rs1);
+
+			let rs1_value = cpu.xregs[rs1];
+
+			// Arithmetic shift as the lower word is signed.
+			cpu.xregs[rd] = ((rs1_value as i32) >> shamt) as i64;
+
 			Ok(())
 		},
 	},
 	Instruction {
-		//      fn7     shamt rs1   fn3 rd    op
+		//      fn7     rs2   rs1   fn3 rd    op
 		mask: 0b1111111_00000_00000_111_00000_1111111,
 		reqd: 0b0000000_00000_00000_000_00000_0111011,
 		name: "ADDW",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatR
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
+
+			let rd = resolve_xreg(cpu, rd);
+			let rs1 = resolve_xreg(cpu, rs1);
+			let rs2 = resolve_xreg(cpu, rs2);
+
+			let rs1_value = cpu.xregs[rs1] as i32;
+			let rs2_value = cpu.xregs[rs2] as i32;
+
+			// Wraps in 32 bits before the result is sign extended
+			cpu.xregs[rd] = rs1_value.wrapping_add(rs2_value) as i64;
+
 			Ok(())
 		},
 	},
 	Instruction {
-		//      fn7     shamt rs1   fn3 rd    op
+		//      fn7     rs2   rs1   fn3 rd    op
 		mask: 0b1111111_00000_00000_111_00000_1111111,
 		reqd: 0b0100000_00000_00000_000_00000_0111011,
 		name: "SUBW",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
-			// FormatR
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			let FormatR { rd, rs1, rs2 } = FormatR::parse(word);
+
+			let rd = resolve_xreg(cpu, rd);
+			let rs1 = resolve_xreg(cpu, rs1);
+			let rs2 = resolve_xreg(cpu, rs2);
+
+			let rs1_value = cpu.xregs[rs1] as i32;
+			let rs2_value = cpu.xregs[rs2] as i32;
+
+			// Wraps in 32 bits before the result is sign extended
+			cpu.xregs[rd] = rs1_value.wrapping_sub(rs2_value) as i64;
+
 			Ok(())
 		},
 	},
@@ -1071,7 +2081,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000000_00000_00000_001_00000_0111011,
 		name: "SLLW",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1082,7 +2093,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000000_00000_00000_101_00000_0111011,
 		name: "SRLW",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1093,7 +2105,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0100000_00000_00000_101_00000_0111011,
 		name: "SRAW",
 		extension: "RV64I",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1105,7 +2118,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_001_00000_0001111,
 		name: "FENCE.I",
 		extension: "Zifencei",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			// FormatI
 			Ok(())
 		},
@@ -1117,9 +2131,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_001_00000_1110011,
 		name: "CSRRW",
 		extension: "Zicsr",
-		op: |cpu, word, _addr| {
-			// FormatI
-			Ok(())
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			csr_op(cpu, word, false, true, |_, src| src)
 		},
 	},
 	Instruction {
@@ -1128,9 +2142,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_010_00000_1110011,
 		name: "CSRRS",
 		extension: "Zicsr",
-		op: |cpu, word, _addr| {
-			// FormatI
-			Ok(())
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			csr_op(cpu, word, false, false, |old, src| old | src)
 		},
 	},
 	Instruction {
@@ -1139,9 +2153,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_011_00000_1110011,
 		name: "CSRRC",
 		extension: "Zicsr",
-		op: |cpu, word, _addr| {
-			// FormatI
-			Ok(())
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			csr_op(cpu, word, false, false, |old, src| old & !src)
 		},
 	},
 	Instruction {
@@ -1150,9 +2164,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_101_00000_1110011,
 		name: "CSRRWI",
 		extension: "Zicsr",
-		op: |cpu, word, _addr| {
-			// FormatI
-			Ok(())
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			csr_op(cpu, word, true, true, |_, src| src)
 		},
 	},
 	Instruction {
@@ -1161,9 +2175,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_110_00000_1110011,
 		name: "CSRRSI",
 		extension: "Zicsr",
-		op: |cpu, word, _addr| {
-			// FormatI
-			Ok(())
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			csr_op(cpu, word, true, false, |old, src| old | src)
 		},
 	},
 	Instruction {
@@ -1172,9 +2186,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_111_00000_1110011,
 		name: "CSRRCI",
 		extension: "Zicsr",
-		op: |cpu, word, _addr| {
-			// FormatI
-			Ok(())
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
+			csr_op(cpu, word, true, false, |old, src| old & !src)
 		},
 	},
 	// RV32M
@@ -1184,7 +2198,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_000_00000_0110011,
 		name: "MUL",
 		extension: "RV32M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1195,7 +2210,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_001_00000_0110011,
 		name: "MULH",
 		extension: "RV32M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1206,7 +2222,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_010_00000_0110011,
 		name: "MULHSU",
 		extension: "RV32M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1217,7 +2234,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_011_00000_0110011,
 		name: "MULHU",
 		extension: "RV32M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1228,7 +2246,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_100_00000_0110011,
 		name: "DIV",
 		extension: "RV32M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1239,7 +2258,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_101_00000_0110011,
 		name: "DIVU",
 		extension: "RV32M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1250,7 +2270,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_110_00000_0110011,
 		name: "REM",
 		extension: "RV32M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1261,7 +2282,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_111_00000_0110011,
 		name: "REMU",
 		extension: "RV32M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1273,7 +2295,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_000_00000_0111011,
 		name: "MULW",
 		extension: "RV64M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1284,7 +2307,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_100_00000_0111011,
 		name: "DIVW",
 		extension: "RV64M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1295,7 +2319,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_101_00000_0111011,
 		name: "DIVUW",
 		extension: "RV64M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1306,7 +2331,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_110_00000_0111011,
 		name: "REMW",
 		extension: "RV64M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1317,7 +2343,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_111_00000_0111011,
 		name: "REMUW",
 		extension: "RV64M",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1327,12 +2354,10 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		//      fn7       rs2   rs1   fn3 rd    op
 		mask: 0b11111_0_0_11111_00000_111_00000_1111111,
 		reqd: 0b00010_0_0_00000_00000_010_00000_0101111,
-		name: "LR.W",
-		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
-		},
+		name: "LR.W",
+		extension: "RV32A",
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| load_reserved(cpu, word, 4),
 	},
 	Instruction {
 		//      fn7       rs2   rs1   fn3 rd    op
@@ -1340,10 +2365,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00011_0_0_00000_00000_010_00000_0101111,
 		name: "SC.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
-		},
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| store_conditional(cpu, word, 4),
 	},
 	Instruction {
 		//      fn7       rs2   rs1   fn3 rd    op
@@ -1351,10 +2374,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00001_0_0_00000_00000_010_00000_0101111,
 		name: "AMOSWAP.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
-		},
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| amo_w(cpu, word, |_, src| src),
 	},
 	Instruction {
 		//      fn7       rs2   rs1   fn3 rd    op
@@ -1362,9 +2383,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_0_0_00000_00000_010_00000_0101111,
 		name: "AMOADD.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_w(cpu, word, |value, src| value.wrapping_add(src))
 		},
 	},
 	Instruction {
@@ -1373,9 +2394,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00100_0_0_00000_00000_010_00000_0101111,
 		name: "AMOXOR.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_w(cpu, word, |value, src| value ^ src)
 		},
 	},
 	Instruction {
@@ -1384,9 +2405,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b01100_0_0_00000_00000_010_00000_0101111,
 		name: "AMOAND.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_w(cpu, word, |value, src| value & src)
 		},
 	},
 	Instruction {
@@ -1395,9 +2416,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b01000_0_0_00000_00000_010_00000_0101111,
 		name: "AMOOR.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_w(cpu, word, |value, src| value | src)
 		},
 	},
 	Instruction {
@@ -1406,9 +2427,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b10000_0_0_00000_00000_010_00000_0101111,
 		name: "AMOMIN.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_w(cpu, word, |value, src| value.min(src))
 		},
 	},
 	Instruction {
@@ -1417,9 +2438,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b10100_0_0_00000_00000_010_00000_0101111,
 		name: "AMOMAX.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_w(cpu, word, |value, src| value.max(src))
 		},
 	},
 	Instruction {
@@ -1428,9 +2449,11 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b11000_0_0_00000_00000_010_00000_0101111,
 		name: "AMOMINU.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_w(cpu, word, |value, src| {
+				(value as u32).min(src as u32) as i32
+			})
 		},
 	},
 	Instruction {
@@ -1439,9 +2462,11 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b11100_0_0_00000_00000_010_00000_0101111,
 		name: "AMOMAXU.W",
 		extension: "RV32A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_w(cpu, word, |value, src| {
+				(value as u32).max(src as u32) as i32
+			})
 		},
 	},
 	// RV64A
@@ -1451,10 +2476,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00010_0_0_00000_00000_011_00000_0101111,
 		name: "LR.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
-		},
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| load_reserved(cpu, word, 8),
 	},
 	Instruction {
 		//      fn7       rs2   rs1   fn3 rd    op
@@ -1462,10 +2485,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00011_0_0_00000_00000_011_00000_0101111,
 		name: "SC.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
-		},
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| store_conditional(cpu, word, 8),
 	},
 	Instruction {
 		//      fn7       rs2   rs1   fn3 rd    op
@@ -1473,10 +2494,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00001_0_0_00000_00000_011_00000_0101111,
 		name: "AMOSWAP.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
-		},
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| amo_d(cpu, word, |_, src| src),
 	},
 	Instruction {
 		//      fn7       rs2   rs1   fn3 rd    op
@@ -1484,9 +2503,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_0_0_00000_00000_011_00000_0101111,
 		name: "AMOADD.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_d(cpu, word, |value, src| value.wrapping_add(src))
 		},
 	},
 	Instruction {
@@ -1495,9 +2514,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00100_0_0_00000_00000_011_00000_0101111,
 		name: "AMOXOR.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_d(cpu, word, |value, src| value ^ src)
 		},
 	},
 	Instruction {
@@ -1506,9 +2525,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b01100_0_0_00000_00000_011_00000_0101111,
 		name: "AMOAND.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_d(cpu, word, |value, src| value & src)
 		},
 	},
 	Instruction {
@@ -1517,9 +2536,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b01000_0_0_00000_00000_011_00000_0101111,
 		name: "AMOOR.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_d(cpu, word, |value, src| value | src)
 		},
 	},
 	Instruction {
@@ -1528,9 +2547,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b10000_0_0_00000_00000_011_00000_0101111,
 		name: "AMOMIN.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_d(cpu, word, |value, src| value.min(src))
 		},
 	},
 	Instruction {
@@ -1539,9 +2558,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b10100_0_0_00000_00000_011_00000_0101111,
 		name: "AMOMAX.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_d(cpu, word, |value, src| value.max(src))
 		},
 	},
 	Instruction {
@@ -1550,9 +2569,11 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b11000_0_0_00000_00000_011_00000_0101111,
 		name: "AMOMINU.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_d(cpu, word, |value, src| {
+				(value as IntWidthU).min(src as IntWidthU) as i64
+			})
 		},
 	},
 	Instruction {
@@ -1561,9 +2582,11 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b11100_0_0_00000_00000_011_00000_0101111,
 		name: "AMOMAXU.D",
 		extension: "RV64A",
-		op: |cpu, word, _addr| {
-			// FormatR
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			amo_d(cpu, word, |value, src| {
+				(value as IntWidthU).max(src as IntWidthU) as i64
+			})
 		},
 	},
 	// RV32F
@@ -1573,7 +2596,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_010_00000_0000111,
 		name: "FLW",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			// FormatI
 			Ok(())
 		},
@@ -1584,7 +2608,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000000_00000_00000_010_00000_0100111,
 		name: "FSW",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::S,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1595,7 +2620,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_00_00000_00000_000_00000_1000011,
 		name: "FMADD.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R4,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1606,7 +2632,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_00_00000_00000_000_00000_1000111,
 		name: "FMSUB.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R4,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1617,7 +2644,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_00_00000_00000_000_00000_1001011,
 		name: "FNMSUB.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R4,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1628,7 +2656,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_00_00000_00000_000_00000_1001111,
 		name: "FNMADD.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R4,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1639,7 +2668,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000000_00000_00000_000_00000_1010011,
 		name: "FADD.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1650,7 +2680,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000100_00000_00000_000_00000_1010011,
 		name: "FSUB.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1661,7 +2692,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0001000_00000_00000_000_00000_1010011,
 		name: "FMUL.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1672,7 +2704,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0001100_00000_00000_000_00000_1010011,
 		name: "FDIV.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1683,7 +2716,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0101100_00000_00000_000_00000_1010011,
 		name: "FSQRT.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1694,7 +2728,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010000_00000_00000_000_00000_1010011,
 		name: "FSGNJ.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1705,7 +2740,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010000_00000_00000_001_00000_1010011,
 		name: "FSGNJN.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1716,7 +2752,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010000_00000_00000_010_00000_1010011,
 		name: "FSGNJX.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1727,7 +2764,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010100_00000_00000_000_00000_1010011,
 		name: "FMIN.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1738,7 +2776,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010100_00000_00000_001_00000_1010011,
 		name: "FMAX.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1749,9 +2788,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1100000_00000_00000_000_00000_1010011,
 		name: "FCVT.W.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
-			// FormatS
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			fcvt_to_int(cpu, word, i32::MIN as i128, i32::MAX as i128, 32)
 		},
 	},
 	Instruction {
@@ -1760,9 +2799,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1100000_00001_00000_000_00000_1010011,
 		name: "FCVT.WU.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
-			// FormatS
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			fcvt_to_int(cpu, word, 0, u32::MAX as i128, 32)
 		},
 	},
 	Instruction {
@@ -1771,7 +2810,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1110000_00000_00000_000_00000_1010011,
 		name: "FMV.X.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1782,7 +2822,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1010000_00000_00000_010_00000_1010011,
 		name: "FEQ.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1793,7 +2834,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1010000_00000_00000_001_00000_1010011,
 		name: "FLT.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1804,7 +2846,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1010000_00000_00000_000_00000_1010011,
 		name: "FLE.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1815,7 +2858,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1110000_00000_00000_001_00000_1010011,
 		name: "FCLASS.S",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1826,7 +2870,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1101000_00000_00000_000_00000_1010011,
 		name: "FCVT.S.W",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1837,7 +2882,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1101000_00001_00000_000_00000_1010011,
 		name: "FCVT.S.WU",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1848,7 +2894,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1111000_00000_00000_000_00000_1010011,
 		name: "FMV.W.X",
 		extension: "RV32F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1860,9 +2907,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1100000_00010_00000_000_00000_1010011,
 		name: "FCVT.L.S",
 		extension: "RV64F",
-		op: |cpu, word, _addr| {
-			// FormatS
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			fcvt_to_int(cpu, word, i64::MIN as i128, i64::MAX as i128, 64)
 		},
 	},
 	Instruction {
@@ -1871,9 +2918,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1100000_00011_00000_000_00000_1010011,
 		name: "FCVT.LU.S",
 		extension: "RV64F",
-		op: |cpu, word, _addr| {
-			// FormatS
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			fcvt_to_int(cpu, word, 0, u64::MAX as i128, 64)
 		},
 	},
 	Instruction {
@@ -1882,7 +2929,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1101000_00010_00000_000_00000_1010011,
 		name: "FCVT.S.L",
 		extension: "RV64F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1893,7 +2941,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1101000_00011_00000_000_00000_1010011,
 		name: "FCVT.S.LU",
 		extension: "RV64F",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1905,7 +2954,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b000000000000_00000_011_00000_0000111,
 		name: "FLD",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			// FormatI
 			Ok(())
 		},
@@ -1916,7 +2966,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000000_00000_00000_011_00000_0100111,
 		name: "FSD",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::S,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1927,7 +2978,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_01_00000_00000_000_00000_1000011,
 		name: "FMADD.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R4,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1938,7 +2990,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_01_00000_00000_000_00000_1000111,
 		name: "FMSUB.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R4,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1949,7 +3002,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_01_00000_00000_000_00000_1001011,
 		name: "FNMSUB.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R4,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1960,7 +3014,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b00000_01_00000_00000_000_00000_1001111,
 		name: "FNMADD.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R4,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -1971,7 +3026,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000001_00000_00000_000_00000_1010011,
 		name: "FADD.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1982,7 +3038,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0000101_00000_00000_000_00000_1010011,
 		name: "FSUB.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -1993,7 +3050,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0001001_00000_00000_000_00000_1010011,
 		name: "FMUL.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2004,7 +3062,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0001101_00000_00000_000_00000_1010011,
 		name: "FDIV.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2015,7 +3074,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0101101_00000_00000_000_00000_1010011,
 		name: "FSQRT.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2026,7 +3086,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010001_00000_00000_000_00000_1010011,
 		name: "FSGNJ.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2037,7 +3098,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010001_00000_00000_001_00000_1010011,
 		name: "FSGNJN.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2048,7 +3110,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010001_00000_00000_010_00000_1010011,
 		name: "FSGNJX.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2059,7 +3122,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010101_00000_00000_000_00000_1010011,
 		name: "FMIN.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2070,7 +3134,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0010101_00000_00000_001_00000_1010011,
 		name: "FMAX.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2081,7 +3146,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0100000_00001_00000_000_00000_1010011,
 		name: "FCVT.S.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2092,7 +3158,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0100001_00000_00000_000_00000_1010011,
 		name: "FCVT.D.S",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2103,7 +3170,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1010001_00000_00000_010_00000_1010011,
 		name: "FEQ.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2114,7 +3182,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1010001_00000_00000_001_00000_1010011,
 		name: "FLT.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2125,7 +3194,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1010001_00000_00000_000_00000_1010011,
 		name: "FLE.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2136,7 +3206,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1110001_00000_00000_001_00000_1010011,
 		name: "FCLASS.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2147,9 +3218,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1100001_00000_00000_000_00000_1010011,
 		name: "FCVT.W.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
-			// FormatS
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			fcvt_to_int(cpu, word, i32::MIN as i128, i32::MAX as i128, 32)
 		},
 	},
 	Instruction {
@@ -2158,9 +3229,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1100001_00001_00000_000_00000_1010011,
 		name: "FCVT.WU.D",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
-			// FormatS
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			fcvt_to_int(cpu, word, 0, u32::MAX as i128, 32)
 		},
 	},
 	Instruction {
@@ -2169,7 +3240,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1101001_00000_00000_000_00000_1010011,
 		name: "FCVT.D.W",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2180,7 +3252,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1101001_00001_00000_000_00000_1010011,
 		name: "FCVT.D.WU",
 		extension: "RV32D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2192,9 +3265,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1100001_00010_00000_000_00000_1010011,
 		name: "FCVT.L.D",
 		extension: "RV64D",
-		op: |cpu, word, _addr| {
-			// FormatS
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			fcvt_to_int(cpu, word, i64::MIN as i128, i64::MAX as i128, 64)
 		},
 	},
 	Instruction {
@@ -2203,9 +3276,9 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1100001_00011_00000_000_00000_1010011,
 		name: "FCVT.LU.D",
 		extension: "RV64D",
-		op: |cpu, word, _addr| {
-			// FormatS
-			Ok(())
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
+			fcvt_to_int(cpu, word, 0, u64::MAX as i128, 64)
 		},
 	},
 	Instruction {
@@ -2214,7 +3287,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1110001_00000_00000_000_00000_1010011,
 		name: "FMV.X.D",
 		extension: "RV64D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2225,7 +3299,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1101001_00010_00000_000_00000_1010011,
 		name: "FCVT.D.L",
 		extension: "RV64D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2236,7 +3311,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1101001_00011_00000_000_00000_1010011,
 		name: "FCVT.D.LU",
 		extension: "RV64D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2247,7 +3323,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b1111001_00000_00000_000_00000_1010011,
 		name: "FMV.D.X",
 		extension: "RV64D",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::R,
+		op: |cpu, word, _addr, _len| {
 			// FormatS
 			Ok(())
 		},
@@ -2259,7 +3336,8 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0001000_00010_00000_000_00000_1110011,
 		name: "SRET",
 		extension: "Privileged",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, word, _addr, _len| {
 			// FormatR
 			Ok(())
 		},
@@ -2270,8 +3348,13 @@ pub const INSTRUCTIONS: [Instruction; 158] = [
 		reqd: 0b0011000_00010_00000_000_00000_1110011,
 		name: "MRET",
 		extension: "Privileged",
-		op: |cpu, word, _addr| {
+		format: InstructionFormat::I,
+		op: |cpu, _word, _addr, _len| {
 			// FormatR
+			// Only machine mode is supported, so there is no privilege to
+			// restore
+			cpu.pc = cpu.read_csr(csr::MEPC);
+
 			Ok(())
 		},
 	},
@@ -2290,6 +3373,293 @@ fn decode() {
 	}
 }
 
+#[test]
+fn asm() {
+	use self::asm::*;
+	use self::format::{FormatB, FormatI, FormatJ, FormatS};
+
+	fn decode(word: u32) -> &'static str {
+		INSTRUCTIONS.iter().find(|i| word & i.mask == i.reqd).unwrap().name
+	}
+
+	// add a0, a1, a2
+	assert_eq!(r_type(0b0110011, 10, 0b000, 11, 12, 0b0000000), 0x00c5_8533);
+	assert_eq!(decode(r_type(0b0110011, 10, 0b000, 11, 12, 0b0000000)), "ADD");
+	assert_eq!(decode(r_type(0b0110011, 10, 0b000, 11, 12, 0b0100000)), "SUB");
+	// amoadd.d a2, a1, (a0)
+	assert_eq!(r_type(0b0101111, 12, 0b011, 10, 11, 0b0000000), 0x00b5_362f);
+	assert_eq!(decode(r_type(0b0101111, 12, 0b011, 10, 11, 0)), "AMOADD.D");
+
+	// addi a0, a1, -3
+	let word = i_type(0b0010011, 10, 0b000, 11, -3);
+	assert_eq!(word, 0xffd5_8513);
+	assert_eq!(decode(word), "ADDI");
+	assert_eq!(FormatI::parse(word).imm, -3);
+	assert_eq!(decode(i_type(0b0000011, 10, 0b010, 11, 8)), "LW");
+
+	// sd a1, -0x7f8(a0)
+	let word = s_type(0b0100011, 0b011, 10, 11, -0x7f8);
+	assert_eq!(word, 0x80b5_3423);
+	assert_eq!(decode(word), "SD");
+	assert_eq!(FormatS::parse(word).imm, -0x7f8);
+
+	// bne a0, a1, -8
+	let word = b_type(0b1100011, 0b001, 10, 11, -8);
+	assert_eq!(word, 0xfeb5_1ce3);
+	assert_eq!(decode(word), "BNQ");
+	assert_eq!(FormatB::parse(word).imm as i64, -8);
+	assert_eq!(FormatB::parse(b_type(0b1100011, 0, 1, 2, 4094)).imm, 4094);
+
+	// lui a0, 0x12345
+	let word = u_type(0b0110111, 10, 0x1234_5000);
+	assert_eq!(word, 0x1234_5537);
+	assert_eq!(decode(word), "LUI");
+	assert_eq!(decode(u_type(0b0010111, 10, 0x1000)), "AUIPC");
+
+	// jal ra, -0x12344
+	let word = j_type(0b1101111, 1, -0x12344);
+	assert_eq!(word, 0xcbde_d0ef);
+	assert_eq!(decode(word), "JAL");
+	assert_eq!(FormatJ::parse(word).imm as i64, -0x12344);
+}
+
+#[test]
+fn format_immediates() {
+	use self::format::{FormatB, FormatI, FormatJ, FormatS};
+
+	// addi a0, a1, -3
+	assert_eq!(FormatI::parse(0xffd5_8513).imm, -3);
+	// sd a1, -0x7f8(a0)
+	assert_eq!(FormatS::parse(0x80b5_3423).imm, -0x7f8);
+	// beq a0, a1, 0xaaa
+	assert_eq!(FormatB::parse(0x2ab5_05e3).imm, 0xaaa);
+	// jal ra, 0x5554a
+	assert_eq!(FormatJ::parse(0x54a5_50ef).imm, 0x5554a);
+}
+
+#[test]
+fn format_b_immediate() {
+	use self::format::FormatB;
+
+	// bne a0, a1, -8
+	let b = FormatB::parse(0xfeb5_1ce3);
+	assert_eq!((b.rs1, b.rs2), (10, 11));
+	assert_eq!(b.imm as i64, -8);
+
+	// blt a0, a1, -4096 (smallest offset)
+	assert_eq!(FormatB::parse(0x80b5_4063).imm as i64, -4096);
+
+	// bge a0, a1, 4094 (largest offset)
+	assert_eq!(FormatB::parse(0x7eb5_5fe3).imm as i64, 4094);
+}
+
+#[test]
+fn format_j_immediate() {
+	use self::format::FormatJ;
+
+	// jal ra, -0x12344
+	let j = FormatJ::parse(0xcbde_d0ef);
+	assert_eq!(j.rd, 1);
+	assert_eq!(j.imm as i64, -0x12344);
+
+	// jal zero, -0x100000 (smallest offset)
+	assert_eq!(FormatJ::parse(0x8000_006f).imm as i64, -0x10_0000);
+
+	// jal zero, 2048 (only imm[11] set)
+	assert_eq!(FormatJ::parse(0x0010_006f).imm as i64, 2048);
+}
+
+#[test]
+fn format_u_immediate() {
+	use self::format::FormatU;
+
+	// lui a0, 0x12345
+	let u = FormatU::parse(0x1234_5537);
+	assert_eq!(u.rd, 10);
+	assert_eq!(u.imm, 0x1234_5000);
+
+	// lui a0, 0xfffff (sign bit set)
+	assert_eq!(FormatU::parse(0xffff_f537).imm, 0xffff_ffff_ffff_f000);
+
+	// lui a0, 0x80000 (only the sign bit set)
+	assert_eq!(FormatU::parse(0x8000_0537).imm, 0xffff_ffff_8000_0000);
+
+	// lui a0, 0x7ffff (largest positive)
+	assert_eq!(FormatU::parse(0x7fff_f537).imm, 0x7fff_f000);
+}
+
+#[test]
+fn lui_auipc() {
+	use self::asm::u_type;
+
+	const LUI: u8 = 0b0110111;
+	const AUIPC: u8 = 0b0010111;
+
+	let mut cpu = Cpu::default();
+
+	execute(&mut cpu, "LUI", u_type(LUI, 10, 0x1234_5000)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x10), 0x1234_5000);
+
+	// Sign extended from bit 31 into the upper 32 bits
+	execute(&mut cpu, "LUI", u_type(LUI, 10, 0xffff_f000_u32 as i32)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x10) as u64, 0xffff_ffff_ffff_f000);
+
+	execute(&mut cpu, "LUI", u_type(LUI, 10, i32::MIN)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x10) as u64, 0xffff_ffff_8000_0000);
+
+	// Relative to the address of the instruction, not the advanced `pc`
+	cpu.pc = 0x8000_1004;
+	execute_at(&mut cpu, "AUIPC", u_type(AUIPC, 10, 0x1000), 0x8000_1000)
+		.unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x10), 0x8000_2000);
+
+	execute_at(
+		&mut cpu,
+		"AUIPC",
+		u_type(AUIPC, 10, 0xffff_f000_u32 as i32),
+		0x8000_1000,
+	)
+	.unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x10), 0x8000_0000);
+}
+
+#[test]
+fn rvc_expand() {
+	use self::rvc::{expand, is_compressed};
+
+	// (parcel, expanded base instruction)
+	let cases = [
+		// c.addi4spn a0, sp, 16 => addi a0, sp, 16
+		(0x0808, 0x0101_0513),
+		// c.addi4spn s0, sp, 1020 => addi s0, sp, 1020
+		(0x1fe0, 0x3fc1_0413),
+		// c.lwsp a0, 4(sp) => lw a0, 4(sp)
+		(0x4512, 0x0041_2503),
+		// c.lwsp ra, 252(sp) => lw ra, 252(sp)
+		(0x50fe, 0x0fc1_2083),
+		// c.ldsp ra, 8(sp) => ld ra, 8(sp)
+		(0x60a2, 0x0081_3083),
+		// c.ldsp s0, 504(sp) => ld s0, 504(sp)
+		(0x747e, 0x1f81_3403),
+		// c.jr ra => jalr zero, 0(ra)
+		(0x8082, 0x0000_8067),
+		// c.jalr a0 => jalr ra, 0(a0)
+		(0x9502, 0x0005_00e7),
+		// c.swsp ra, 12(sp) => sw ra, 12(sp)
+		(0xc606, 0x0011_2623),
+		// c.swsp s0, 252(sp) => sw s0, 252(sp)
+		(0xdfa2, 0x0e81_2e23),
+		// c.sdsp ra, 8(sp) => sd ra, 8(sp)
+		(0xe406, 0x0011_3423),
+		// c.sdsp s0, 504(sp) => sd s0, 504(sp)
+		(0xffa2, 0x1e81_3c23),
+	];
+
+	for (parcel, expected) in cases {
+		assert!(is_compressed(parcel));
+		assert_eq!(
+			expand(parcel),
+			Some(expected),
+			"Expanded 0x{parcel:04x} to 0x{:08x?} instead of 0x{expected:08x}",
+			expand(parcel)
+		);
+	}
+
+	// Reserved: `nzuimm == 0` (all-zero parcel), `rd == 0`
+	assert_eq!(expand(0x0000), None);
+	assert_eq!(expand(0x4002), None);
+	assert_eq!(expand(0x6002), None);
+	// Reserved: `c.jr zero`, `c.ebreak` (not supported)
+	assert_eq!(expand(0x8002), None);
+	assert_eq!(expand(0x9002), None);
+
+	assert!(!is_compressed(0x0513));
+}
+
+#[test]
+fn compute_address_wraps() {
+	assert_eq!(compute_address(0x1000, 0x10), 0x1010);
+	assert_eq!(compute_address(0x1000, -0x10), 0x0ff0);
+
+	// Wraps at the top and bottom of the address space
+	assert_eq!(compute_address(-4, 8), 0x4);
+	assert_eq!(compute_address(i64::MAX, 1), 0x8000_0000_0000_0000);
+	assert_eq!(compute_address(0, -1), u64::MAX);
+	assert_eq!(compute_address(4, -8), 0xffff_ffff_ffff_fffc);
+
+	// Loads and stores use the wrapped address
+	let mut cpu = Cpu::default();
+	cpu.mmu.memory = Box::new(crate::mem::Memory(vec![0; 16]));
+	cpu.mmu.memory.write_u32_le(4, 0xdead_beef).unwrap();
+
+	// lw a1, 8(a0) with a0 = -4
+	cpu.xregs.set(IntReg::x10, -4);
+	execute(&mut cpu, "LW", asm::i_type(0b0000011, 11, 0b010, 10, 8)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x11), 0xdead_beef_u32 as i32 as i64);
+
+	// sw a1, -4(a0) with a0 = 12
+	cpu.xregs.set(IntReg::x10, 12);
+	let word = asm::s_type(0b0100011, 0b010, 10, 11, -4);
+	execute(&mut cpu, "SW", word).unwrap();
+	assert_eq!(cpu.mmu.memory.read_u32_le(8), Ok(0xdead_beef));
+}
+
+#[test]
+fn jal_jalr_return_address() {
+	use self::asm::{i_type, j_type};
+
+	const JAL: u8 = 0b1101111;
+	const JALR: u8 = 0b1100111;
+
+	// The return address must not depend on `cpu.pc` having been advanced
+	let mut cpu = Cpu::default();
+	cpu.pc = 0xdead_beef;
+
+	// jal ra, 0x100
+	execute_at(&mut cpu, "JAL", j_type(JAL, 1, 0x100), 0x8000_0000).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x1), 0x8000_0004);
+	assert_eq!(cpu.pc, 0x8000_0100);
+
+	// jalr ra, 8(ra) (`rd == rs1`, target uses the old value)
+	cpu.pc = 0;
+	execute_at(&mut cpu, "JALR", i_type(JALR, 1, 0, 1, 8), 0x8000_0100)
+		.unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x1), 0x8000_0104);
+	assert_eq!(cpu.pc, 0x8000_000c);
+
+	// jalr zero, 1(ra) (least-significant bit cleared, no link)
+	execute_at(&mut cpu, "JALR", i_type(JALR, 0, 0, 1, 1), 0x8000_000c)
+		.unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x0), 0);
+	assert_eq!(cpu.pc, 0x8000_0104);
+
+	// c.jalr a0 (links to the next 2-byte instruction)
+	cpu.xregs.set(IntReg::x10, 0x8000_0200);
+	cpu.execute_word(0x9502, 0x8000_0104).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x1), 0x8000_0106);
+	assert_eq!(cpu.pc, 0x8000_0200);
+}
+
+#[test]
+#[allow(clippy::unusual_byte_groupings)]
+fn format_shift() {
+	use self::format::FormatShift;
+
+	// slli a0, a1, 63
+	let shift = FormatShift::parse(0b000000_111111_01011_001_01010_0010011);
+	assert_eq!(
+		shift,
+		FormatShift { rd: 10, rs1: 11, shamt: 63, funct6: 0b000000 }
+	);
+
+	// srai a0, a1, 33
+	let shift = FormatShift::parse(0b010000_100001_01011_101_01010_0010011);
+	assert_eq!(
+		shift,
+		FormatShift { rd: 10, rs1: 11, shamt: 33, funct6: 0b010000 }
+	);
+}
+
 #[test]
 fn unique_instruction_names() {
 	use std::collections::HashMap;
@@ -2309,6 +3679,45 @@ fn unique_instruction_names() {
 	assert!(!duplicates, "Found duplicate names");
 }
 
+#[test]
+fn instruction_count_per_extension() {
+	const EXPECTED: [(&str, usize); 13] = [
+		("Privileged", 2),
+		("RV32A", 11),
+		("RV32D", 26),
+		("RV32F", 26),
+		("RV32I", 37),
+		("RV32M", 8),
+		("RV64A", 11),
+		("RV64D", 6),
+		("RV64F", 4),
+		("RV64I", 15),
+		("RV64M", 5),
+		("Zicsr", 6),
+		("Zifencei", 1),
+	];
+
+	let counts: Vec<_> = instructions_by_extension()
+		.into_iter()
+		.map(|(extension, instructions)| (extension, instructions.len()))
+		.collect();
+
+	assert_eq!(counts, EXPECTED);
+	assert_eq!(
+		EXPECTED.iter().map(|(_, count)| count).sum::<usize>(),
+		INSTRUCTIONS.len()
+	);
+
+	let rv32m: Vec<_> = instructions_by_extension()["RV32M"]
+		.iter()
+		.map(|inst| inst.name())
+		.collect();
+	assert_eq!(
+		rv32m,
+		["MUL", "MULH", "MULHSU", "MULHU", "DIV", "DIVU", "REM", "REMU"]
+	);
+}
+
 #[test]
 fn valid_masks() {
 	for instr in &INSTRUCTIONS {
@@ -2327,6 +3736,98 @@ fn valid_masks() {
 	}
 }
 
+#[test]
+fn no_overlapping_instructions() {
+	let overlaps: Vec<_> = find_overlaps(&INSTRUCTIONS)
+		.map(|(a, b)| format!("{}/{}", a.name, b.name))
+		.collect();
+
+	assert!(overlaps.is_empty(), "Overlapping instructions: {overlaps:?}");
+}
+
+#[test]
+fn instruction_format() {
+	let format = |name: &str| {
+		INSTRUCTIONS.iter().find(|inst| inst.name() == name).unwrap().format()
+	};
+
+	assert_eq!(format("ADDI"), InstructionFormat::I);
+	assert_eq!(format("BEQ"), InstructionFormat::B);
+	assert_eq!(format("LUI"), InstructionFormat::U);
+	assert_eq!(format("JAL"), InstructionFormat::J);
+	assert_eq!(format("SD"), InstructionFormat::S);
+	assert_eq!(format("ADD"), InstructionFormat::R);
+	assert_eq!(format("FMADD.D"), InstructionFormat::R4);
+
+	// The format is determined by the opcode
+	for a in &INSTRUCTIONS {
+		for b in &INSTRUCTIONS {
+			if a.reqd & 0x7f == b.reqd & 0x7f {
+				assert_eq!(a.format, b.format, "{} / {}", a.name, b.name);
+			}
+		}
+	}
+}
+
+#[test]
+fn decode_all_matches() {
+	use self::asm::r_type;
+
+	// add a0, a1, a2
+	let matches = decode_all(r_type(0b0110011, 10, 0b000, 11, 12, 0));
+	assert_eq!(matches.len(), 1);
+	assert_eq!(matches[0].name, "ADD");
+
+	assert!(decode_all(0xffff_ffff).is_empty());
+}
+
+// Only run on 32-bit hosts (see the `32-bit host` job of the CI)
+#[test]
+#[cfg(target_pointer_width = "32")]
+fn decode_without_memory() {
+	use self::asm::i_type;
+
+	// Only the tables and the expansion of compressed instructions are used,
+	// neither of which needs a cpu or memory (and therefore a 64-bit host)
+	let matches = decode_all(i_type(0b0000011, 10, 0b011, 11, -8));
+	assert_eq!(matches.len(), 1);
+	assert_eq!(matches[0].name, "LD");
+
+	// c.lwsp a0, 4(sp)
+	let word = rvc::expand(0x4512).unwrap();
+	assert_eq!(decode_all(word)[0].name, "LW");
+}
+
+#[test]
+fn find_overlaps_detects_overlap() {
+	fn instruction(mask: u32, reqd: u32) -> Instruction {
+		Instruction {
+			mask,
+			reqd,
+			name: "",
+			extension: "",
+			format: InstructionFormat::R,
+			op: |_, _, _, _| Ok(()),
+		}
+	}
+
+	// Differ in a bit constrained by both
+	assert!(!instruction(0b11, 0b01).overlaps(&instruction(0b01, 0b00)));
+	// Agree on all commonly constrained bits (e.g. `0b0111` matches both)
+	assert!(instruction(0b0011, 0b0011).overlaps(&instruction(0b0110, 0b0110)));
+	// Unconstrained matches everything
+	assert!(instruction(0, 0).overlaps(&instruction(!0, 0x1234)));
+
+	let table = [
+		instruction(0b111, 0b001),
+		instruction(0b111, 0b010),
+		instruction(0b011, 0b001),
+	];
+	let overlaps: Vec<_> =
+		find_overlaps(&table).map(|(a, b)| (a.reqd, b.reqd)).collect();
+	assert_eq!(overlaps, [(0b001, 0b001)]);
+}
+
 #[test]
 #[ignore = "Takes long to run and maxes out the whole cpu. Only run when the \
             instructions change."]
@@ -2367,3 +3868,686 @@ fn unique_instruction_codes() {
 		handle.join().unwrap();
 	}
 }
+
+/// Runs `word` as the instruction `name` at address `0`.
+#[cfg(test)]
+fn execute(cpu: &mut Cpu, name: &str, word: u32) -> Result<(), Trap> {
+	execute_at(cpu, name, word, 0)
+}
+
+/// Runs `word` as the instruction `name` at address `addr`.
+#[cfg(test)]
+fn execute_at(
+	cpu: &mut Cpu,
+	name: &str,
+	word: u32,
+	addr: Address,
+) -> Result<(), Trap> {
+	let inst = INSTRUCTIONS.iter().find(|i| i.name == name).unwrap();
+	assert_eq!(word & inst.mask, inst.reqd);
+	// Expanded base instruction
+	(inst.op)(cpu, word, addr, 4)
+}
+
+#[test]
+fn set_less_than_unsigned() {
+	// slt a2, a0, a1
+	const SLT: u32 = 0x00b5_2633;
+	// sltu a2, a0, a1
+	const SLTU: u32 = 0x00b5_3633;
+	// sltiu a2, a0, -1
+	const SLTIU: u32 = 0xfff5_3613;
+
+	let mut cpu = Cpu::default();
+
+	// Signed: -1 < 1; Unsigned: u64::MAX > 1
+	cpu.xregs.set(IntReg::x10, -1);
+	cpu.xregs.set(IntReg::x11, 1);
+
+	execute(&mut cpu, "SLT", SLT).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), 1);
+
+	execute(&mut cpu, "SLTU", SLTU).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), 0);
+
+	// The immediate `-1` is compared as `u64::MAX`
+	cpu.xregs.set(IntReg::x10, 1);
+
+	execute(&mut cpu, "SLTIU", SLTIU).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), 1);
+}
+
+#[test]
+fn set_less_than_immediate() {
+	use self::asm::i_type;
+
+	// sltiu a2, a0, 1 (`seqz a2, a0`)
+	const SEQZ: u32 = i_type(0b0010011, 12, 0b011, 10, 1);
+	// sltiu a2, a0, -2048
+	const SLTIU_MIN: u32 = i_type(0b0010011, 12, 0b011, 10, -2048);
+	// slti a2, a0, -2048
+	const SLTI_MIN: u32 = i_type(0b0010011, 12, 0b010, 10, -2048);
+
+	let mut cpu = Cpu::default();
+
+	for (value, expected) in [(0, 1), (1, 0), (-1, 0), (i64::MIN, 0)] {
+		cpu.xregs.set(IntReg::x10, value);
+		execute(&mut cpu, "SLTIU", SEQZ).unwrap();
+		assert_eq!(cpu.xregs.get(IntReg::x12), expected, "seqz {value}");
+	}
+
+	// `-2048` is sign extended to `0xffff_ffff_ffff_f800` and then compared
+	// as unsigned, while `SLTI` compares it as signed
+	for (value, sltiu, slti) in [
+		(0, 1, 0),
+		(-2049, 1, 1),
+		(-2048, 0, 0),
+		(-1, 0, 0),
+		(i64::MIN, 1, 1),
+		(i64::MAX, 1, 0),
+	] {
+		cpu.xregs.set(IntReg::x10, value);
+
+		execute(&mut cpu, "SLTIU", SLTIU_MIN).unwrap();
+		assert_eq!(cpu.xregs.get(IntReg::x12), sltiu, "sltiu {value}");
+
+		execute(&mut cpu, "SLTI", SLTI_MIN).unwrap();
+		assert_eq!(cpu.xregs.get(IntReg::x12), slti, "slti {value}");
+	}
+}
+
+#[test]
+fn add_sub_word() {
+	use self::asm::r_type;
+
+	// addw a2, a0, a1
+	const ADDW: u32 = r_type(0b0111011, 12, 0b000, 10, 11, 0b0000000);
+	// subw a2, a0, a1
+	const SUBW: u32 = r_type(0b0111011, 12, 0b000, 10, 11, 0b0100000);
+
+	let mut cpu = Cpu::default();
+
+	for (name, word, a, b, expected) in [
+		// Overflows 32 bits, the 64-bit sum would be `0x8000_0000`
+		("ADDW", ADDW, 0x7fff_ffff, 1, 0xffff_ffff_8000_0000_u64 as i64),
+		("ADDW", ADDW, -1, -1, -2),
+		// The upper 32 bits of the operands are ignored
+		("ADDW", ADDW, 0x1_0000_0001, 0x2_0000_0001, 2),
+		("SUBW", SUBW, 0xffff_ffff_8000_0000_u64 as i64, 1, 0x7fff_ffff),
+		("SUBW", SUBW, 0, 1, -1),
+		("SUBW", SUBW, 0x1_0000_0000, 0, 0),
+	] {
+		cpu.xregs.set(IntReg::x10, a);
+		cpu.xregs.set(IntReg::x11, b);
+
+		execute(&mut cpu, name, word).unwrap();
+		assert_eq!(
+			cpu.xregs.get(IntReg::x12),
+			expected,
+			"{name} {a:#x}, {b:#x}"
+		);
+	}
+}
+
+#[test]
+fn branch_greater_equal() {
+	// bge a0, a1, 8
+	const BGE: u32 = 0x00b5_5463;
+	// bgeu a0, a1, 8
+	const BGEU: u32 = 0x00b5_7463;
+
+	let mut cpu = Cpu::default();
+
+	// Signed: -1 < 1; Unsigned: u64::MAX > 1
+	cpu.xregs.set(IntReg::x10, -1);
+	cpu.xregs.set(IntReg::x11, 1);
+
+	cpu.pc = 4;
+	execute(&mut cpu, "BGE", BGE).unwrap();
+	assert_eq!(cpu.pc, 4);
+
+	execute(&mut cpu, "BGEU", BGEU).unwrap();
+	assert_eq!(cpu.pc, 8);
+
+	// Equal operands branch for both
+	cpu.xregs.set(IntReg::x11, -1);
+
+	cpu.pc = 4;
+	execute(&mut cpu, "BGE", BGE).unwrap();
+	assert_eq!(cpu.pc, 8);
+
+	cpu.pc = 4;
+	execute(&mut cpu, "BGEU", BGEU).unwrap();
+	assert_eq!(cpu.pc, 8);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Branch BLT at 0x4 diverged from the reference")]
+fn branch_reference_check() {
+	use crate::mem::Memory;
+
+	// blt a0, a1, 8
+	const BLT: u32 = 0x00b5_4463;
+
+	// `BLT` with an (incorrect) unsigned comparison
+	let patched = Instruction {
+		op: |cpu, word, addr, _len| {
+			let FormatB { rs1, rs2, imm } = FormatB::parse(word);
+			let rs1 = resolve_xreg(cpu, rs1);
+			let rs2 = resolve_xreg(cpu, rs2);
+
+			if (cpu.xregs[rs1] as IntWidthU) < (cpu.xregs[rs2] as IntWidthU) {
+				cpu.pc = addr.wrapping_add(imm);
+			}
+
+			Ok(())
+		},
+		..*INSTRUCTIONS.iter().find(|i| i.name == "BLT").unwrap()
+	};
+
+	let mut cpu = Cpu::default();
+	cpu.mmu.memory = Box::new(Memory(vec![0; 16]));
+	cpu.xregs.set(IntReg::x10, 1);
+	cpu.xregs.set(IntReg::x11, 2);
+
+	// Agrees with the reference for operands of the same sign
+	cpu.pc = 8;
+	cpu.execute(&patched, BLT, 4, 4).unwrap();
+	assert_eq!(cpu.pc, 12);
+
+	// Signed: -1 < 2; Unsigned: u64::MAX > 2
+	cpu.xregs.set(IntReg::x10, -1);
+	cpu.pc = 8;
+	let _ = cpu.execute(&patched, BLT, 4, 4);
+}
+
+#[test]
+fn shift_right_arithmetic() {
+	use self::asm::r_type;
+
+	// sra a2, a0, a1
+	const SRA: u32 = r_type(0b0110011, 12, 0b101, 10, 11, 0b0100000);
+
+	let mut cpu = Cpu::default();
+
+	// Sign bits fill in
+	cpu.xregs.set(IntReg::x10, -256);
+	cpu.xregs.set(IntReg::x11, 4);
+	execute(&mut cpu, "SRA", SRA).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), -16);
+
+	cpu.xregs.set(IntReg::x10, i64::MIN);
+	cpu.xregs.set(IntReg::x11, 63);
+	execute(&mut cpu, "SRA", SRA).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), -1);
+
+	// Positive values are filled with zeros
+	cpu.xregs.set(IntReg::x10, i64::MAX);
+	execute(&mut cpu, "SRA", SRA).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), 0);
+
+	// The shift amount is masked to 6 bits: 64 => 0, 68 => 4
+	cpu.xregs.set(IntReg::x10, -256);
+	cpu.xregs.set(IntReg::x11, 64);
+	execute(&mut cpu, "SRA", SRA).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), -256);
+
+	cpu.xregs.set(IntReg::x11, 68);
+	execute(&mut cpu, "SRA", SRA).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), -16);
+}
+
+#[test]
+fn word_shift_immediates() {
+	use self::asm::i_type;
+
+	const fn shift(funct3: u8, shamt: i32) -> u32 {
+		// s{l,r}{l,a}iw a2, a0, shamt
+		i_type(0b0011011, 12, funct3, 10, shamt)
+	}
+
+	let mut cpu = Cpu::default();
+	cpu.xregs.set(IntReg::x10, 0x1234_5678_8000_0001);
+
+	execute(&mut cpu, "SLLIW", shift(0b001, 4)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), 0x10);
+
+	execute(&mut cpu, "SLLIW", shift(0b001, 31)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), i32::MIN as i64);
+
+	execute(&mut cpu, "SRLIW", shift(0b101, 4)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), 0x0800_0000);
+
+	execute(&mut cpu, "SRAIW", shift(0b101, 0x400 | 4)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), 0xffff_ffff_f800_0000_u64 as i64);
+
+	// `shamt[5]` set is illegal for the word shifts
+	for (name, word) in [
+		("SLLIW", shift(0b001, 32)),
+		("SRLIW", shift(0b101, 32)),
+		("SRAIW", shift(0b101, 0x400 | 32)),
+	] {
+		assert_eq!(decode_all(word)[0].name(), name);
+		assert_eq!(
+			execute(&mut cpu, name, word),
+			Err(Trap::IllegalInstruction(word))
+		);
+	}
+}
+
+#[test]
+fn csr_instructions() {
+	use self::asm::i_type;
+	use crate::csr::{MHARTID, MSCRATCH};
+
+	const fn csr(funct3: u8, rd: u8, rs1: u8, csr: u16) -> u32 {
+		i_type(0b1110011, rd, funct3, rs1, csr as i32)
+	}
+
+	let mut cpu = Cpu::new().with_hart_id(3);
+	cpu.xregs.set(IntReg::x10, 0b1100);
+
+	// csrr a1, mhartid (csrrs a1, mhartid, x0)
+	execute(&mut cpu, "CSRRS", csr(0b010, 11, 0, MHARTID)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x11), 3);
+
+	// mhartid is read-only
+	let word = csr(0b001, 11, 10, MHARTID);
+	assert_eq!(
+		execute(&mut cpu, "CSRRW", word),
+		Err(Trap::IllegalInstruction(word))
+	);
+	let word = csr(0b110, 0, 1, MHARTID);
+	assert_eq!(
+		execute(&mut cpu, "CSRRSI", word),
+		Err(Trap::IllegalInstruction(word))
+	);
+	assert_eq!(cpu.read_csr(MHARTID), 3);
+
+	// csrw mscratch, a0
+	execute(&mut cpu, "CSRRW", csr(0b001, 0, 10, MSCRATCH)).unwrap();
+	assert_eq!(cpu.read_csr(MSCRATCH), 0b1100);
+
+	// csrrsi a1, mscratch, 0b11
+	execute(&mut cpu, "CSRRSI", csr(0b110, 11, 0b11, MSCRATCH)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x11), 0b1100);
+	assert_eq!(cpu.read_csr(MSCRATCH), 0b1111);
+
+	// csrrc a1, mscratch, a0
+	execute(&mut cpu, "CSRRC", csr(0b011, 11, 10, MSCRATCH)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x11), 0b1111);
+	assert_eq!(cpu.read_csr(MSCRATCH), 0b0011);
+
+	// csrrwi a1, mscratch, 0 (always writes)
+	execute(&mut cpu, "CSRRWI", csr(0b101, 11, 0, MSCRATCH)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x11), 0b0011);
+	assert_eq!(cpu.read_csr(MSCRATCH), 0);
+
+	// csrrci a1, mscratch, 0 (reads only)
+	cpu.write_csr(MSCRATCH, 7).unwrap();
+	execute(&mut cpu, "CSRRCI", csr(0b111, 11, 0, MSCRATCH)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x11), 7);
+	assert_eq!(cpu.read_csr(MSCRATCH), 7);
+}
+
+#[test]
+fn csr_permissions() {
+	use self::asm::i_type;
+	use crate::csr::{Privilege, CYCLE, MSCRATCH};
+
+	const fn csr(funct3: u8, rd: u8, rs1: u8, csr: u16) -> u32 {
+		i_type(0b1110011, rd, funct3, rs1, csr as i32)
+	}
+
+	let mut cpu = Cpu::new();
+	assert_eq!(cpu.privilege(), Privilege::Machine);
+	cpu.xregs.set(IntReg::x10, 0b1100);
+
+	// csrw cycle, a0 (read-only)
+	let word = csr(0b001, 0, 10, CYCLE);
+	assert_eq!(
+		execute(&mut cpu, "CSRRW", word),
+		Err(Trap::IllegalInstruction(word))
+	);
+	// rdcycle a1 only reads
+	execute(&mut cpu, "CSRRS", csr(0b010, 11, 0, CYCLE)).unwrap();
+
+	// csrw mscratch, a0 (read-write, machine level)
+	execute(&mut cpu, "CSRRW", csr(0b001, 0, 10, MSCRATCH)).unwrap();
+	assert_eq!(cpu.read_csr(MSCRATCH), 0b1100);
+}
+
+#[test]
+fn fcvt_to_int_saturation() {
+	use self::asm::r_type;
+	use crate::csr::{FFLAGS, FFLAGS_NV, FFLAGS_NX, FRM};
+	use crate::reg::FloatReg;
+
+	// Rounding modes
+	const RNE: u8 = 0b000;
+	const RTZ: u8 = 0b001;
+	const RDN: u8 = 0b010;
+	const RUP: u8 = 0b011;
+	const RMM: u8 = 0b100;
+	const DYN: u8 = 0b111;
+
+	const fn fcvt(name: &str, rm: u8) -> u32 {
+		// fcvt.<int>.<float> a0, fa0, rm
+		let (funct7, rs2) = match name.as_bytes() {
+			b"FCVT.W.S" => (0b1100000, 0),
+			b"FCVT.WU.S" => (0b1100000, 1),
+			b"FCVT.L.S" => (0b1100000, 2),
+			b"FCVT.LU.S" => (0b1100000, 3),
+			b"FCVT.W.D" => (0b1100001, 0),
+			b"FCVT.LU.D" => (0b1100001, 3),
+			_ => panic!("Unsupported conversion"),
+		};
+
+		r_type(0b1010011, 10, rm, 10, rs2, funct7)
+	}
+
+	// (instruction, rounding mode, value, expected result, expected flags)
+	let cases = [
+		// NaN converts to the maximum (`as` would return 0)
+		("FCVT.W.S", RTZ, f64::NAN, i32::MAX as i64, FFLAGS_NV),
+		("FCVT.W.S", RTZ, f64::INFINITY, i32::MAX as i64, FFLAGS_NV),
+		("FCVT.W.S", RTZ, f64::NEG_INFINITY, i32::MIN as i64, FFLAGS_NV),
+		// Just above `i32::MAX` (representable as single precision)
+		("FCVT.W.S", RTZ, 2_147_483_648.0, i32::MAX as i64, FFLAGS_NV),
+		("FCVT.W.S", RTZ, -2_147_483_648.0, i32::MIN as i64, 0),
+		("FCVT.W.S", RTZ, -1.5, -1, FFLAGS_NX),
+		("FCVT.W.S", RNE, -1.5, -2, FFLAGS_NX),
+		("FCVT.W.S", RNE, 2.5, 2, FFLAGS_NX),
+		("FCVT.W.S", RMM, 2.5, 3, FFLAGS_NX),
+		("FCVT.W.S", RDN, 2.5, 2, FFLAGS_NX),
+		("FCVT.W.S", RUP, 2.5, 3, FFLAGS_NX),
+		("FCVT.W.S", RTZ, 42.0, 42, 0),
+		// Negative to unsigned saturates to 0
+		("FCVT.WU.S", RTZ, -1.0, 0, FFLAGS_NV),
+		// Rounds to (negative) zero first, which is in range
+		("FCVT.WU.S", RTZ, -0.5, 0, FFLAGS_NX),
+		// 32-bit results are sign-extended
+		("FCVT.WU.S", RTZ, 4_294_967_040.0, 0xffff_ff00_u32 as i32 as i64, 0),
+		("FCVT.WU.S", RTZ, f64::NAN, -1, FFLAGS_NV),
+		("FCVT.L.S", RTZ, f64::NAN, i64::MAX, FFLAGS_NV),
+		("FCVT.L.S", RTZ, 9.3e18, i64::MAX, FFLAGS_NV),
+		("FCVT.L.S", RTZ, f64::NEG_INFINITY, i64::MIN, FFLAGS_NV),
+		("FCVT.LU.S", RTZ, f64::NAN, u64::MAX as i64, FFLAGS_NV),
+		("FCVT.LU.S", RTZ, -3.0, 0, FFLAGS_NV),
+		("FCVT.W.D", RTZ, 2_147_483_647.9, i32::MAX as i64, FFLAGS_NX),
+		("FCVT.W.D", RTZ, f64::NAN, i32::MAX as i64, FFLAGS_NV),
+		("FCVT.LU.D", RTZ, 1.9e19, u64::MAX as i64, FFLAGS_NV),
+	];
+
+	for (name, rm, value, expected, flags) in cases {
+		let mut cpu = Cpu::default();
+		cpu.fregs.set(FloatReg::f10, value);
+
+		execute(&mut cpu, name, fcvt(name, rm)).unwrap();
+		assert_eq!(
+			cpu.xregs.get(IntReg::x10),
+			expected,
+			"{} (rm: {:03b}) of {}",
+			name,
+			rm,
+			value
+		);
+		assert_eq!(cpu.read_csr(FFLAGS), flags, "{} of {}", name, value);
+	}
+
+	// Dynamic rounding mode
+	let mut cpu = Cpu::default();
+	cpu.fregs.set(FloatReg::f10, 2.5);
+	cpu.write_csr(FRM, RUP as u64).unwrap();
+
+	execute(&mut cpu, "FCVT.W.S", fcvt("FCVT.W.S", DYN)).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x10), 3);
+
+	// Reserved rounding modes
+	let word = fcvt("FCVT.W.S", 0b101);
+	assert_eq!(
+		execute(&mut cpu, "FCVT.W.S", word),
+		Err(Trap::IllegalInstruction(word))
+	);
+
+	cpu.write_csr(FRM, 0b110).unwrap();
+	let word = fcvt("FCVT.W.S", DYN);
+	assert_eq!(
+		execute(&mut cpu, "FCVT.W.S", word),
+		Err(Trap::IllegalInstruction(word))
+	);
+}
+
+#[test]
+fn amo_min_max() {
+	use self::asm::r_type;
+	use crate::mem::Memory;
+
+	const fn amo(funct5: u8, funct3: u8) -> u32 {
+		// amo<op> a2, a1, (a0)
+		r_type(0b0101111, 12, funct3, 10, 11, funct5 << 2)
+	}
+
+	// (name, funct5, expected value in memory)
+	let cases = [
+		("MIN", 0b10000, -1),
+		("MAX", 0b10100, 1),
+		("MINU", 0b11000, 1),
+		("MAXU", 0b11100, -1),
+	];
+
+	for (name, funct5, expected) in cases {
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 16]));
+		cpu.xregs.set(IntReg::x10, 8);
+		cpu.xregs.set(IntReg::x11, 1);
+
+		// Word: Upper half of the double word stays untouched
+		cpu.mmu.memory.write_u64_le(8, 0xffff_ffff).unwrap();
+
+		execute(&mut cpu, &format!("AMO{}.W", name), amo(funct5, 0b010))
+			.unwrap();
+		assert_eq!(cpu.xregs.get(IntReg::x12), -1, "AMO{}.W", name);
+		assert_eq!(
+			cpu.mmu.memory.read_u64_le(8).unwrap(),
+			expected as u32 as u64,
+			"AMO{}.W",
+			name
+		);
+
+		// Double word
+		cpu.mmu.memory.write_u64_le(8, u64::MAX).unwrap();
+
+		execute(&mut cpu, &format!("AMO{}.D", name), amo(funct5, 0b011))
+			.unwrap();
+		assert_eq!(cpu.xregs.get(IntReg::x12), -1, "AMO{}.D", name);
+		assert_eq!(
+			cpu.mmu.memory.read_u64_le(8).unwrap(),
+			expected as u64,
+			"AMO{}.D",
+			name
+		);
+	}
+}
+
+#[test]
+fn load_reserved_store_conditional() {
+	use self::asm::{r_type, s_type};
+	use crate::mem::Memory;
+
+	// lr.w a2, (a0)
+	const LR_W: u32 = r_type(0b0101111, 12, 0b010, 10, 0, 0b0001000);
+	// sc.w a3, a1, (a0)
+	const SC_W: u32 = r_type(0b0101111, 13, 0b010, 10, 11, 0b0001100);
+	// lr.d a2, (a0)
+	const LR_D: u32 = r_type(0b0101111, 12, 0b011, 10, 0, 0b0001000);
+	// sc.d a3, a1, (a0)
+	const SC_D: u32 = r_type(0b0101111, 13, 0b011, 10, 11, 0b0001100);
+	// sw a1, 0(a0)
+	const SW: u32 = s_type(0b0100011, 0b010, 10, 11, 0);
+
+	let mut cpu = Cpu::default();
+	cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+	cpu.mmu.write_u32_le(0x10, 0xffff_fffe).unwrap();
+	cpu.xregs.set(IntReg::x10, 0x10);
+	cpu.xregs.set(IntReg::x11, 7);
+
+	// Succeeds without an intervening store
+	execute(&mut cpu, "LR.W", LR_W).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), -2);
+	assert!(cpu.mmu.reservation().valid);
+	execute(&mut cpu, "SC.W", SC_W).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x13), 0);
+	assert_eq!(cpu.mmu.read_u32_le(0x10), Ok(7));
+	assert!(!cpu.mmu.reservation().valid);
+
+	// A second `SC` without `LR` fails
+	cpu.xregs.set(IntReg::x11, 9);
+	execute(&mut cpu, "SC.W", SC_W).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x13), 1);
+	assert_eq!(cpu.mmu.read_u32_le(0x10), Ok(7));
+
+	// A plain store to the reserved address invalidates the reservation
+	execute(&mut cpu, "LR.W", LR_W).unwrap();
+	execute(&mut cpu, "SW", SW).unwrap();
+	assert!(!cpu.mmu.reservation().valid);
+	cpu.xregs.set(IntReg::x11, 11);
+	execute(&mut cpu, "SC.W", SC_W).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x13), 1);
+	assert_eq!(cpu.mmu.read_u32_le(0x10), Ok(9));
+
+	// Stores next to the reserved bytes and AMOs to them
+	execute(&mut cpu, "LR.D", LR_D).unwrap();
+	cpu.mmu.write_u32_le(0x18, 0).unwrap();
+	cpu.mmu.write_u32_le(0x0c, 0).unwrap();
+	assert!(cpu.mmu.reservation().valid);
+	cpu.mmu.write(0x17, &[0]).unwrap();
+	assert!(!cpu.mmu.reservation().valid);
+
+	execute(&mut cpu, "LR.D", LR_D).unwrap();
+	execute(&mut cpu, "SC.D", SC_D).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x13), 0);
+	assert_eq!(cpu.mmu.read_u64_le(0x10), Ok(11));
+
+	// `SC` to a different address than the reservation
+	execute(&mut cpu, "LR.W", LR_W).unwrap();
+	cpu.xregs.set(IntReg::x10, 0x20);
+	execute(&mut cpu, "SC.W", SC_W).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x13), 1);
+
+	// Misaligned
+	cpu.xregs.set(IntReg::x10, 0x12);
+	assert_eq!(
+		execute(&mut cpu, "LR.W", LR_W),
+		Err(Trap::LoadAddressMisaligned(0x12))
+	);
+	assert_eq!(
+		execute(&mut cpu, "SC.W", SC_W),
+		Err(Trap::StoreAddressMisaligned(0x12))
+	);
+}
+
+#[test]
+fn amo() {
+	use crate::mem::Memory;
+
+	// amoadd.d a2, a1, (a0)
+	const AMOADD_D: u32 = 0x00b5_362f;
+	// amoadd.w a2, a1, (a0)
+	const AMOADD_W: u32 = 0x00b5_262f;
+
+	let mut cpu = Cpu::default();
+	cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+	cpu.mmu.memory.write_u64_le(8, 40).unwrap();
+
+	cpu.xregs.set(IntReg::x10, 8);
+	cpu.xregs.set(IntReg::x11, 2);
+
+	execute(&mut cpu, "AMOADD.D", AMOADD_D).unwrap();
+	assert_eq!(cpu.xregs.get(IntReg::x12), 40);
+	assert_eq!(cpu.mmu.memory.read_u64_le(8).unwrap(), 42);
+
+	// Word aligned but not double word aligned.
+	cpu.xregs.set(IntReg::x10, 12);
+	assert_eq!(
+		execute(&mut cpu, "AMOADD.D", AMOADD_D),
+		Err(Trap::StoreAddressMisaligned(12))
+	);
+	assert_eq!(cpu.mmu.memory.read_u64_le(8).unwrap(), 42);
+
+	execute(&mut cpu, "AMOADD.W", AMOADD_W).unwrap();
+	assert_eq!(cpu.mmu.memory.read_u32_le(12).unwrap(), 2);
+
+	cpu.xregs.set(IntReg::x10, 10);
+	assert_eq!(
+		execute(&mut cpu, "AMOADD.W", AMOADD_W),
+		Err(Trap::StoreAddressMisaligned(10))
+	);
+}
+
+#[test]
+fn disassemble_text_section() {
+	let bytes = include_bytes!("../../../resources/riscv-tests/rv64ui-p-add");
+	let elf = Elf::from_bytes(bytes).unwrap();
+
+	let lines = disassemble_section(&elf, ".text.init");
+	assert_eq!(lines[0], (0x8000_0000, "jal x0, 0x80000048".to_string()));
+
+	let text = |addr: u64| {
+		lines
+			.iter()
+			.find(|(line_addr, _)| *line_addr == addr)
+			.map(|(_, text)| text.as_str())
+	};
+	assert_eq!(text(0x8000_0004), Some("csrrs x30, 0x342, x0"));
+	assert_eq!(text(0x8000_000c), Some("beq x30, x31, 0x8000003c"));
+	assert_eq!(text(0x8000_0028), Some("jalr x0, 0(x30)"));
+	assert_eq!(text(0x8000_0038), Some("ori x3, x3, 1337"));
+	assert_eq!(text(0x8000_003c), Some("auipc x30, 1"));
+	assert_eq!(text(0x8000_0040), Some("sw x3, -60(x30)"));
+	assert_eq!(text(0x8000_0044), Some("jal x0, 0x8000003c"));
+	assert_eq!(text(0x8000_0688), Some("fence"));
+	assert_eq!(text(0x8000_0698), Some("ecall"));
+	// `unimp`
+	assert_eq!(text(0x8000_069c), Some("csrrw x0, 0xc00, x0"));
+
+	// Zeroed padding is the defined illegal instruction
+	assert_eq!(text(0x8000_06a0), Some(".2byte 0x0000"));
+	assert_eq!(lines.last().map(|(addr, _)| *addr), Some(0x8000_06ba));
+
+	assert!(disassemble_section(&elf, ".missing").is_empty());
+}
+
+#[test]
+fn disassemble_operands() {
+	use self::asm::{i_type, r_type};
+
+	assert_eq!(
+		disassemble(i_type(0b0010011, 1, 0, 0, -5), 0).as_deref(),
+		Some("addi x1, x0, -5")
+	);
+	// srai x1, x2, 3
+	assert_eq!(disassemble(0x4031_5093, 0).as_deref(), Some("srai x1, x2, 3"));
+	assert_eq!(disassemble(0x0000_0073, 0).as_deref(), Some("ecall"));
+	// fsqrt.d f1, f2 (`rs2` is fixed)
+	assert_eq!(disassemble(0x5a01_70d3, 0).as_deref(), Some("fsqrt.d f1, f2"));
+	// feq.s x1, f2, f3
+	assert_eq!(
+		disassemble(r_type(0b1010011, 1, 0b010, 2, 3, 0b1010000), 0)
+			.as_deref(),
+		Some("feq.s x1, f2, f3")
+	);
+	// fld f1, 8(x2)
+	assert_eq!(
+		disassemble(i_type(0b0000111, 1, 0b011, 2, 8), 0).as_deref(),
+		Some("fld f1, 8(x2)")
+	);
+	// lr.w x1, (x2) / amoadd.w x1, x3, (x2)
+	assert_eq!(disassemble(0x1001_20af, 0).as_deref(), Some("lr.w x1, (x2)"));
+	assert_eq!(
+		disassemble(0x0031_20af, 0).as_deref(),
+		Some("amoadd.w x1, x3, (x2)")
+	);
+	assert_eq!(disassemble(0x0000_007f, 0), None);
+}
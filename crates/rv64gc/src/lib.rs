@@ -1,6 +1,8 @@
 #![allow(dead_code)]
-#[cfg(not(target_pointer_width = "64"))]
-compile_error!("This emulator requires a 64-bit system");
+// The emulator itself does not depend on the width of the host: Memory
+// accesses convert addresses with `usize::try_from` and fault if they exceed
+// the host, so the decoder and instruction tables can be used on 32-bit
+// hosts as well.
 
 // References:
 // - https://github.com/riscv/riscv-isa-manual
@@ -14,17 +16,33 @@ pub mod shared {
 	/// ILEN   (max. instruction length in bits)
 
 	pub type IntWidth = i64;
-	pub type IntWidthU = i64;
+	pub type IntWidthU = u64;
 	pub type FloatWidth = f64;
 	pub type Width = IntWidth;
 
 	pub type Word = i32;
 	pub type HalfWord = i16;
-	pub type DoulbeWord = i64;
+	pub type DoubleWord = i64;
+	#[deprecated(note = "Misspelled, use `DoubleWord` instead")]
+	pub type DoulbeWord = DoubleWord;
 	pub type QuadWord = i128;
 
 	pub type Address = u64;
 
+	#[test]
+	fn type_widths() {
+		use core::mem::size_of;
+
+		assert_eq!(IntWidth::BITS as usize, XLEN);
+		assert_eq!(IntWidthU::BITS as usize, XLEN);
+		assert_eq!(IntWidthU::MIN, 0);
+
+		assert_eq!(size_of::<HalfWord>(), 2);
+		assert_eq!(size_of::<Word>(), 4);
+		assert_eq!(size_of::<DoubleWord>(), 8);
+		assert_eq!(size_of::<QuadWord>(), 16);
+	}
+
 	#[test]
 	fn assert_twos_complement() {
 		assert_eq!(unsafe { core::mem::transmute::<i8, u8>(-128_i8) }, 128_u8);
@@ -93,6 +111,22 @@ pub mod adr {
 		};
 	}
 
+	/// Error of [`Addressable::read_slice`].
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum SliceError<E> {
+		/// The memory can not lend out its bytes, [`Addressable::read`] must
+		/// be used instead.
+		Unsupported,
+		/// The read itself failed.
+		Access(E),
+	}
+
+	impl<E> From<E> for SliceError<E> {
+		fn from(err: E) -> Self {
+			Self::Access(err)
+		}
+	}
+
 	#[allow(clippy::len_without_is_empty)]
 	pub trait Addressable {
 		type Address;
@@ -114,6 +148,19 @@ pub mod adr {
 			data: &[u8],
 		) -> Result<(), Self::Error>;
 
+		/// Returns `len` bytes starting at `addr` without copying them.
+		///
+		/// Fails with [`SliceError::Unsupported`] if zero-copy reads are not
+		/// supported (the default).
+		fn read_slice(
+			&mut self,
+			addr: Self::Address,
+			len: usize,
+		) -> Result<&[u8], SliceError<Self::Error>> {
+			let _ = (addr, len);
+			Err(SliceError::Unsupported)
+		}
+
 		read!(read_u8: u8: le);
 
 		read!(read_u16_be: u16: be);
@@ -128,6 +175,16 @@ pub mod adr {
 		read!(read_u128_be: u128: be);
 		read!(read_u128_le: u128: le);
 
+		// Signed (sign-extended by widening casts, e.g. `as i64`)
+
+		read!(read_i8: i8: le);
+
+		read!(read_i16_le: i16: le);
+
+		read!(read_i32_le: i32: le);
+
+		read!(read_i64_le: i64: le);
+
 		write!(write_u8: u8: le);
 
 		write!(write_u16_be: u16: be);
@@ -170,17 +227,179 @@ pub mod tra {
 	// - Fatal Trap
 	// > Causes execution env to terminate
 
-	pub struct Trap;
+	use crate::shared::Address;
+
+	/// Synchronous exceptions as listed for the `mcause` register.
+	///
+	/// Variants carry the value which would be written to `mtval` (faulting
+	/// address or instruction word).
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum Trap {
+		InstructionAddressMisaligned(Address),
+		InstructionAccessFault(Address),
+		IllegalInstruction(u32),
+		Breakpoint(Address),
+		LoadAddressMisaligned(Address),
+		LoadAccessFault(Address),
+		/// Also raised by misaligned `AMO`s.
+		StoreAddressMisaligned(Address),
+		/// Also raised by faulting `AMO`s.
+		StoreAccessFault(Address),
+		EnvironmentCallFromUMode,
+		EnvironmentCallFromSMode,
+		EnvironmentCallFromMMode,
+		InstructionPageFault(Address),
+		LoadPageFault(Address),
+		StorePageFault(Address),
+	}
+
+	impl Trap {
+		/// Returns the exception code as written to `mcause`.
+		///
+		/// Traps are synchronous exceptions, so the interrupt bit
+		/// (`mcause[XLEN-1]`) is never set.
+		pub const fn cause_code(&self) -> u64 {
+			match self {
+				Self::InstructionAddressMisaligned(_) => 0,
+				Self::InstructionAccessFault(_) => 1,
+				Self::IllegalInstruction(_) => 2,
+				Self::Breakpoint(_) => 3,
+				Self::LoadAddressMisaligned(_) => 4,
+				Self::LoadAccessFault(_) => 5,
+				Self::StoreAddressMisaligned(_) => 6,
+				Self::StoreAccessFault(_) => 7,
+				Self::EnvironmentCallFromUMode => 8,
+				Self::EnvironmentCallFromSMode => 9,
+				Self::EnvironmentCallFromMMode => 11,
+				Self::InstructionPageFault(_) => 12,
+				Self::LoadPageFault(_) => 13,
+				Self::StorePageFault(_) => 15,
+			}
+		}
+
+		/// Returns the value written to `mtval` (`0` for traps without a
+		/// value).
+		pub const fn tval(&self) -> u64 {
+			match self {
+				Self::InstructionAddressMisaligned(addr)
+				| Self::InstructionAccessFault(addr)
+				| Self::Breakpoint(addr)
+				| Self::LoadAddressMisaligned(addr)
+				| Self::LoadAccessFault(addr)
+				| Self::StoreAddressMisaligned(addr)
+				| Self::StoreAccessFault(addr)
+				| Self::InstructionPageFault(addr)
+				| Self::LoadPageFault(addr)
+				| Self::StorePageFault(addr) => *addr,
+				Self::IllegalInstruction(word) => *word as u64,
+				Self::EnvironmentCallFromUMode
+				| Self::EnvironmentCallFromSMode
+				| Self::EnvironmentCallFromMMode => 0,
+			}
+		}
+
+		/// Returns `true` if the trap can not be handled by software and
+		/// should halt the cpu.
+		///
+		/// Access faults are fatal as there is no memory at the address
+		/// (without paging there is nothing a handler could map). All other
+		/// traps can be resumed from a handler (e.g. via `MRET`).
+		pub const fn is_fatal(&self) -> bool {
+			matches!(
+				self,
+				Self::InstructionAccessFault(_)
+					| Self::LoadAccessFault(_)
+					| Self::StoreAccessFault(_)
+			)
+		}
+	}
+
+	#[test]
+	fn cause_code() {
+		// Exception codes from the privileged spec (table "Machine cause
+		// register (mcause) values after trap")
+		let cases = [
+			(Trap::InstructionAddressMisaligned(0), 0),
+			(Trap::InstructionAccessFault(0), 1),
+			(Trap::IllegalInstruction(0), 2),
+			(Trap::Breakpoint(0), 3),
+			(Trap::LoadAddressMisaligned(0), 4),
+			(Trap::LoadAccessFault(0), 5),
+			(Trap::StoreAddressMisaligned(0), 6),
+			(Trap::StoreAccessFault(0), 7),
+			(Trap::EnvironmentCallFromUMode, 8),
+			(Trap::EnvironmentCallFromSMode, 9),
+			(Trap::EnvironmentCallFromMMode, 11),
+			(Trap::InstructionPageFault(0), 12),
+			(Trap::LoadPageFault(0), 13),
+			(Trap::StorePageFault(0), 15),
+		];
+
+		for (trap, code) in cases {
+			assert_eq!(trap.cause_code(), code, "{trap:?}");
+			// Not an interrupt
+			assert_eq!(trap.cause_code() >> 63, 0);
+		}
+	}
+
+	#[test]
+	fn is_fatal() {
+		assert!(Trap::InstructionAccessFault(0).is_fatal());
+		assert!(Trap::LoadAccessFault(0).is_fatal());
+		assert!(Trap::StoreAccessFault(0).is_fatal());
+
+		assert!(!Trap::IllegalInstruction(0).is_fatal());
+		assert!(!Trap::LoadAddressMisaligned(0).is_fatal());
+		assert!(!Trap::EnvironmentCallFromMMode.is_fatal());
+		assert!(!Trap::StorePageFault(0).is_fatal());
+	}
 }
 
 pub mod mem {
-	use crate::adr::Addressable;
+	use std::any::Any;
+	use std::cell::RefCell;
+	use std::collections::{HashMap, HashSet};
+	use std::rc::Rc;
+
+	use elf::elf::Elf;
+
+	use crate::adr::{Addressable, SliceError};
 	use crate::shared::Address;
 	use crate::tra::Trap;
 
-	#[derive(Default, Debug)]
+	/// Memory backed by a single contiguous buffer.
+	///
+	/// Cloning copies the whole buffer, which is expensive for large
+	/// memories (prefer [`PagedMemory`], which only copies touched pages).
+	#[derive(Default, Debug, Clone)]
 	pub struct Memory(pub Vec<u8>);
 
+	impl Memory {
+		/// Allocates a zeroed memory which covers the loadable segments of
+		/// `elf` (see [`Elf::memory_span`]) followed by a stack region of
+		/// `stack_size` bytes, with index `0` corresponding to `base`.
+		///
+		/// The memory is meant to be accessed relative to `base`, e.g.
+		/// through an [`OffsetMemory`]. Returns `None` if `elf` has no
+		/// loadable segments or they start below `base`.
+		pub fn sized_for(
+			elf: &Elf,
+			base: Address,
+			stack_size: usize,
+		) -> Option<Self> {
+			let (start, end) = elf.memory_span()?;
+
+			if start < base {
+				return None;
+			}
+
+			let len =
+				usize::try_from(end - base).ok()?.checked_add(stack_size)?;
+
+			Some(Self(vec![0; len]))
+		}
+	}
+
 	impl Addressable for Memory {
 		type Address = Address;
 		type Error = ();
@@ -194,10 +413,10 @@ pub mod mem {
 			addr: Self::Address,
 			data: &mut [u8],
 		) -> Result<(), Self::Error> {
-			let start = addr as usize;
-			let end = start + data.len();
+			let start = usize::try_from(addr).map_err(|_| ())?;
+			let end = start.checked_add(data.len()).ok_or(())?;
 
-			data.copy_from_slice(&self.0[start..end]);
+			data.copy_from_slice(self.0.get(start..end).ok_or(())?);
 			Ok(())
 		}
 
@@ -206,30 +425,48 @@ pub mod mem {
 			addr: Self::Address,
 			data: &[u8],
 		) -> Result<(), Self::Error> {
-			let start = addr as usize;
-			let end = start + data.len();
+			let start = usize::try_from(addr).map_err(|_| ())?;
+			let end = start.checked_add(data.len()).ok_or(())?;
 
 			// TODO: resize if neccessary?
-			(&mut self.0[start..end]).copy_from_slice(data);
+			self.0.get_mut(start..end).ok_or(())?.copy_from_slice(data);
 			Ok(())
 		}
-	}
 
-	#[derive(Default, Debug)]
-	pub struct MemoryManagementUnit {
-		pub memory: Memory,
+		fn read_slice(
+			&mut self,
+			addr: Self::Address,
+			len: usize,
+		) -> Result<&[u8], SliceError<Self::Error>> {
+			let start = usize::try_from(addr).map_err(|_| ())?;
+			let end = start.checked_add(len).ok_or(())?;
+
+			Ok(self.0.get(start..end).ok_or(())?)
+		}
 	}
 
-	impl MemoryManagementUnit {
-		pub fn tick(&mut self) {}
+	/// Memory which only stores bytes that have been written to.
+	///
+	/// Reads from addresses which were never written return zero. This makes
+	/// it possible to use the whole address space without allocating it up
+	/// front, e.g. for programs which place their stack far away from the
+	/// loaded segments.
+	#[derive(Default, Debug, Clone)]
+	pub struct SparseMemory(pub HashMap<Address, u8>);
+
+	impl SparseMemory {
+		pub fn new() -> Self {
+			Self::default()
+		}
 	}
 
-	impl Addressable for MemoryManagementUnit {
+	impl Addressable for SparseMemory {
 		type Address = Address;
-		type Error = Trap;
+		type Error = ();
 
+		/// The sparse memory spans the whole address space.
 		fn len(&self) -> usize {
-			self.memory.len()
+			usize::MAX
 		}
 
 		fn read(
@@ -237,7 +474,14 @@ pub mod mem {
 			addr: Self::Address,
 			data: &mut [u8],
 		) -> Result<(), Self::Error> {
-			Ok(self.memory.read(addr, data).unwrap())
+			for (offset, byte) in (0..).zip(data.iter_mut()) {
+				*byte = self
+					.0
+					.get(&addr.wrapping_add(offset))
+					.copied()
+					.unwrap_or(0);
+			}
+			Ok(())
 		}
 
 		fn write(
@@ -245,166 +489,1056 @@ pub mod mem {
 			addr: Self::Address,
 			data: &[u8],
 		) -> Result<(), Self::Error> {
-			Ok(self.memory.write(addr, data).unwrap())
+			for (offset, &byte) in (0..).zip(data) {
+				self.0.insert(addr.wrapping_add(offset), byte);
+			}
+			Ok(())
 		}
 	}
-}
 
-pub mod reg {
-	use crate::shared::{FloatWidth, IntWidth};
+	/// Memory which is split into fixed-size pages that are allocated on
+	/// first write.
+	///
+	/// Unlike [`Memory`] only the touched pages are allocated, so a program
+	/// placed at `0x8000_0000` with its stack near the top of the address
+	/// space does not require allocating everything in between. Reads from
+	/// pages which were never written return zero and do not allocate.
+	#[derive(Default, Debug, Clone)]
+	pub struct PagedMemory {
+		pages: HashMap<Address, Box<[u8; PagedMemory::PAGE_SIZE]>>,
+	}
 
-	macro_rules! regs {
-		(
-			$regs:ident {
-				$(
-					$( #[doc = $doc:literal] )*
-					Reg( $idx:literal => $ident:ident, name = $name:literal, desc = $desc:literal )
-				),+
-			}
-		) => {
-			#[allow(non_camel_case_types)]
-			#[repr(usize)]
-			#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-			pub enum $regs {
-				$(
-					#[doc = "Register `"]
-					#[doc = stringify!($ident)]
-					#[doc = "` / `"]
-					#[doc = $name]
-					#[doc = "`: "]
-					#[doc = $desc]
-					$ident = $idx,
-				)+
-			}
+	impl PagedMemory {
+		pub const PAGE_SIZE: usize = 4 * 1024;
 
-			impl $regs {
-				pub const fn name(&self) -> &'static str {
-					match self {
-						$(
-							Self::$ident => $name,
-						)+
-					}
-				}
+		pub fn new() -> Self {
+			Self::default()
+		}
 
-				pub const fn description(&self) -> &'static str {
-					match self {
-						$(
-							Self::$ident => $desc,
-						)+
-					}
-				}
-			}
+		/// Returns the number of allocated pages.
+		pub fn page_count(&self) -> usize {
+			self.pages.len()
+		}
 
-			impl std::convert::From<$regs> for usize {
-				fn from(value: $regs) -> usize {
-					value as usize
-				}
+		/// Splits `addr` into its page number and the offset into the page.
+		const fn split(addr: Address) -> (Address, usize) {
+			let size = Self::PAGE_SIZE as Address;
+			(addr / size, (addr % size) as usize)
+		}
+
+		/// Calls `f` for each page-contiguous chunk starting at `addr`, with
+		/// the page number, the offset into the page and the range of the
+		/// chunk within a buffer of `len` bytes.
+		fn for_each_chunk(
+			addr: Address,
+			len: usize,
+			mut f: impl FnMut(Address, usize, core::ops::Range<usize>),
+		) {
+			let mut done = 0;
+
+			while done < len {
+				let (page, offset) =
+					Self::split(addr.wrapping_add(done as Address));
+				let chunk = (Self::PAGE_SIZE - offset).min(len - done);
+
+				f(page, offset, done..done + chunk);
+				done += chunk;
 			}
+		}
+	}
 
-			impl std::convert::TryFrom<u8> for $regs {
-				type Error = ();
+	impl Addressable for PagedMemory {
+		type Address = Address;
+		type Error = ();
 
-				fn try_from(value: u8) -> Result<Self, Self::Error> {
-					match value {
-						$(
-							$idx => Ok(Self::$ident),
-						)+
-						_ => Err(()),
+		/// The paged memory spans the whole address space.
+		fn len(&self) -> usize {
+			usize::MAX
+		}
+
+		fn read(
+			&mut self,
+			addr: Self::Address,
+			data: &mut [u8],
+		) -> Result<(), Self::Error> {
+			Self::for_each_chunk(addr, data.len(), |page, offset, range| {
+				let len = range.len();
+				let dst = &mut data[range];
+
+				match self.pages.get(&page) {
+					Some(page) => {
+						dst.copy_from_slice(&page[offset..offset + len])
 					}
+					None => dst.fill(0),
 				}
-			}
-		};
+			});
+			Ok(())
+		}
+
+		fn write(
+			&mut self,
+			addr: Self::Address,
+			data: &[u8],
+		) -> Result<(), Self::Error> {
+			Self::for_each_chunk(addr, data.len(), |page, offset, range| {
+				let len = range.len();
+				let page = self
+					.pages
+					.entry(page)
+					.or_insert_with(|| Box::new([0; Self::PAGE_SIZE]));
+
+				page[offset..offset + len]
+					.copy_from_slice(core::ops::Index::index(data, range));
+			});
+			Ok(())
+		}
 	}
 
-	regs! {
-		IntReg {
-			Reg( 0 => x0, name = "Zero", desc = "Always zero"),
-			Reg( 1 => x1, name = "ra", desc = "Return address"),
-			Reg( 2 => x2, name = "sp", desc = "Stack pointer"),
-			Reg( 3 => x3, name = "gp", desc = "Global pointer"),
-			Reg( 4 => x4, name = "tp", desc = "Thread pointer"),
-			Reg( 5 => x5, name = "t0", desc = "Temporary / alternate return address"),
-			Reg( 6 => x6, name = "t1", desc = "Temporary"),
-			Reg( 7 => x7, name = "t2", desc = "Temporary"),
-			Reg( 8 => x8, name = "s0", desc = "Saved register / frame pointer"),
-			Reg( 9 => x9, name = "s1", desc = "Saved register"),
-			Reg(10 => x10, name = "a0", desc = "Function argument / return value"),
-			Reg(11 => x11, name = "a1", desc = "Function argument"),
-			Reg(12 => x12, name = "a2", desc = "Function argument"),
-			Reg(13 => x13, name = "a3", desc = "Function argument"),
-			Reg(14 => x14, name = "a4", desc = "Function argument"),
-			Reg(15 => x15, name = "a5", desc = "Function argument"),
-			Reg(16 => x16, name = "a6", desc = "Function argument"),
-			Reg(17 => x17, name = "a7", desc = "Function argument"),
-			Reg(18 => x18, name = "s2", desc = "Saved register"),
-			Reg(19 => x19, name = "s3", desc = "Saved register"),
-			Reg(20 => x20, name = "s4", desc = "Saved register"),
-			Reg(21 => x21, name = "s5", desc = "Saved register"),
-			Reg(22 => x22, name = "s6", desc = "Saved register"),
-			Reg(23 => x23, name = "s7", desc = "Saved register"),
-			Reg(24 => x24, name = "s8", desc = "Saved register"),
-			Reg(25 => x25, name = "s9", desc = "Saved register"),
-			Reg(26 => x26, name = "s10", desc = "Saved register"),
-			Reg(27 => x27, name = "s11", desc = "Saved register"),
-			Reg(28 => x28, name = "t3", desc = "Temporary"),
-			Reg(29 => x29, name = "t4", desc = "Temporary"),
-			Reg(30 => x30, name = "t5", desc = "Temporary"),
-			Reg(31 => x31, name = "t6", desc = "Temporary")
+	/// Maps the addresses starting at `base` to the start of the wrapped
+	/// [`Addressable`] (e.g. a [`Memory`] created by [`Memory::sized_for`]).
+	///
+	/// Accesses below `base` fail.
+	#[derive(Debug, Clone)]
+	pub struct OffsetMemory<A> {
+		base: Address,
+		inner: A,
+	}
+
+	impl<A> OffsetMemory<A> {
+		pub fn new(base: Address, inner: A) -> Self {
+			Self { base, inner }
+		}
+
+		pub fn base(&self) -> Address {
+			self.base
+		}
+
+		pub fn into_inner(self) -> A {
+			self.inner
 		}
 	}
 
-	#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
-	pub struct IntRegisters([IntWidth; 32]);
+	impl<A> Addressable for OffsetMemory<A>
+	where
+		A: Addressable<Address = Address, Error = ()>,
+	{
+		type Address = Address;
+		type Error = ();
 
-	impl IntRegisters {
-		pub fn get(&self, index: IntReg) -> IntWidth {
-			let index: usize = index.into();
+		fn len(&self) -> usize {
+			self.inner.len()
+		}
 
-			// The `x0` register is always zero.
-			if index == 0 {
-				0
-			} else {
-				self.0[index]
-			}
+		fn read(
+			&mut self,
+			addr: Self::Address,
+			data: &mut [u8],
+		) -> Result<(), Self::Error> {
+			let addr = addr.checked_sub(self.base).ok_or(())?;
+			self.inner.read(addr, data)
 		}
 
-		pub fn set(&mut self, index: IntReg, value: IntWidth) {
-			let index: usize = index.into();
+		fn write(
+			&mut self,
+			addr: Self::Address,
+			data: &[u8],
+		) -> Result<(), Self::Error> {
+			let addr = addr.checked_sub(self.base).ok_or(())?;
+			self.inner.write(addr, data)
+		}
 
-			// The `x0` register is always zero. Any set is voided.
-			if index != 0 {
-				self.0[index] = value;
-			}
+		fn read_slice(
+			&mut self,
+			addr: Self::Address,
+			len: usize,
+		) -> Result<&[u8], SliceError<Self::Error>> {
+			let addr = addr.checked_sub(self.base).ok_or(())?;
+			self.inner.read_slice(addr, len)
 		}
 	}
 
-	impl std::ops::Index<IntReg> for IntRegisters {
-		type Output = IntWidth;
+	/// Direction of an [`Access`].
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum AccessKind {
+		Read,
+		Write,
+	}
 
-		fn index(&self, index: IntReg) -> &Self::Output {
-			&self.0[index as usize]
+	/// Memory access recorded by a [`TracingMemory`].
+	#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+	pub struct Access<T = Address> {
+		pub kind: AccessKind,
+		pub addr: T,
+		/// Bytes read or written (its length is the size of the access).
+		pub data: Vec<u8>,
+	}
+
+	/// Shared log of the accesses of a [`TracingMemory`].
+	pub type AccessLog<T = Address> = Rc<RefCell<Vec<Access<T>>>>;
+
+	/// Wraps an [`Addressable`] and records every successful access to it.
+	///
+	/// The log is shared (see [`TracingMemory::log`]), so it can still be
+	/// inspected after the memory has been moved into the
+	/// [`MemoryManagementUnit`].
+	#[derive(Debug)]
+	pub struct TracingMemory<A: Addressable> {
+		inner: A,
+		log: AccessLog<A::Address>,
+	}
+
+	impl<A: Addressable> TracingMemory<A> {
+		pub fn new(inner: A) -> Self {
+			Self { inner, log: AccessLog::default() }
+		}
+
+		/// Returns a handle to the access log.
+		pub fn log(&self) -> AccessLog<A::Address> {
+			Rc::clone(&self.log)
+		}
+
+		pub fn into_inner(self) -> A {
+			self.inner
+		}
+
+		fn record(&self, kind: AccessKind, addr: A::Address, data: &[u8]) {
+			self.log.borrow_mut().push(Access {
+				kind,
+				addr,
+				data: data.to_vec(),
+			});
 		}
 	}
 
-	impl std::ops::IndexMut<IntReg> for IntRegisters {
-		fn index_mut(&mut self, index: IntReg) -> &mut Self::Output {
-			// TODO: prevent setting of `x0`
-			&mut self.0[index as usize]
+	/// The clone gets its own copy of the log, so accesses to it are not
+	/// recorded in the log of the original (and vice versa).
+	impl<A> Clone for TracingMemory<A>
+	where
+		A: Addressable + Clone,
+		A::Address: Clone,
+	{
+		fn clone(&self) -> Self {
+			Self {
+				inner: self.inner.clone(),
+				log: Rc::new(RefCell::new(self.log.borrow().clone())),
+			}
 		}
 	}
 
-	regs! {
-		FloatReg {
-			Reg( 0 => f0, name = "ft0", desc = "Floating-point temporaries"),
-			Reg( 1 => f1, name = "ft1", desc = "Floating-point temporaries"),
-			Reg( 2 => f2, name = "ft2", desc = "Floating-point temporaries"),
-			Reg( 3 => f3, name = "ft3", desc = "Floating-point temporaries"),
-			Reg( 4 => f4, name = "ft4", desc = "Floating-point temporaries"),
-			Reg( 5 => f5, name = "ft5", desc = "Floating-point temporaries"),
-			Reg( 6 => f6, name = "ft6", desc = "Floating-point temporaries"),
-			Reg( 7 => f7, name = "ft7", desc = "Floating-point temporaries"),
-			Reg( 8 => f8, name = "fs0", desc = "Floating-point saved registers"),
-			Reg( 9 => f9, name = "fs1", desc = "Floating-point saved registers"),
+	impl<A> Addressable for TracingMemory<A>
+	where
+		A: Addressable,
+		A::Address: Copy,
+	{
+		type Address = A::Address;
+		type Error = A::Error;
+
+		fn len(&self) -> usize {
+			self.inner.len()
+		}
+
+		fn read(
+			&mut self,
+			addr: Self::Address,
+			data: &mut [u8],
+		) -> Result<(), Self::Error> {
+			self.inner.read(addr, data)?;
+			self.record(AccessKind::Read, addr, data);
+			Ok(())
+		}
+
+		fn write(
+			&mut self,
+			addr: Self::Address,
+			data: &[u8],
+		) -> Result<(), Self::Error> {
+			self.inner.write(addr, data)?;
+			self.record(AccessKind::Write, addr, data);
+			Ok(())
+		}
+
+		fn read_slice(
+			&mut self,
+			addr: Self::Address,
+			len: usize,
+		) -> Result<&[u8], SliceError<Self::Error>> {
+			let slice = self.inner.read_slice(addr, len)?;
+
+			// Not via `record` as `slice` still borrows `inner`
+			self.log.borrow_mut().push(Access {
+				kind: AccessKind::Read,
+				addr,
+				data: slice.to_vec(),
+			});
+
+			Ok(slice)
+		}
+	}
+
+	/// Reservation set of a load-reserved (`LR`) instruction.
+	///
+	/// Any store overlapping the reserved bytes invalidates it, so a later
+	/// store-conditional (`SC`) fails.
+	#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct Reservation {
+		pub addr: Address,
+		/// Number of reserved bytes starting at `addr`.
+		pub size: Address,
+		pub valid: bool,
+	}
+
+	impl Reservation {
+		/// Checks if `len` bytes at `addr` overlap the reserved bytes.
+		pub fn overlaps(&self, addr: Address, len: Address) -> bool {
+			addr.wrapping_sub(self.addr) < self.size
+				|| self.addr.wrapping_sub(addr) < len
+		}
+	}
+
+	/// Backing store of the [`MemoryManagementUnit`].
+	///
+	/// Implemented for every [`Addressable`] with matching address and error
+	/// types (e.g. [`Memory`], [`SparseMemory`] and [`PagedMemory`]).
+	pub trait Storage:
+		Addressable<Address = Address, Error = ()> + std::fmt::Debug
+	{
+		/// Returns the storage as [`Any`] to downcast it to the concrete
+		/// type (e.g. from a [`FaultHandler`]).
+		fn as_any_mut(&mut self) -> &mut dyn Any;
+
+		/// Returns a deep copy of the storage (used by `Clone` of
+		/// `Box<dyn Storage>`).
+		fn box_clone(&self) -> Box<dyn Storage>;
+	}
+
+	impl<T> Storage for T
+	where
+		T: Addressable<Address = Address, Error = ()>
+			+ std::fmt::Debug
+			+ Clone
+			+ 'static,
+	{
+		fn as_any_mut(&mut self) -> &mut dyn Any {
+			self
+		}
+
+		fn box_clone(&self) -> Box<dyn Storage> {
+			Box::new(self.clone())
+		}
+	}
+
+	impl Clone for Box<dyn Storage> {
+		fn clone(&self) -> Self {
+			self.box_clone()
+		}
+	}
+
+	/// Outcome of a [`FaultHandler`].
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum FaultAction {
+		/// The handler resolved the fault (e.g. mapped the missing memory),
+		/// the access is retried once.
+		Retry,
+		/// The fault is genuine and raised as trap.
+		Fault,
+	}
+
+	/// Handler invoked by the [`MemoryManagementUnit`] if an access misses
+	/// the memory, together with the trap which would be raised.
+	///
+	/// The handler can e.g. log the fault or lazily map the memory (demand
+	/// paging).
+	pub type FaultHandler =
+		fn(memory: &mut dyn Storage, fault: Trap) -> FaultAction;
+
+	#[derive(Debug, Clone)]
+	pub struct MemoryManagementUnit {
+		pub memory: Box<dyn Storage>,
+		fault_handler: Option<FaultHandler>,
+		clock: u64,
+		watchpoints: HashSet<(Address, AccessKind)>,
+		watch_hit: Option<Access>,
+		reservation: Reservation,
+	}
+
+	impl Default for MemoryManagementUnit {
+		fn default() -> Self {
+			Self::new(Box::new(Memory::default()))
+		}
+	}
+
+	impl MemoryManagementUnit {
+		pub fn new(memory: Box<dyn Storage>) -> Self {
+			Self {
+				memory,
+				fault_handler: None,
+				clock: 0,
+				watchpoints: HashSet::new(),
+				watch_hit: None,
+				reservation: Reservation::default(),
+			}
+		}
+
+		pub fn tick(&mut self) {
+			self.clock = self.clock.wrapping_add(1);
+		}
+
+		/// Returns the number of ticks since the mmu was created.
+		pub fn clock(&self) -> u64 {
+			self.clock
+		}
+
+		/// Sets the handler which is invoked if an access misses the
+		/// memory.
+		///
+		/// Without a handler every miss raises an access fault.
+		pub fn set_fault_handler(&mut self, handler: FaultHandler) {
+			self.fault_handler = Some(handler);
+		}
+
+		/// Watches `addr` for accesses of `kind`.
+		///
+		/// The last successful access covering a watched address is kept
+		/// (see [`MemoryManagementUnit::watch_hit`]), the [`crate::cpu::Cpu`]
+		/// stops with a [`Trap::Breakpoint`] carrying the address of the
+		/// accessing instruction after it was executed.
+		pub fn add_watchpoint(&mut self, addr: Address, kind: AccessKind) {
+			self.watchpoints.insert((addr, kind));
+		}
+
+		/// Removes the watchpoint, returning `false` if there was none.
+		pub fn remove_watchpoint(
+			&mut self,
+			addr: Address,
+			kind: AccessKind,
+		) -> bool {
+			self.watchpoints.remove(&(addr, kind))
+		}
+
+		/// Returns the last access which hit a watchpoint (including the
+		/// bytes read or written).
+		pub fn watch_hit(&self) -> Option<&Access> {
+			self.watch_hit.as_ref()
+		}
+
+		pub(crate) fn clear_watch_hit(&mut self) {
+			self.watch_hit = None;
+		}
+
+		/// Returns the current reservation (see [`Reservation`]).
+		pub fn reservation(&self) -> Reservation {
+			self.reservation
+		}
+
+		/// Reserves `size` bytes at `addr` (replacing any previous
+		/// reservation).
+		pub fn reserve(&mut self, addr: Address, size: Address) {
+			self.reservation = Reservation { addr, size, valid: true };
+		}
+
+		/// Replaces the reservation (e.g. when restoring a
+		/// [`crate::cpu::CpuSnapshot`]).
+		pub fn set_reservation(&mut self, reservation: Reservation) {
+			self.reservation = reservation;
+		}
+
+		/// Returns `true` if the reservation is valid and covers exactly
+		/// `size` bytes at `addr`, and invalidates it in any case.
+		pub fn take_reservation(
+			&mut self,
+			addr: Address,
+			size: Address,
+		) -> bool {
+			let reservation = core::mem::take(&mut self.reservation);
+
+			reservation.valid
+				&& reservation.addr == addr
+				&& reservation.size == size
+		}
+
+		/// Reads the instruction bytes at `addr` into `data`.
+		///
+		/// Unlike [`Addressable::read`] a miss raises
+		/// [`Trap::InstructionAccessFault`] (also towards the fault handler)
+		/// and watchpoints are not checked.
+		pub fn fetch(
+			&mut self,
+			addr: Address,
+			data: &mut [u8],
+		) -> Result<(), Trap> {
+			self.access(Trap::InstructionAccessFault(addr), |memory| {
+				memory.read(addr, data)
+			})
+		}
+
+		/// Checks if an access of `kind` to `len` bytes at `addr` covers a
+		/// watched address.
+		fn watched(
+			&self,
+			kind: AccessKind,
+			addr: Address,
+			len: Address,
+		) -> bool {
+			self.watchpoints.iter().any(|&(watched, watched_kind)| {
+				watched_kind == kind && watched.wrapping_sub(addr) < len
+			})
+		}
+
+		fn watch(&mut self, kind: AccessKind, addr: Address, data: &[u8]) {
+			if self.watched(kind, addr, data.len() as Address) {
+				self.watch_hit =
+					Some(Access { kind, addr, data: data.to_vec() });
+			}
+		}
+
+		/// Runs `access` and gives the fault handler the chance to resolve
+		/// a miss before raising `fault`.
+		fn access(
+			&mut self,
+			fault: Trap,
+			mut access: impl FnMut(&mut dyn Storage) -> Result<(), ()>,
+		) -> Result<(), Trap> {
+			if access(self.memory.as_mut()).is_ok() {
+				return Ok(());
+			}
+
+			match self.fault_handler {
+				Some(handler)
+					if handler(self.memory.as_mut(), fault)
+						== FaultAction::Retry =>
+				{
+					access(self.memory.as_mut()).map_err(|_| fault)
+				}
+				_ => Err(fault),
+			}
+		}
+	}
+
+	impl Addressable for MemoryManagementUnit {
+		type Address = Address;
+		type Error = Trap;
+
+		fn len(&self) -> usize {
+			self.memory.len()
+		}
+
+		fn read(
+			&mut self,
+			addr: Self::Address,
+			data: &mut [u8],
+		) -> Result<(), Self::Error> {
+			self.access(Trap::LoadAccessFault(addr), |memory| {
+				memory.read(addr, data)
+			})?;
+			self.watch(AccessKind::Read, addr, data);
+			Ok(())
+		}
+
+		fn write(
+			&mut self,
+			addr: Self::Address,
+			data: &[u8],
+		) -> Result<(), Self::Error> {
+			self.access(Trap::StoreAccessFault(addr), |memory| {
+				memory.write(addr, data)
+			})?;
+			self.watch(AccessKind::Write, addr, data);
+
+			if self.reservation.overlaps(addr, data.len() as Address) {
+				self.reservation.valid = false;
+			}
+
+			Ok(())
+		}
+
+		/// Goes through the fault handler and watchpoints like
+		/// [`Addressable::read`].
+		fn read_slice(
+			&mut self,
+			addr: Self::Address,
+			len: usize,
+		) -> Result<&[u8], SliceError<Self::Error>> {
+			let fault = Trap::LoadAccessFault(addr);
+
+			// Unsupported reads are not a miss the fault handler could resolve
+			self.access(fault, |memory| match memory.read_slice(addr, len) {
+				Err(SliceError::Access(())) => Err(()),
+				_ => Ok(()),
+			})?;
+			let watched = self.watched(AccessKind::Read, addr, len as Address);
+
+			let slice =
+				self.memory.read_slice(addr, len).map_err(
+					|err| match err {
+						SliceError::Unsupported => SliceError::Unsupported,
+						SliceError::Access(()) => SliceError::Access(fault),
+					},
+				)?;
+			if watched {
+				self.watch_hit = Some(Access {
+					kind: AccessKind::Read,
+					addr,
+					data: slice.to_vec(),
+				});
+			}
+
+			Ok(slice)
+		}
+	}
+
+	#[test]
+	fn sized_for() {
+		let bytes =
+			include_bytes!("../../../resources/riscv-tests/rv64ui-p-add");
+		let elf = Elf::from_bytes(bytes).unwrap();
+		let (start, end) = elf.memory_span().unwrap();
+		assert_eq!((start, end), (0x8000_0000, 0x8000_1048));
+
+		let mem = Memory::sized_for(&elf, start, 0x1000).unwrap();
+		assert_eq!(mem.len(), 0x1048 + 0x1000);
+
+		// Leading space between `base` and the segments is covered as well
+		let mem = Memory::sized_for(&elf, start - 0x100, 0).unwrap();
+		assert_eq!(mem.len(), 0x1148);
+
+		// The segments start below `base`
+		assert!(Memory::sized_for(&elf, start + 1, 0x1000).is_none());
+
+		// Accessed relative to `base`
+		let mut mem = OffsetMemory::new(start - 0x100, mem);
+		for (addr, data, _) in elf.load_segments() {
+			mem.write(addr.0, data).unwrap();
+		}
+		assert_eq!(mem.read_u32_le(start), Ok(0x0480_006f));
+		assert_eq!(
+			mem.into_inner().0.get(0x100..0x104),
+			Some(&[0x6f, 0, 0x80, 0x04][..])
+		);
+
+		let mut mem = OffsetMemory::new(0x100, Memory(vec![0; 8]));
+		assert_eq!(mem.write(0x104, &[1, 2, 3, 4]), Ok(()));
+		assert_eq!(mem.read_u32_le(0x104), Ok(0x0403_0201));
+		assert_eq!(mem.read_u8(0xff), Err(()));
+		assert_eq!(mem.read_u8(0x108), Err(()));
+	}
+
+	#[test]
+	fn read_slice() {
+		const KIB: usize = 1024;
+
+		let mut mem = Memory((0..4 * KIB).map(|i| i as u8).collect());
+
+		let slice = mem.read_slice(0x100, KIB).unwrap();
+		assert_eq!(slice.len(), KIB);
+		assert_eq!(slice.first(), Some(&0x00));
+		assert_eq!(slice.last(), Some(&0xff));
+		// Borrowed directly from the backing vector
+		let ptr = slice.as_ptr();
+		assert_eq!(ptr, mem.0[0x100..].as_ptr());
+
+		assert_eq!(
+			mem.read_slice(3 * KIB as Address, 2 * KIB),
+			Err(SliceError::Access(()))
+		);
+		assert_eq!(
+			mem.read_slice(Address::MAX, 1),
+			Err(SliceError::Access(()))
+		);
+
+		// Forwarded by the mmu, including the watchpoints
+		let mut mmu = MemoryManagementUnit::new(Box::new(mem));
+		mmu.add_watchpoint(5, AccessKind::Read);
+		assert_eq!(mmu.read_slice(4, 2), Ok(&[4, 5][..]));
+		assert_eq!(
+			mmu.watch_hit(),
+			Some(&Access {
+				kind: AccessKind::Read,
+				addr: 4,
+				data: vec![4, 5]
+			})
+		);
+		assert_eq!(
+			mmu.read_slice(4 * KIB as Address, 1),
+			Err(SliceError::Access(Trap::LoadAccessFault(4 * KIB as Address)))
+		);
+
+		// Not supported by default
+		assert_eq!(
+			SparseMemory::new().read_slice(0, 8),
+			Err(SliceError::Unsupported)
+		);
+		let mut mmu = MemoryManagementUnit::new(Box::new(SparseMemory::new()));
+		assert_eq!(mmu.read_slice(0, 8), Err(SliceError::Unsupported));
+	}
+
+	#[test]
+	fn signed_reads() {
+		let mut mem =
+			Memory(vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]);
+
+		assert_eq!(mem.read_u8(0), Ok(0xff));
+		assert_eq!(mem.read_i8(0), Ok(-1));
+		assert_eq!(mem.read_i8(0).unwrap() as i64, -1);
+		assert_eq!(mem.read_u8(0).unwrap() as i64, 0xff);
+
+		assert_eq!(mem.read_u16_le(0), Ok(0xffff));
+		assert_eq!(mem.read_i16_le(0), Ok(-1));
+		assert_eq!(mem.read_u32_le(0), Ok(0xffff_ffff));
+		assert_eq!(mem.read_i32_le(0), Ok(-1));
+
+		// Sign bit is only set in the last byte
+		assert_eq!(mem.read_i64_le(0), Ok(i64::MAX));
+		assert_eq!(mem.read_i8(7), Ok(0x7f));
+
+		assert_eq!(mem.read_i32_le(6), Err(()));
+	}
+
+	#[test]
+	fn sparse_memory() {
+		let mut mem = SparseMemory::new();
+
+		assert_eq!(mem.read_u64_le(0).unwrap(), 0);
+		assert_eq!(mem.read_u64_le(0x8000_0000).unwrap(), 0);
+
+		mem.write_u32_le(0, 0xdead_beef).unwrap();
+		mem.write_u64_le(0x8000_0000, 0x0123_4567_89ab_cdef).unwrap();
+		mem.write_u64_le(0xffff_ffff_ffff_fff8, u64::MAX).unwrap();
+
+		assert_eq!(mem.read_u32_le(0).unwrap(), 0xdead_beef);
+		assert_eq!(
+			mem.read_u64_le(0x8000_0000).unwrap(),
+			0x0123_4567_89ab_cdef
+		);
+		assert_eq!(mem.read_u8(0x8000_0000).unwrap(), 0xef);
+		assert_eq!(mem.read_u64_le(0xffff_ffff_ffff_fff8).unwrap(), u64::MAX);
+
+		// Unwritten neighbours still read as zero
+		assert_eq!(mem.read_u32_le(4).unwrap(), 0);
+		assert_eq!(mem.read_u64_le(0x4000_0000).unwrap(), 0);
+		assert_eq!(mem.read_u64_le(0x8000_0008).unwrap(), 0);
+
+		// Only the written bytes are stored
+		assert_eq!(mem.0.len(), 4 + 8 + 8);
+	}
+
+	#[test]
+	fn paged_memory() {
+		let mut mem = PagedMemory::new();
+
+		// Reading untouched memory does not allocate
+		assert_eq!(mem.read_u64_le(0x8000_0000).unwrap(), 0);
+		assert_eq!(mem.page_count(), 0);
+
+		mem.write_u32_le(0x8000_0000, 0xdead_beef).unwrap();
+		mem.write_u64_le(0xffff_ffff_ffff_fff0, u64::MAX).unwrap();
+
+		assert_eq!(mem.page_count(), 2);
+		assert_eq!(mem.read_u32_le(0x8000_0000).unwrap(), 0xdead_beef);
+		assert_eq!(mem.read_u64_le(0xffff_ffff_ffff_fff0).unwrap(), u64::MAX);
+		assert_eq!(mem.read_u64_le(0x8000_0008).unwrap(), 0);
+
+		// Accesses crossing a page boundary touch both pages
+		let boundary = 0x1000_0000 + PagedMemory::PAGE_SIZE as Address - 4;
+		mem.write_u64_le(boundary, 0x0123_4567_89ab_cdef).unwrap();
+
+		assert_eq!(mem.page_count(), 4);
+		assert_eq!(mem.read_u64_le(boundary).unwrap(), 0x0123_4567_89ab_cdef);
+		assert_eq!(mem.read_u32_le(boundary).unwrap(), 0x89ab_cdef);
+		assert_eq!(mem.read_u32_le(boundary + 4).unwrap(), 0x0123_4567);
+	}
+
+	#[test]
+	fn paged_memory_grows_with_touched_pages() {
+		const PAGE: Address = PagedMemory::PAGE_SIZE as Address;
+
+		let mut mem = PagedMemory::new();
+
+		// Write a byte into every 16th page of a 3 GiB range
+		for (n, page) in
+			(0..3 * 1024 * 1024 * 1024 / PAGE).step_by(16 * 1024).enumerate()
+		{
+			mem.write_u8(page * PAGE, 0xff).unwrap();
+			assert_eq!(mem.page_count(), n + 1);
+		}
+
+		// Writes to already allocated pages don't allocate new ones
+		let count = mem.page_count();
+		mem.write(0, &[0xaa; PagedMemory::PAGE_SIZE]).unwrap();
+		assert_eq!(mem.page_count(), count);
+	}
+
+	#[test]
+	fn sparse_memory_as_mmu_storage() {
+		let mut mmu = MemoryManagementUnit::new(Box::new(SparseMemory::new()));
+
+		mmu.write_u32_le(0x10, 0x1234_5678).unwrap();
+		mmu.write_u32_le(0x7fff_fff0, 0x9abc_def0).unwrap();
+
+		assert_eq!(mmu.read_u32_le(0x10).unwrap(), 0x1234_5678);
+		assert_eq!(mmu.read_u32_le(0x7fff_fff0).unwrap(), 0x9abc_def0);
+		assert_eq!(mmu.read_u32_le(0x4000_0000).unwrap(), 0);
+	}
+
+	#[test]
+	fn fault_handler() {
+		const PAGE: Address = 0x1000;
+
+		// Grows the memory to contain the page of the faulting address
+		fn map_page(memory: &mut dyn Storage, fault: Trap) -> FaultAction {
+			let addr = match fault {
+				Trap::LoadAccessFault(addr) | Trap::StoreAccessFault(addr) => {
+					addr
+				}
+				_ => return FaultAction::Fault,
+			};
+
+			match memory.as_any_mut().downcast_mut::<Memory>() {
+				Some(memory) if addr < 4 * PAGE => {
+					let end = (addr / PAGE + 1) * PAGE;
+					memory.0.resize(memory.0.len().max(end as usize), 0);
+					FaultAction::Retry
+				}
+				_ => FaultAction::Fault,
+			}
+		}
+
+		let mut mmu = MemoryManagementUnit::new(Box::new(Memory(Vec::new())));
+		assert_eq!(
+			mmu.read_u32_le(0x1004),
+			Err(Trap::LoadAccessFault(0x1004))
+		);
+
+		mmu.set_fault_handler(map_page);
+		assert_eq!(mmu.len(), 0);
+
+		// Mapped on the first access
+		mmu.write_u32_le(0x1004, 0xdead_beef).unwrap();
+		assert_eq!(mmu.len(), 2 * PAGE as usize);
+		assert_eq!(mmu.read_u32_le(0x1004), Ok(0xdead_beef));
+		assert_eq!(mmu.read_u32_le(0x1ffc), Ok(0));
+
+		// Genuine faults are still raised
+		assert_eq!(
+			mmu.write_u32_le(0x8000, 0),
+			Err(Trap::StoreAccessFault(0x8000))
+		);
+		assert_eq!(mmu.len(), 2 * PAGE as usize);
+
+		// Slice reads are mapped as well
+		assert_eq!(mmu.read_slice(0x2ffe, 2), Ok(&[0, 0][..]));
+		assert_eq!(mmu.len(), 3 * PAGE as usize);
+	}
+
+	#[test]
+	fn tracing_memory() {
+		use crate::cpu::Cpu;
+		use crate::ins::asm::{i_type, s_type};
+		use crate::reg::IntReg;
+
+		let mem = TracingMemory::new(Memory(vec![0; 64]));
+		let log = mem.log();
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(mem);
+		cpu.xregs.set(IntReg::x1, 0x1234_5678);
+
+		// sw x1, 32(x0)
+		cpu.mmu.write_u32_le(0, s_type(0b0100011, 0b010, 0, 1, 32)).unwrap();
+		// lbu x2, 33(x0)
+		cpu.mmu.write_u32_le(4, i_type(0b0000011, 2, 0b100, 0, 33)).unwrap();
+		log.borrow_mut().clear();
+
+		cpu.tick();
+		cpu.tick();
+		assert_eq!(cpu.xregs.get(IntReg::x2), 0x56);
+
+		// Instruction fetches are recorded as well
+		let data: Vec<_> = log
+			.borrow()
+			.iter()
+			.filter(|access| access.addr >= 32)
+			.cloned()
+			.collect();
+		assert_eq!(
+			data,
+			[
+				Access {
+					kind: AccessKind::Write,
+					addr: 32,
+					data: vec![0x78, 0x56, 0x34, 0x12],
+				},
+				Access { kind: AccessKind::Read, addr: 33, data: vec![0x56] },
+			]
+		);
+		assert!(log
+			.borrow()
+			.iter()
+			.any(|access| access.addr == 4 && access.kind == AccessKind::Read));
+
+		// Failed accesses are not recorded
+		let mut mem = TracingMemory::new(Memory(vec![0; 8]));
+		assert_eq!(mem.read_slice(4, 8), Err(SliceError::Access(())));
+		assert!(mem.log().borrow().is_empty());
+		assert_eq!(mem.read_slice(4, 2), Ok(&[0, 0][..]));
+		assert_eq!(mem.log().borrow().len(), 1);
+	}
+}
+
+pub mod reg {
+	use crate::shared::{FloatWidth, IntWidth};
+
+	macro_rules! regs {
+		(
+			$regs:ident {
+				$(
+					$( #[doc = $doc:literal] )*
+					Reg( $idx:literal => $ident:ident, name = $name:literal, desc = $desc:literal )
+				),+
+			}
+		) => {
+			#[allow(non_camel_case_types)]
+			#[repr(usize)]
+			#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+			pub enum $regs {
+				$(
+					#[doc = "Register `"]
+					#[doc = stringify!($ident)]
+					#[doc = "` / `"]
+					#[doc = $name]
+					#[doc = "`: "]
+					#[doc = $desc]
+					$ident = $idx,
+				)+
+			}
+
+			impl $regs {
+				pub const fn name(&self) -> &'static str {
+					match self {
+						$(
+							Self::$ident => $name,
+						)+
+					}
+				}
+
+				pub const fn description(&self) -> &'static str {
+					match self {
+						$(
+							Self::$ident => $desc,
+						)+
+					}
+				}
+			}
+
+			impl std::convert::From<$regs> for usize {
+				fn from(value: $regs) -> usize {
+					value as usize
+				}
+			}
+
+			impl std::convert::TryFrom<u8> for $regs {
+				type Error = ();
+
+				fn try_from(value: u8) -> Result<Self, Self::Error> {
+					match value {
+						$(
+							$idx => Ok(Self::$ident),
+						)+
+						_ => Err(()),
+					}
+				}
+			}
+		};
+	}
+
+	regs! {
+		IntReg {
+			Reg( 0 => x0, name = "Zero", desc = "Always zero"),
+			Reg( 1 => x1, name = "ra", desc = "Return address"),
+			Reg( 2 => x2, name = "sp", desc = "Stack pointer"),
+			Reg( 3 => x3, name = "gp", desc = "Global pointer"),
+			Reg( 4 => x4, name = "tp", desc = "Thread pointer"),
+			Reg( 5 => x5, name = "t0", desc = "Temporary / alternate return address"),
+			Reg( 6 => x6, name = "t1", desc = "Temporary"),
+			Reg( 7 => x7, name = "t2", desc = "Temporary"),
+			Reg( 8 => x8, name = "s0", desc = "Saved register / frame pointer"),
+			Reg( 9 => x9, name = "s1", desc = "Saved register"),
+			Reg(10 => x10, name = "a0", desc = "Function argument / return value"),
+			Reg(11 => x11, name = "a1", desc = "Function argument"),
+			Reg(12 => x12, name = "a2", desc = "Function argument"),
+			Reg(13 => x13, name = "a3", desc = "Function argument"),
+			Reg(14 => x14, name = "a4", desc = "Function argument"),
+			Reg(15 => x15, name = "a5", desc = "Function argument"),
+			Reg(16 => x16, name = "a6", desc = "Function argument"),
+			Reg(17 => x17, name = "a7", desc = "Function argument"),
+			Reg(18 => x18, name = "s2", desc = "Saved register"),
+			Reg(19 => x19, name = "s3", desc = "Saved register"),
+			Reg(20 => x20, name = "s4", desc = "Saved register"),
+			Reg(21 => x21, name = "s5", desc = "Saved register"),
+			Reg(22 => x22, name = "s6", desc = "Saved register"),
+			Reg(23 => x23, name = "s7", desc = "Saved register"),
+			Reg(24 => x24, name = "s8", desc = "Saved register"),
+			Reg(25 => x25, name = "s9", desc = "Saved register"),
+			Reg(26 => x26, name = "s10", desc = "Saved register"),
+			Reg(27 => x27, name = "s11", desc = "Saved register"),
+			Reg(28 => x28, name = "t3", desc = "Temporary"),
+			Reg(29 => x29, name = "t4", desc = "Temporary"),
+			Reg(30 => x30, name = "t5", desc = "Temporary"),
+			Reg(31 => x31, name = "t6", desc = "Temporary")
+		}
+	}
+
+	#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub struct IntRegisters([IntWidth; 32]);
+
+	impl IntRegisters {
+		pub fn get(&self, index: IntReg) -> IntWidth {
+			let index: usize = index.into();
+
+			// The `x0` register is always zero.
+			if index == 0 {
+				0
+			} else {
+				self.0[index]
+			}
+		}
+
+		pub fn set(&mut self, index: IntReg, value: IntWidth) {
+			let index: usize = index.into();
+
+			// The `x0` register is always zero. Any set is voided.
+			if index != 0 {
+				self.0[index] = value;
+			}
+		}
+	}
+
+	impl std::ops::Index<IntReg> for IntRegisters {
+		type Output = IntWidth;
+
+		fn index(&self, index: IntReg) -> &Self::Output {
+			&self.0[index as usize]
+		}
+	}
+
+	impl std::ops::IndexMut<IntReg> for IntRegisters {
+		fn index_mut(&mut self, index: IntReg) -> &mut Self::Output {
+			// TODO: prevent setting of `x0`
+			&mut self.0[index as usize]
+		}
+	}
+
+	regs! {
+		FloatReg {
+			Reg( 0 => f0, name = "ft0", desc = "Floating-point temporaries"),
+			Reg( 1 => f1, name = "ft1", desc = "Floating-point temporaries"),
+			Reg( 2 => f2, name = "ft2", desc = "Floating-point temporaries"),
+			Reg( 3 => f3, name = "ft3", desc = "Floating-point temporaries"),
+			Reg( 4 => f4, name = "ft4", desc = "Floating-point temporaries"),
+			Reg( 5 => f5, name = "ft5", desc = "Floating-point temporaries"),
+			Reg( 6 => f6, name = "ft6", desc = "Floating-point temporaries"),
+			Reg( 7 => f7, name = "ft7", desc = "Floating-point temporaries"),
+			Reg( 8 => f8, name = "fs0", desc = "Floating-point saved registers"),
+			Reg( 9 => f9, name = "fs1", desc = "Floating-point saved registers"),
 			Reg(10 => f10, name = "fa0", desc = "Floating-point arguments/return values"),
 			Reg(11 => f11, name = "fa1", desc = "Floating-point arguments/return values"),
 			Reg(12 => f12, name = "fa2", desc = "Floating-point arguments/return values"),
@@ -428,112 +1562,1266 @@ pub mod reg {
 			Reg(30 => f30, name = "ft10", desc = "Floating-point temporaries"),
 			Reg(31 => f31, name = "ft11", desc = "Floating-point temporaries")
 		}
-	}
+	}
+
+	#[derive(Default, Debug, Clone, Copy, PartialEq)]
+	pub struct FloatRegisters([FloatWidth; 32]);
+
+	impl FloatRegisters {
+		pub fn get(&self, index: FloatReg) -> FloatWidth {
+			let index: usize = index.into();
+			self.0[index]
+		}
+
+		pub fn set(&mut self, index: FloatReg, value: FloatWidth) {
+			let index: usize = index.into();
+			self.0[index] = value;
+		}
+	}
+}
+
+/// Control and status registers (`Zicsr`).
+///
+/// # References
+/// - <https://github.com/riscv/riscv-isa-manual> (privileged spec, chapter
+///   "Control and Status Registers")
+pub mod csr {
+	use std::collections::HashMap;
+
+	/// Address of a control and status register (12 bits).
+	pub type CsrAddress = u16;
+
+	/// Floating-point: Accrued exceptions.
+	pub const FFLAGS: CsrAddress = 0x001;
+
+	/// Floating-point: Dynamic rounding mode.
+	pub const FRM: CsrAddress = 0x002;
+
+	/// [`FFLAGS`]: Inexact.
+	pub const FFLAGS_NX: u64 = 0x01;
+
+	/// [`FFLAGS`]: Invalid operation.
+	pub const FFLAGS_NV: u64 = 0x10;
+
+	/// Unprivileged counter: Cycle counter for `rdcycle` (read-only).
+	pub const CYCLE: CsrAddress = 0xc00;
+
+	/// Unprivileged counter: Timer for `rdtime` (read-only).
+	pub const TIME: CsrAddress = 0xc01;
+
+	/// Unprivileged counter: Instructions-retired counter for `rdinstret`
+	/// (read-only).
+	pub const INSTRET: CsrAddress = 0xc02;
+
+	/// Machine information register: Id of the hart running the code
+	/// (read-only).
+	pub const MHARTID: CsrAddress = 0xf14;
+
+	/// Machine trap setup: Base address of the trap handler.
+	pub const MTVEC: CsrAddress = 0x305;
+
+	/// Machine trap handling: Scratch register for machine trap handlers.
+	pub const MSCRATCH: CsrAddress = 0x340;
+
+	/// Machine trap handling: Address of the instruction which trapped.
+	pub const MEPC: CsrAddress = 0x341;
+
+	/// Machine trap handling: Cause of the trap.
+	pub const MCAUSE: CsrAddress = 0x342;
+
+	/// Machine trap handling: Faulting address or instruction.
+	pub const MTVAL: CsrAddress = 0x343;
+
+	/// Returns `true` if the csr at `addr` is read-only (`addr[11:10]` is
+	/// `0b11`).
+	pub const fn is_read_only(addr: CsrAddress) -> bool {
+		(addr >> 10) & 0b11 == 0b11
+	}
+
+	/// Privilege level of a hart (ordered from least to most privileged).
+	#[derive(
+		Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash,
+	)]
+	pub enum Privilege {
+		User = 0,
+		Supervisor = 1,
+		Hypervisor = 2,
+		#[default]
+		Machine = 3,
+	}
+
+	/// Returns the lowest privilege level which can access the csr at
+	/// `addr` (`addr[9:8]`).
+	pub const fn min_privilege(addr: CsrAddress) -> Privilege {
+		match (addr >> 8) & 0b11 {
+			0b00 => Privilege::User,
+			0b01 => Privilege::Supervisor,
+			0b10 => Privilege::Hypervisor,
+			_ => Privilege::Machine,
+		}
+	}
+
+	/// Checks the access to the csr at `addr` from `privilege` against the
+	/// permissions encoded in the address.
+	pub fn check_access(
+		addr: CsrAddress,
+		privilege: Privilege,
+		write: bool,
+	) -> Result<(), CsrError> {
+		if privilege < min_privilege(addr) {
+			return Err(CsrError::Privileged(addr));
+		}
+
+		if write && is_read_only(addr) {
+			return Err(CsrError::ReadOnly(addr));
+		}
+
+		Ok(())
+	}
+
+	/// Error raised when accessing a csr.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum CsrError {
+		/// Write to a read-only csr.
+		ReadOnly(CsrAddress),
+		/// Access from a privilege level below the one of the csr.
+		Privileged(CsrAddress),
+	}
+
+	/// Values of the csrs without special behaviour.
+	///
+	/// Csrs which were never written read as `0`.
+	#[derive(Default, Debug, Clone, PartialEq, Eq)]
+	pub struct Csrs(HashMap<CsrAddress, u64>);
+
+	impl Csrs {
+		pub fn get(&self, addr: CsrAddress) -> u64 {
+			self.0.get(&addr).copied().unwrap_or(0)
+		}
+
+		pub fn set(&mut self, addr: CsrAddress, value: u64) {
+			self.0.insert(addr, value);
+		}
+	}
+
+	#[test]
+	fn read_only() {
+		assert!(is_read_only(MHARTID));
+		assert!(is_read_only(CYCLE));
+		assert!(is_read_only(TIME));
+		assert!(is_read_only(INSTRET));
+		assert!(!is_read_only(MSCRATCH));
+	}
+
+	#[test]
+	fn access_permissions() {
+		assert_eq!(min_privilege(CYCLE), Privilege::User);
+		assert_eq!(min_privilege(FFLAGS), Privilege::User);
+		assert_eq!(min_privilege(MSCRATCH), Privilege::Machine);
+
+		assert_eq!(check_access(CYCLE, Privilege::User, false), Ok(()));
+		assert_eq!(
+			check_access(CYCLE, Privilege::Machine, true),
+			Err(CsrError::ReadOnly(CYCLE))
+		);
+		assert_eq!(check_access(MSCRATCH, Privilege::Machine, true), Ok(()));
+		assert_eq!(
+			check_access(MSCRATCH, Privilege::Supervisor, false),
+			Err(CsrError::Privileged(MSCRATCH))
+		);
+	}
+}
+
+pub mod sym {
+	use std::fmt;
+	use std::io::Write;
+
+	use elf::elf::OwnedElf;
+
+	use crate::cpu::Tracer;
+	use crate::ins::{self, Instruction};
+	use crate::reg::IntRegisters;
+	use crate::shared::Address;
+
+	/// Tracer which writes a line for every instruction to `out`, annotating
+	/// addresses with the function and object symbols of an elf (see
+	/// [`elf::elf::Elf::symbol_at_address`]).
+	///
+	/// Lines have the form `<pc> <symbol>: <name> -> <target> <symbol>`,
+	/// where the symbols are only present if the address lies within a
+	/// symbol and the target only for branches and jumps. Write errors are
+	/// ignored, tracing never stops the cpu.
+	#[derive(Debug)]
+	pub struct SymbolTracer<W> {
+		elf: Option<OwnedElf>,
+		pub out: W,
+	}
+
+	impl<W> SymbolTracer<W> {
+		/// Creates a tracer which writes plain addresses to `out`.
+		pub fn new(out: W) -> Self {
+			Self { elf: None, out }
+		}
+
+		/// Creates a tracer which annotates addresses with the symbols of
+		/// `elf` (e.g. the elf the cpu runs).
+		pub fn with_elf(elf: OwnedElf, out: W) -> Self {
+			Self { elf: Some(elf), out }
+		}
+	}
+
+	impl<W: Write + fmt::Debug> Tracer for SymbolTracer<W> {
+		fn on_instruction(
+			&mut self,
+			pc: Address,
+			word: u32,
+			inst: &Instruction,
+			xregs: &IntRegisters,
+		) {
+			let elf = self.elf.as_ref().map(OwnedElf::elf);
+			let annotated = |addr: Address| match elf
+				.as_ref()
+				.and_then(|elf| elf.symbol_at_address(addr))
+			{
+				Some((name, _, 0)) => format!("{:#x} <{}>", addr, name),
+				Some((name, _, offset)) => {
+					format!("{:#x} <{}+{:#x}>", addr, name, offset)
+				}
+				None => format!("{:#x}", addr),
+			};
+
+			let mut line = format!("{}: {}", annotated(pc), inst.name);
+			if let Some(target) = ins::jump_target(xregs, word, pc) {
+				line.push_str(" -> ");
+				line.push_str(&annotated(target));
+			}
+
+			let _ = writeln!(self.out, "{}", line);
+		}
+	}
+
+	#[test]
+	fn symbol_tracer() {
+		use std::cell::RefCell;
+		use std::rc::Rc;
+
+		use elf::elf::Elf;
+
+		use crate::adr::Addressable;
+		use crate::cpu::Cpu;
+		use crate::mem::PagedMemory;
+		use crate::reg::IntReg;
+
+		const STRLEN: Address = 0x8000_20e0;
+
+		let bytes =
+			include_bytes!("../../../resources/riscv-tests/rv64ui-v-add");
+		let elf = Elf::from_bytes(bytes).unwrap();
+
+		let mut memory = PagedMemory::new();
+		for (addr, data, _) in elf.load_segments() {
+			memory.write(addr.0, data).unwrap();
+		}
+
+		// `strlen` of an empty string (in `.bss`), returning to `_start`
+		// (no function symbol)
+		let mut cpu = Cpu::with_reset_vector(STRLEN);
+		cpu.mmu.memory = Box::new(memory);
+		cpu.set_regs(&[(IntReg::x1, 0x8000_0000), (IntReg::x10, 0x8000_8000)]);
+
+		let tracer = Rc::new(RefCell::new(SymbolTracer::with_elf(
+			OwnedElf::from_bytes(bytes.to_vec()).unwrap(),
+			Vec::new(),
+		)));
+		cpu.set_tracer(tracer.clone());
+		let mut plain = cpu.clone();
+		cpu.run_bounded(4);
+
+		assert_eq!(
+			String::from_utf8(tracer.borrow().out.clone()).unwrap(),
+			"0x800020e0 <strlen>: LBU\n0x800020e4 <strlen+0x4>: BEQ -> \
+			 0x80002100 <strlen+0x20>\n0x80002100 <strlen+0x20>: \
+			 ADDI\n0x80002104 <strlen+0x24>: JALR -> 0x80000000\n"
+		);
+
+		// Without symbols
+		let tracer = Rc::new(RefCell::new(SymbolTracer::new(Vec::new())));
+		plain.set_tracer(tracer.clone());
+		plain.tick();
+		assert_eq!(tracer.borrow().out, b"0x800020e0: LBU\n");
+	}
+}
+
+pub mod cpu {
+	use std::cell::RefCell;
+	use std::collections::HashSet;
+	use std::fmt;
+	use std::rc::Rc;
+
+	use elf::header::consts::ident::data::Endianness;
+
+	use crate::adr::Addressable;
+	use crate::csr::{self, CsrAddress, CsrError, Csrs, Privilege};
+	use crate::ins::{self, rvc, Instruction, INSTRUCTIONS};
+	use crate::mem::{Memory, MemoryManagementUnit, Reservation};
+	use crate::reg::{FloatRegisters, IntReg, IntRegisters};
+	use crate::shared::{Address, IntWidth};
+	use crate::tra::Trap;
+
+	pub type Result<T, E = Trap> = std::result::Result<T, E>;
+
+	/// Reset vector of the cpus created by [`Cpu::new`] (the start of the
+	/// RAM in most RISC-V systems, e.g. `qemu virt` and `spike`).
+	pub const DEFAULT_RESET_VECTOR: Address = 0x8000_0000;
+
+	pub const PC_STEP: Address = 4;
+	/// Step of the `pc` for compressed instructions.
+	pub const PC_STEP_COMPRESSED: Address = 2;
+
+	/// Note type of the register state in core dumps.
+	const NT_PRSTATUS: u32 = 1;
+
+	/// Size of `struct elf_prstatus` on 64-bit RISC-V Linux.
+	const PRSTATUS_SIZE: usize = 376;
+
+	/// Offset of `pr_cursig` (signal which caused the dump) in
+	/// `struct elf_prstatus`.
+	const PRSTATUS_CURSIG: usize = 12;
+
+	/// Offset of `pr_reg` (`pc` followed by `x1` to `x31`) in
+	/// `struct elf_prstatus`.
+	const PRSTATUS_REG: usize = 112;
+
+	/// Alignment of the memory in core dumps.
+	const CORE_ALIGN: u64 = 0x1000;
+
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+	pub enum Status {
+		Initializing,
+		Running,
+		Halted,
+	}
+
+	impl Default for Status {
+		fn default() -> Self {
+			Self::Initializing
+		}
+	}
+
+	/// Reason why [`Cpu::run_bounded`] stopped.
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum RunOutcome {
+		/// The cpu halted (see [`Cpu::halt_trap`]).
+		Halted(Option<Trap>),
+		/// The maximum number of steps was reached.
+		StepLimit,
+		/// The instruction at the address re-executed without changing the
+		/// state (e.g. `beq x0, x0, 0`), so the cpu would spin forever (see
+		/// [`Cpu::set_livelock_detection`]).
+		Livelock(Address),
+	}
+
+	/// Architectural state of a [`Cpu`] which can be restored later on.
+	///
+	/// The memory is not part of the snapshot as it is potentially very large
+	/// and needs to be handled separately.
+	#[derive(Debug, Clone, PartialEq)]
+	pub struct CpuSnapshot {
+		pub status: Status,
+		pub halt_trap: Option<Trap>,
+		/// Set after a breakpoint was hit (see [`Cpu::add_breakpoint`]).
+		pub resume_breakpoint: bool,
+		pub pc: Address,
+		pub xregs: IntRegisters,
+		pub fregs: FloatRegisters,
+		pub csrs: Csrs,
+		pub cycles: u64,
+		pub instret: u64,
+		pub privilege: Privilege,
+		/// Reservation of the mmu (see [`Reservation`]).
+		pub reservation: Reservation,
+		pub endianness: Endianness,
+	}
+
+	impl CpuSnapshot {
+		/// Checks if both snapshots are equal apart from the counters.
+		fn same_state(&self, other: &Self) -> bool {
+			let other = Self {
+				cycles: self.cycles,
+				instret: self.instret,
+				..other.clone()
+			};
+
+			*self == other
+		}
+	}
+
+	/// Records how often each entry of [`INSTRUCTIONS`] has been executed.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	pub struct Coverage {
+		hits: [u64; INSTRUCTIONS.len()],
+	}
+
+	impl Default for Coverage {
+		fn default() -> Self {
+			Self { hits: [0; INSTRUCTIONS.len()] }
+		}
+	}
+
+	impl Coverage {
+		pub fn new() -> Self {
+			Self::default()
+		}
+
+		fn record(&mut self, index: usize) {
+			self.hits[index] += 1;
+		}
+
+		/// Returns how often the instruction with the given `name` was
+		/// executed or `None` if no such instruction exists.
+		pub fn hits(&self, name: &str) -> Option<u64> {
+			INSTRUCTIONS
+				.iter()
+				.position(|inst| inst.name() == name)
+				.map(|index| self.hits[index])
+		}
+
+		pub fn is_covered(&self, name: &str) -> bool {
+			self.hits(name).is_some_and(|hits| hits > 0)
+		}
+
+		/// Returns all executed instructions together with their hit count.
+		pub fn executed(
+			&self,
+		) -> impl Iterator<Item = (&'static Instruction, u64)> + '_ {
+			instructions().iter().zip(self.hits).filter(|(_, hits)| *hits > 0)
+		}
+
+		/// Returns all instructions which were never executed.
+		pub fn missed(
+			&self,
+		) -> impl Iterator<Item = &'static Instruction> + '_ {
+			instructions()
+				.iter()
+				.zip(self.hits)
+				.filter(|(_, hits)| *hits == 0)
+				.map(|(inst, _)| inst)
+		}
+	}
+
+	impl fmt::Display for Coverage {
+		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			writeln!(
+				f,
+				"Coverage: {}/{}",
+				self.executed().count(),
+				INSTRUCTIONS.len()
+			)?;
+
+			writeln!(f, "Executed:")?;
+			for (inst, hits) in self.executed() {
+				writeln!(
+					f,
+					"\t{}/{}: {}",
+					inst.extension(),
+					inst.name(),
+					hits
+				)?;
+			}
+
+			writeln!(f, "Never executed:")?;
+			for inst in self.missed() {
+				writeln!(f, "\t{}/{}", inst.extension(), inst.name())?;
+			}
+
+			Ok(())
+		}
+	}
+
+	/// Returns [`INSTRUCTIONS`] as a `'static` slice.
+	fn instructions() -> &'static [Instruction] {
+		&INSTRUCTIONS
+	}
+
+	/// Handler invoked by the [`Cpu`] for every trap raised while executing.
+	///
+	/// The `pc` points to the instruction which raised the trap. The handler
+	/// can e.g. skip it by advancing the `pc` or stop the cpu with
+	/// [`Cpu::halt`].
+	pub type TrapHandler = fn(cpu: &mut Cpu, trap: Trap);
+
+	/// Sink for the instructions and traps of a [`Cpu`] (see
+	/// [`Cpu::set_tracer`]), e.g. to write them to a file or collect them in
+	/// a test.
+	///
+	/// Both methods do nothing by default, `()` is the no-op tracer.
+	pub trait Tracer: fmt::Debug {
+		/// Called for every decoded instruction before it is executed.
+		///
+		/// `xregs` are the registers before the instruction, e.g. to
+		/// resolve jump targets (see [`crate::ins::jump_target`]).
+		fn on_instruction(
+			&mut self,
+			_pc: Address,
+			_word: u32,
+			_inst: &Instruction,
+			_xregs: &IntRegisters,
+		) {
+		}
+
+		/// Called for every trap before it is handled.
+		fn on_trap(&mut self, _trap: &Trap) {}
+	}
+
+	impl Tracer for () {}
+
+	/// Event recorded by a [`VecTracer`].
+	#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+	pub enum TraceEvent {
+		Instruction { pc: Address, word: u32, name: &'static str },
+		Trap(Trap),
+	}
+
+	/// Tracer which collects all events.
+	#[derive(Default, Debug, Clone, PartialEq, Eq)]
+	pub struct VecTracer {
+		pub events: Vec<TraceEvent>,
+	}
+
+	impl Tracer for VecTracer {
+		fn on_instruction(
+			&mut self,
+			pc: Address,
+			word: u32,
+			inst: &Instruction,
+			_xregs: &IntRegisters,
+		) {
+			self.events.push(TraceEvent::Instruction {
+				pc,
+				word,
+				name: inst.name(),
+			});
+		}
+
+		fn on_trap(&mut self, trap: &Trap) {
+			self.events.push(TraceEvent::Trap(*trap));
+		}
+	}
+
+	/// Cloning a cpu deep-copies its memory (see [`Storage::box_clone`]).
+	/// Only the tracer (see [`Cpu::set_tracer`]) is shared between the cpu
+	/// and its clones, so it receives the events of all of them.
+	///
+	/// [`Storage::box_clone`]: crate::mem::Storage::box_clone
+	#[derive(Default, Debug, Clone)]
+	pub struct Cpu {
+		// Status
+		status: Status,
+		halt_trap: Option<Trap>,
+		trap_handler: Option<TrapHandler>,
+
+		// Tracing
+		// Shared with the caller (and clones of the cpu)
+		tracer: Option<Rc<RefCell<dyn Tracer>>>,
+
+		// Coverage (only recorded if enabled)
+		coverage: Option<Coverage>,
+
+		// Stop `run_bounded` if the state stops changing
+		detect_livelock: bool,
+
+		// Debugging
+		breakpoints: HashSet<Address>,
+		// Set after a breakpoint was hit, so the instruction is executed
+		// when resuming
+		resume_breakpoint: bool,
+
+		// Address of the first instruction after a reset
+		reset_vector: Address,
+
+		// Id of the hart (`mhartid`)
+		hart_id: u64,
+
+		// Privilege level (only machine mode is implemented)
+		privilege: Privilege,
+
+		// Byte order of loads and stores
+		endianness: Endianness,
+
+		// Counters (`cycle` and `instret`)
+		cycles: u64,
+		instret: u64,
+
+		// Registers
+		pub pc: Address,
+		pub xregs: IntRegisters,
+		pub fregs: FloatRegisters,
+		csrs: Csrs,
+
+		// Memory
+		pub mmu: MemoryManagementUnit,
+	}
+
+	impl Cpu {
+		/// Creates a cpu whose `pc` points to [`DEFAULT_RESET_VECTOR`].
+		///
+		/// [`Cpu::default`] uses a reset vector of `0` instead.
+		pub fn new() -> Self {
+			Self::with_reset_vector(DEFAULT_RESET_VECTOR)
+		}
+
+		/// Creates a cpu whose `pc` points to `reset_vector`.
+		pub fn with_reset_vector(reset_vector: Address) -> Self {
+			Self { reset_vector, pc: reset_vector, ..Self::default() }
+		}
+
+		/// Returns the address of the first instruction after a reset.
+		pub fn reset_vector(&self) -> Address {
+			self.reset_vector
+		}
+
+		/// Sets the id of the hart (read via `mhartid`).
+		pub fn with_hart_id(mut self, hart_id: u64) -> Self {
+			self.hart_id = hart_id;
+			self
+		}
+
+		pub fn hart_id(&self) -> u64 {
+			self.hart_id
+		}
+
+		/// Returns the current privilege level of the hart.
+		pub fn privilege(&self) -> Privilege {
+			self.privilege
+		}
+
+		/// Returns the byte order of explicit memory accesses (loads, stores
+		/// and `AMO`s), corresponding to the `MBE` field of `mstatus`.
+		pub fn endianness(&self) -> Endianness {
+			self.endianness
+		}
+
+		/// Sets the byte order of loads and stores.
+		///
+		/// Instruction fetches are deliberately not affected, as the
+		/// specification requires instructions to always be stored as
+		/// little-endian parcels.
+		pub fn set_endianness(&mut self, endianness: Endianness) {
+			self.endianness = endianness;
+		}
+
+		/// Loads a half word from `addr` in the configured byte order.
+		pub fn load_u16(&mut self, addr: Address) -> Result<u16, Trap> {
+			match self.endianness {
+				Endianness::Little => self.mmu.read_u16_le(addr),
+				Endianness::Big => self.mmu.read_u16_be(addr),
+			}
+		}
+
+		/// Loads a word from `addr` in the configured byte order.
+		pub fn load_u32(&mut self, addr: Address) -> Result<u32, Trap> {
+			match self.endianness {
+				Endianness::Little => self.mmu.read_u32_le(addr),
+				Endianness::Big => self.mmu.read_u32_be(addr),
+			}
+		}
+
+		/// Loads a double word from `addr` in the configured byte order.
+		pub fn load_u64(&mut self, addr: Address) -> Result<u64, Trap> {
+			match self.endianness {
+				Endianness::Little => self.mmu.read_u64_le(addr),
+				Endianness::Big => self.mmu.read_u64_be(addr),
+			}
+		}
+
+		/// Stores a half word to `addr` in the configured byte order.
+		pub fn store_u16(
+			&mut self,
+			addr: Address,
+			value: u16,
+		) -> Result<(), Trap> {
+			match self.endianness {
+				Endianness::Little => self.mmu.write_u16_le(addr, value),
+				Endianness::Big => self.mmu.write_u16_be(addr, value),
+			}
+		}
+
+		/// Stores a word to `addr` in the configured byte order.
+		pub fn store_u32(
+			&mut self,
+			addr: Address,
+			value: u32,
+		) -> Result<(), Trap> {
+			match self.endianness {
+				Endianness::Little => self.mmu.write_u32_le(addr, value),
+				Endianness::Big => self.mmu.write_u32_be(addr, value),
+			}
+		}
+
+		/// Stores a double word to `addr` in the configured byte order.
+		pub fn store_u64(
+			&mut self,
+			addr: Address,
+			value: u64,
+		) -> Result<(), Trap> {
+			match self.endianness {
+				Endianness::Little => self.mmu.write_u64_le(addr, value),
+				Endianness::Big => self.mmu.write_u64_be(addr, value),
+			}
+		}
+
+		/// Returns the value of the csr at `addr`.
+		pub fn read_csr(&self, addr: CsrAddress) -> u64 {
+			match addr {
+				csr::CYCLE => self.cycles,
+				csr::TIME => self.mmu.clock(),
+				csr::INSTRET => self.instret,
+				csr::MHARTID => self.hart_id,
+				_ => self.csrs.get(addr),
+			}
+		}
+
+		/// Writes `value` to the csr at `addr`, failing if the csr is
+		/// read-only.
+		pub fn write_csr(
+			&mut self,
+			addr: CsrAddress,
+			value: u64,
+		) -> std::result::Result<(), CsrError> {
+			if csr::is_read_only(addr) {
+				return Err(CsrError::ReadOnly(addr));
+			}
+
+			self.csrs.set(addr, value);
+			Ok(())
+		}
+
+		/// Returns the value of the integer register `reg`.
+		pub fn get_reg(&self, reg: IntReg) -> IntWidth {
+			self.xregs.get(reg)
+		}
+
+		/// Sets all integer registers in `regs` (e.g. to prepare the state
+		/// of a test).
+		pub fn set_regs(&mut self, regs: &[(IntReg, IntWidth)]) {
+			for &(reg, value) in regs {
+				self.xregs.set(reg, value);
+			}
+		}
+
+		pub fn tick(&mut self) {
+			if self.status == Status::Halted {
+				return;
+			}
+
+			if !self.resume_breakpoint && self.breakpoints.contains(&self.pc) {
+				self.resume_breakpoint = true;
+				self.handle_breakpoint(Trap::Breakpoint(self.pc));
+				return;
+			}
+			self.resume_breakpoint = false;
+
+			self.cycles = self.cycles.wrapping_add(1);
+
+			let inst_addr = self.pc;
+
+			let (word, len) = match self.fetch() {
+				Ok(fetched) => fetched,
+				Err(trap) => {
+					self.handle_trap(trap, inst_addr);
+					return;
+				}
+			};
+
+			self.step_pc(len);
+
+			let (index, inst) = match self.decode(word) {
+				Ok(decoded) => decoded,
+				Err(trap) => {
+					self.handle_trap(trap, inst_addr);
+					return;
+				}
+			};
+
+			if let Some(tracer) = &self.tracer {
+				tracer.borrow_mut().on_instruction(
+					inst_addr,
+					word,
+					inst,
+					&self.xregs,
+				);
+			}
+
+			if let Some(coverage) = &mut self.coverage {
+				coverage.record(index);
+			}
+
+			self.mmu.clear_watch_hit();
+
+			if let Err(trap) = self.execute(inst, word, inst_addr, len) {
+				self.handle_trap(trap, inst_addr);
+				return;
+			}
+
+			self.instret = self.instret.wrapping_add(1);
+			self.mmu.tick();
+
+			// The access already happened, so the cpu stops after the
+			// instruction (the accessed address is kept in the watch hit)
+			if self.mmu.watch_hit().is_some() {
+				self.handle_breakpoint(Trap::Breakpoint(inst_addr));
+			}
+		}
+
+		/// Decodes and runs the single instruction `word` as if it was
+		/// located at `addr`, without fetching it from memory (e.g. to test
+		/// the semantics of an instruction in isolation).
+		///
+		/// The `pc` is set to the instruction following `addr` before the
+		/// instruction runs, so jumps and branches behave as in
+		/// [`Cpu::tick`]. Compressed instructions (upper 16 bits zero) are
+		/// expanded first. Traps are returned instead of handled and the
+		/// counters, breakpoints and trace/coverage hooks are not involved.
+		pub fn execute_word(
+			&mut self,
+			word: u32,
+			addr: Address,
+		) -> Result<()> {
+			let (word, len) = if rvc::is_compressed(word as u16) {
+				let parcel = word as u16;
+				let word = rvc::expand(parcel)
+					.filter(|_| word >> 16 == 0)
+					.ok_or(Trap::IllegalInstruction(word))?;
+
+				(word, PC_STEP_COMPRESSED)
+			} else {
+				(word, PC_STEP)
+			};
+
+			self.pc = addr.wrapping_add(len);
+
+			let (_, inst) = self.decode(word)?;
+			self.execute(inst, word, addr, len)
+		}
+
+		/// Runs `inst` for the `len` bytes long `word` located at `addr` (the
+		/// `pc` already points to the next instruction).
+		///
+		/// In debug builds the outcome of conditional branches is checked
+		/// against a reference implementation
+		/// ([`crate::ins::expected_branch_pc`]) to catch regressions in the
+		/// comparisons.
+		pub(crate) fn execute(
+			&mut self,
+			inst: &Instruction,
+			word: u32,
+			addr: Address,
+			len: Address,
+		) -> Result<()> {
+			#[cfg(debug_assertions)]
+			let expected = crate::ins::expected_branch_pc(
+				&self.xregs,
+				word,
+				addr,
+				self.pc,
+			);
+
+			let result = (inst.op)(self, word, addr, len);
+			// Reset `x0` to `0` (allowed through Index)
+			// TODO: fix
+			self.xregs[IntReg::x0] = 0;
+			result?;
+
+			#[cfg(debug_assertions)]
+			if let Some(expected) = expected {
+				assert_eq!(
+					self.pc, expected,
+					"Branch {} at {:#x} diverged from the reference",
+					inst.name, addr
+				);
+			}
+
+			Ok(())
+		}
 
-	#[derive(Default, Debug, Clone, Copy, PartialEq)]
-	pub struct FloatRegisters([FloatWidth; 32]);
+		/// Ticks the cpu until it halts or `max_steps` ticks were run.
+		///
+		/// With livelock detection enabled, it also stops as soon as a tick
+		/// leaves the `pc` and the registers unchanged. Only an instruction
+		/// jumping to itself can do so, which without interrupts repeats
+		/// forever.
+		pub fn run_bounded(&mut self, max_steps: usize) -> RunOutcome {
+			for _ in 0..max_steps {
+				if self.status == Status::Halted {
+					break;
+				}
 
-	impl FloatRegisters {
-		pub fn get(&self, index: FloatReg) -> FloatWidth {
-			let index: usize = index.into();
-			self.0[index]
+				let before = self.detect_livelock.then(|| self.snapshot());
+				let instret = self.instret;
+
+				self.tick();
+
+				// Only a retired instruction can spin, breakpoints and traps
+				// handled by the trap handler leave the state untouched
+				if self.status != Status::Halted
+					&& self.instret != instret
+					&& before.is_some_and(|before| {
+						before.same_state(&self.snapshot())
+					}) {
+					return RunOutcome::Livelock(self.pc);
+				}
+			}
+
+			if self.status == Status::Halted {
+				RunOutcome::Halted(self.halt_trap)
+			} else {
+				RunOutcome::StepLimit
+			}
 		}
 
-		pub fn set(&mut self, index: FloatReg, value: FloatWidth) {
-			let index: usize = index.into();
-			self.0[index] = value;
+		/// Enables or disables the livelock detection of
+		/// [`Cpu::run_bounded`] (disabled by default).
+		pub fn set_livelock_detection(&mut self, enabled: bool) {
+			self.detect_livelock = enabled;
 		}
-	}
-}
 
-pub mod cpu {
-	use crate::adr::Addressable;
-	use crate::ins::{Instruction, INSTRUCTIONS};
-	use crate::mem::MemoryManagementUnit;
-	use crate::reg::{FloatRegisters, IntReg, IntRegisters};
-	use crate::shared::{Address, IntWidth, Word};
-	use crate::tra::Trap;
+		/// Starts recording which instructions are executed.
+		///
+		/// Any previously recorded coverage is discarded.
+		pub fn enable_coverage(&mut self) {
+			self.coverage = Some(Coverage::new());
+		}
 
-	pub type Result<T, E = Trap> = std::result::Result<T, E>;
+		/// Returns the recorded coverage if enabled via
+		/// [`Cpu::enable_coverage`].
+		pub fn coverage(&self) -> Option<&Coverage> {
+			self.coverage.as_ref()
+		}
 
-	pub const PC_STEP: Address = 4;
+		/// Captures the current register files, csrs, counters, privilege
+		/// level, reservation, endianness, `pc` and status (including the
+		/// halting trap and a pending breakpoint resume).
+		pub fn snapshot(&self) -> CpuSnapshot {
+			CpuSnapshot {
+				status: self.status,
+				halt_trap: self.halt_trap,
+				resume_breakpoint: self.resume_breakpoint,
+				pc: self.pc,
+				xregs: self.xregs,
+				fregs: self.fregs,
+				csrs: self.csrs.clone(),
+				cycles: self.cycles,
+				instret: self.instret,
+				privilege: self.privilege,
+				reservation: self.mmu.reservation(),
+				endianness: self.endianness,
+			}
+		}
 
-	#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-	pub enum Status {
-		Initializing,
-		Running,
-		Halted,
-	}
+		/// Restores the state captured by [`Cpu::snapshot`].
+		pub fn restore(&mut self, snap: &CpuSnapshot) {
+			self.status = snap.status;
+			self.halt_trap = snap.halt_trap;
+			self.resume_breakpoint = snap.resume_breakpoint;
+			self.pc = snap.pc;
+			self.xregs = snap.xregs;
+			self.fregs = snap.fregs;
+			self.csrs = snap.csrs.clone();
+			self.cycles = snap.cycles;
+			self.instret = snap.instret;
+			self.privilege = snap.privilege;
+			self.mmu.set_reservation(snap.reservation);
+			self.endianness = snap.endianness;
+		}
 
-	impl Default for Status {
-		fn default() -> Self {
-			Self::Initializing
+		/// Copies the flat binary `bytes` (e.g. hand assembled instructions)
+		/// into memory at `addr` and points the `pc` to its start.
+		pub fn load_binary(
+			&mut self,
+			addr: Address,
+			bytes: &[u8],
+		) -> Result<()> {
+			self.mmu.write(addr, bytes)?;
+			self.pc = addr;
+
+			Ok(())
 		}
-	}
 
-	#[derive(Default, Debug)]
-	pub struct Cpu {
-		// Status
-		status: Status,
+		/// Returns the current status of the cpu.
+		pub fn status(&self) -> Status {
+			self.status
+		}
 
-		// Registers
-		pub pc: Address,
-		pub xregs: IntRegisters,
-		pub fregs: FloatRegisters,
+		/// Returns the trap which halted the cpu (if any).
+		pub fn halt_trap(&self) -> Option<Trap> {
+			self.halt_trap
+		}
 
-		// Memory
-		pub mmu: MemoryManagementUnit,
-	}
+		/// Sets the handler which is invoked for every trap.
+		///
+		/// Without a handler the cpu halts on the first trap.
+		pub fn set_trap_handler(&mut self, handler: TrapHandler) {
+			self.trap_handler = Some(handler);
+		}
 
-	impl Cpu {
-		pub fn tick(&mut self) {
-			let inst_addr = self.pc;
+		/// Stops before the instruction at `addr` is executed by raising a
+		/// [`Trap::Breakpoint`].
+		///
+		/// The trap is passed to the handler set with
+		/// [`Cpu::set_trap_handler`] (never to `mtvec`) or halts the cpu
+		/// without one. Ticking again after the handler returned executes
+		/// the instruction.
+		pub fn add_breakpoint(&mut self, addr: Address) {
+			self.breakpoints.insert(addr);
+		}
 
-			let word = match self.fetch() {
-				Ok(word) => word,
-				Err(trap) => {
-					self.handle_trap(trap);
-					return;
-				}
+		/// Removes the breakpoint at `addr`, returning `false` if there was
+		/// none.
+		pub fn remove_breakpoint(&mut self, addr: Address) -> bool {
+			self.breakpoints.remove(&addr)
+		}
+
+		/// Sets the tracer which is notified of every instruction and trap
+		/// (e.g. a [`crate::sym::SymbolTracer`] for symbol annotated
+		/// traces).
+		///
+		/// The tracer is shared, so the caller can keep a handle to inspect
+		/// it (clones of the cpu share it as well).
+		pub fn set_tracer(&mut self, tracer: Rc<RefCell<dyn Tracer>>) {
+			self.tracer = Some(tracer);
+		}
+
+		/// Writes the state of the cpu and `mem` as a core dump (`ET_CORE`
+		/// elf), e.g. for post-mortem analysis after a fatal trap.
+		///
+		/// The registers are stored in a `NT_PRSTATUS` note laid out as on
+		/// 64-bit RISC-V Linux and `mem` in a single `PT_LOAD` segment at
+		/// address `0`.
+		pub fn write_core_dump(&self, mem: &Memory) -> Vec<u8> {
+			use elf::header::consts::ident::class::EI_CLASS_64;
+			use elf::header::consts::ident::data::EI_DATA_LE;
+			use elf::header::consts::ident::osabi::EI_OSABI_SYSTEMV;
+			use elf::header::consts::ident::version::EI_VERSION_CURRENT;
+			use elf::header::consts::machine::E_MACHINE_RISCV;
+			use elf::header::consts::typ::E_TYPE_ET_CORE;
+			use elf::header::elf64::{Header, EHSIZE};
+			use elf::header::Ident;
+			use elf::program_header::consts::flags::{
+				P_FLAGS_PF_R, P_FLAGS_PF_W, P_FLAGS_PF_X,
+			};
+			use elf::program_header::consts::typ::{
+				P_TYPE_PT_LOAD, P_TYPE_PT_NOTE,
+			};
+			use elf::program_header::elf64::{ProgramHeader, PHENTSIZE};
+			use elf::section_header::elf64::SHENTSIZE;
+
+			// Name padded to 4 bytes
+			const NOTE_NAME: &[u8] = b"CORE\0\0\0\0";
+
+			let mut note = Vec::new();
+			note.extend_from_slice(&5_u32.to_le_bytes());
+			note.extend_from_slice(&(PRSTATUS_SIZE as u32).to_le_bytes());
+			note.extend_from_slice(&NT_PRSTATUS.to_le_bytes());
+			note.extend_from_slice(NOTE_NAME);
+			note.extend_from_slice(&self.prstatus());
+
+			let phoff = EHSIZE as u64;
+			let note_offset = phoff + 2 * PHENTSIZE as u64;
+			let load_offset =
+				(note_offset + note.len() as u64).next_multiple_of(CORE_ALIGN);
+
+			let header = Header {
+				e_ident: Ident([
+					0x7f,
+					b'E',
+					b'L',
+					b'F',
+					EI_CLASS_64,
+					EI_DATA_LE,
+					EI_VERSION_CURRENT as u8,
+					EI_OSABI_SYSTEMV,
+					0,
+					0,
+					0,
+					0,
+					0,
+					0,
+					0,
+					0,
+				]),
+				e_type: E_TYPE_ET_CORE,
+				e_machine: E_MACHINE_RISCV,
+				e_version: EI_VERSION_CURRENT,
+				e_phoff: phoff,
+				e_ehsize: EHSIZE,
+				e_phentsize: PHENTSIZE,
+				e_phnum: 2,
+				e_shentsize: SHENTSIZE,
+				..Default::default()
 			};
+			let pheaders = [
+				ProgramHeader {
+					p_type: P_TYPE_PT_NOTE,
+					p_offset: note_offset,
+					p_filesz: note.len() as u64,
+					p_align: 4,
+					..Default::default()
+				},
+				ProgramHeader {
+					p_type: P_TYPE_PT_LOAD,
+					p_flags: P_FLAGS_PF_R | P_FLAGS_PF_W | P_FLAGS_PF_X,
+					p_offset: load_offset,
+					p_filesz: mem.0.len() as u64,
+					p_memsz: mem.0.len() as u64,
+					p_align: CORE_ALIGN,
+					..Default::default()
+				},
+			];
+
+			let mut bytes = vec![0; load_offset as usize + mem.0.len()];
+
+			// The buffer holds the headers, so writing them can not fail
+			let mut rest = header
+				.to_bytes(Endianness::Little, &mut bytes)
+				.expect("Buffer too small for the header");
+			for ph in &pheaders {
+				rest = ph
+					.to_bytes(Endianness::Little, rest)
+					.expect("Buffer too small for the program headers");
+			}
 
-			// TODO: check instruction size (p. 8/26)
+			let note_offset = note_offset as usize;
+			bytes[note_offset..note_offset + note.len()]
+				.copy_from_slice(&note);
+			bytes[load_offset as usize..].copy_from_slice(&mem.0);
 
-			self.step_pc(PC_STEP);
+			bytes
+		}
 
-			let inst = self.decode(word).unwrap_or_else(|| {
-				panic!(
-					"Unknown instruction (pc: 0x{:016x}; inst: 0b{:032b}; \
-					 should: {:#?})",
-					// TODO: remove riscv_decode
-					inst_addr,
-					word,
-					riscv_decode::decode(word)
-				)
-			});
+		/// Returns the `struct elf_prstatus` of the core dump.
+		fn prstatus(&self) -> [u8; PRSTATUS_SIZE] {
+			let mut desc = [0; PRSTATUS_SIZE];
+
+			// Signal as delivered by Linux for the trap
+			let signal: u16 = match self.halt_trap {
+				Some(Trap::IllegalInstruction(_)) => 4,
+				Some(Trap::Breakpoint(_)) => 5,
+				Some(
+					Trap::InstructionAddressMisaligned(_)
+					| Trap::LoadAddressMisaligned(_)
+					| Trap::StoreAddressMisaligned(_),
+				) => 7,
+				Some(
+					Trap::InstructionAccessFault(_)
+					| Trap::LoadAccessFault(_)
+					| Trap::StoreAccessFault(_)
+					| Trap::InstructionPageFault(_)
+					| Trap::LoadPageFault(_)
+					| Trap::StorePageFault(_),
+				) => 11,
+				_ => 0,
+			};
+			// `pr_info.si_signo` and `pr_cursig`
+			desc[..4].copy_from_slice(&(signal as i32).to_le_bytes());
+			desc[PRSTATUS_CURSIG..PRSTATUS_CURSIG + 2]
+				.copy_from_slice(&signal.to_le_bytes());
+
+			let mut regs = desc[PRSTATUS_REG..].chunks_exact_mut(8);
+			regs.next().unwrap().copy_from_slice(&self.pc.to_le_bytes());
+			for (idx, reg) in (1..32).zip(regs) {
+				let value = self.xregs.get(IntReg::try_from(idx).unwrap());
+				reg.copy_from_slice(&value.to_le_bytes());
+			}
+
+			desc
+		}
+
+		/// Halts the cpu, any further [`Cpu::tick`] is a no-op.
+		pub fn halt(&mut self, trap: Trap) {
+			self.status = Status::Halted;
+			self.halt_trap = Some(trap);
+		}
+
+		fn trace_trap(&self, trap: &Trap) {
+			if let Some(tracer) = &self.tracer {
+				tracer.borrow_mut().on_trap(trap);
+			}
+		}
+
+		/// Handles a breakpoint set with [`Cpu::add_breakpoint`].
+		fn handle_breakpoint(&mut self, trap: Trap) {
+			self.trace_trap(&trap);
+
+			match self.trap_handler {
+				Some(handler) => handler(self, trap),
+				None => self.halt(trap),
+			}
+		}
 
-			println!(">> Running: {}/{}", inst.extension, inst.name);
+		/// Handles `trap` raised by the instruction at `addr`.
+		///
+		/// A handler set with [`Cpu::set_trap_handler`] takes precedence.
+		/// Otherwise fatal traps (see [`Trap::is_fatal`]) halt the cpu and
+		/// resumable ones are vectored to `mtvec`, if set, or halt as well.
+		///
+		/// The `pc` is reset to `addr` first, as it may already point past
+		/// the instruction (decode and execute traps).
+		fn handle_trap(&mut self, trap: Trap, addr: Address) {
+			self.pc = addr;
+			self.trace_trap(&trap);
+
+			if let Some(handler) = self.trap_handler {
+				handler(self, trap);
+				return;
+			}
 
-			if let Err(trap) = (inst.op)(self, word, inst_addr) {
-				self.handle_trap(trap);
-				// Reset `x0` to `0` (allowed through Index)
-				// TODO: fix
-				self.xregs[IntReg::x0] = 0;
+			let mtvec = self.read_csr(csr::MTVEC);
+			if trap.is_fatal() || mtvec == 0 {
+				self.halt(trap);
 				return;
 			}
 
-			self.mmu.tick();
+			self.csrs.set(csr::MEPC, addr);
+			self.csrs.set(csr::MCAUSE, trap.cause_code());
+			self.csrs.set(csr::MTVAL, trap.tval());
+			// `mtvec[1:0]` is the mode, synchronous exceptions always jump to
+			// the base (also in vectored mode)
+			self.pc = mtvec & !0b11;
 		}
 
-		fn handle_trap(&mut self, trap: Trap) {}
+		/// Fetches the instruction at `pc` and returns the instruction word
+		/// together with the length of the instruction.
+		///
+		/// Compressed instructions are expanded to their base instruction.
+		/// Instructions are always little-endian, independent of
+		/// [`Cpu::endianness`], as required by the specification (the
+		/// `MBE` field only applies to explicit memory accesses).
+		///
+		/// The `pc` is not changed, so on a trap it still points to the
+		/// instruction which could not be fetched.
+		fn fetch(&mut self) -> Result<(u32, Address), Trap> {
+			let mut parcel = [0; 2];
+			self.mmu.fetch(self.pc, &mut parcel)?;
+			let parcel = u16::from_le_bytes(parcel);
+
+			if ins::is_illegal_parcel(parcel) {
+				return Err(Trap::IllegalInstruction(parcel.into()));
+			}
+
+			if rvc::is_compressed(parcel) {
+				return rvc::expand(parcel)
+					.map(|word| (word, PC_STEP_COMPRESSED))
+					.ok_or(Trap::IllegalInstruction(parcel.into()));
+			}
+
+			let mut word = [0; 4];
+			self.mmu.fetch(self.pc, &mut word)?;
 
-		fn fetch(&mut self) -> Result<u32, Trap> {
-			match self.mmu.read_u32_le(self.pc) {
-				Ok(word) => Ok(word),
-				Err(err) => {
-					self.step_pc(PC_STEP);
-					Err(err)
+			match u32::from_le_bytes(word) {
+				word if ins::is_illegal_word(word) => {
+					Err(Trap::IllegalInstruction(word))
 				}
+				word => Ok((word, PC_STEP)),
 			}
 		}
 
@@ -541,16 +2829,883 @@ pub mod cpu {
 			self.pc = self.pc.wrapping_add(step);
 		}
 
-		fn decode(&mut self, word: u32) -> Option<&Instruction> {
+		/// Returns the matching instruction and its index into
+		/// [`INSTRUCTIONS`].
+		///
+		/// Fails with [`Trap::IllegalInstruction`] carrying `word` if no
+		/// instruction matches.
+		fn decode(
+			&mut self,
+			word: u32,
+		) -> Result<(usize, &'static Instruction), Trap> {
 			// TODO: cache
 
-			for inst in &INSTRUCTIONS {
-				if word & inst.mask == inst.reqd {
-					return Some(inst);
-				}
+			instructions()
+				.iter()
+				.enumerate()
+				.find(|(_, inst)| word & inst.mask == inst.reqd)
+				.ok_or(Trap::IllegalInstruction(word))
+		}
+	}
+
+	#[test]
+	fn coverage() {
+		use crate::ins::asm::{i_type, r_type};
+		use crate::mem::Memory;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+
+		// addi x1, x0, 5
+		cpu.mmu.memory.write_u32_le(0, i_type(0b0010011, 1, 0, 0, 5)).unwrap();
+		// addi x2, x0, 7
+		cpu.mmu.memory.write_u32_le(4, i_type(0b0010011, 2, 0, 0, 7)).unwrap();
+		// add x3, x1, x2
+		cpu.mmu
+			.memory
+			.write_u32_le(8, r_type(0b0110011, 3, 0, 1, 2, 0))
+			.unwrap();
+
+		// Not recorded unless enabled
+		cpu.tick();
+		assert!(cpu.coverage().is_none());
+
+		cpu.pc = 0;
+		cpu.enable_coverage();
+		for _ in 0..3 {
+			cpu.tick();
+		}
+
+		assert_eq!(cpu.xregs.get(IntReg::x3), 12);
+
+		let coverage = cpu.coverage().unwrap();
+		assert_eq!(coverage.hits("ADDI"), Some(2));
+		assert_eq!(coverage.hits("ADD"), Some(1));
+		assert!(coverage.is_covered("ADDI"));
+		assert!(coverage.is_covered("ADD"));
+		assert!(!coverage.is_covered("FDIV.D"));
+		assert_eq!(coverage.hits("NOT-AN-INSTRUCTION"), None);
+
+		let executed: Vec<_> =
+			coverage.executed().map(|(inst, _)| inst.name()).collect();
+		assert_eq!(executed, ["ADDI", "ADD"]);
+		assert!(coverage.missed().any(|inst| inst.name() == "FDIV.D"));
+		assert_eq!(
+			coverage.missed().count(),
+			INSTRUCTIONS.len() - executed.len()
+		);
+
+		let report = coverage.to_string();
+		assert!(report.starts_with(&format!(
+			"Coverage: 2/{}\nExecuted:\n",
+			INSTRUCTIONS.len()
+		)));
+		assert!(report.contains("\tRV32I/ADD: 1\n"));
+		assert!(report.contains("\tRV32D/FDIV.D\n"));
+	}
+
+	#[test]
+	fn counters() {
+		use crate::csr::{CYCLE, INSTRET, TIME};
+		use crate::ins::asm::i_type;
+		use crate::mem::Memory;
+
+		const N: u64 = 10;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+
+		for idx in 0..N {
+			// addi x1, x1, 1
+			cpu.mmu
+				.memory
+				.write_u32_le(idx * 4, i_type(0b0010011, 1, 0, 1, 1))
+				.unwrap();
+		}
+		// rdcycle x2 (csrrs x2, cycle, x0)
+		cpu.mmu
+			.memory
+			.write_u32_le(N * 4, i_type(0b1110011, 2, 0b010, 0, CYCLE as i32))
+			.unwrap();
+
+		for _ in 0..=N {
+			cpu.tick();
+		}
+
+		assert_eq!(cpu.xregs.get(IntReg::x1), N as IntWidth);
+		// The `rdcycle` itself may or may not be counted
+		let cycles = cpu.xregs.get(IntReg::x2) as u64;
+		assert!((N..=N + 1).contains(&cycles), "{cycles}");
+		assert_eq!(cpu.read_csr(INSTRET), N + 1);
+		assert_eq!(cpu.read_csr(TIME), N + 1);
+
+		// The counters are read-only
+		assert_eq!(cpu.write_csr(CYCLE, 0), Err(CsrError::ReadOnly(CYCLE)));
+	}
+
+	#[test]
+	fn illegal_instruction_halts() {
+		use crate::mem::Memory;
+
+		const UNKNOWN: u32 = 0xffff_ffff;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		cpu.mmu.memory.write_u32_le(0, UNKNOWN).unwrap();
+
+		cpu.tick();
+
+		assert_eq!(cpu.status(), Status::Halted);
+		assert_eq!(cpu.halt_trap(), Some(Trap::IllegalInstruction(UNKNOWN)));
+
+		// Ticking a halted cpu does nothing
+		let pc = cpu.pc;
+		cpu.tick();
+		assert_eq!(cpu.pc, pc);
+	}
+
+	#[test]
+	fn clone_is_deep() {
+		use crate::ins::asm::i_type;
+		use crate::mem::PagedMemory;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(PagedMemory::new());
+		cpu.mmu.write(0x1000, &[1, 2, 3, 4]).unwrap();
+		cpu.set_regs(&[(IntReg::x1, 5)]);
+
+		let mut clone = cpu.clone();
+		assert_eq!(clone.get_reg(IntReg::x1), 5);
+		assert_eq!(clone.mmu.read_u32_le(0x1000), Ok(0x0403_0201));
+
+		// addi x1, x1, 1
+		clone.execute_word(i_type(0b0010011, 1, 0, 1, 1), 0x100).unwrap();
+		clone.mmu.write(0x1000, &[0xff]).unwrap();
+		clone.mmu.write(0x2000, &[0xff]).unwrap();
+
+		assert_eq!(clone.get_reg(IntReg::x1), 6);
+		assert_eq!(clone.mmu.read_u32_le(0x1000), Ok(0x0403_02ff));
+
+		// The original is untouched
+		assert_eq!(cpu.get_reg(IntReg::x1), 5);
+		assert_eq!(cpu.pc, 0);
+		assert_eq!(cpu.mmu.read_u32_le(0x1000), Ok(0x0403_0201));
+		assert_eq!(cpu.mmu.read_u8(0x2000), Ok(0));
+		let paged =
+			cpu.mmu.memory.as_any_mut().downcast_mut::<PagedMemory>().unwrap();
+		assert_eq!(paged.page_count(), 1);
+	}
+
+	#[test]
+	fn tracer() {
+		use crate::ins::asm::{i_type, r_type};
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+
+		let program = [
+			// addi x1, x0, 5
+			i_type(0b0010011, 1, 0, 0, 5),
+			// addi x2, x0, 7
+			i_type(0b0010011, 2, 0, 0, 7),
+			// add x3, x1, x2
+			r_type(0b0110011, 3, 0, 1, 2, 0),
+		];
+		for (addr, &word) in (0..).step_by(4).zip(&program) {
+			cpu.mmu.memory.write_u32_le(addr, word).unwrap();
+		}
+
+		let tracer = Rc::new(RefCell::new(VecTracer::default()));
+		cpu.set_tracer(tracer.clone());
+		let mut clone = cpu.clone();
+
+		// Halts on the zeroed memory after the program
+		cpu.run_bounded(10);
+
+		let names: Vec<_> = tracer
+			.borrow()
+			.events
+			.iter()
+			.map(|event| match event {
+				TraceEvent::Instruction { name, .. } => *name,
+				TraceEvent::Trap(_) => "trap",
+			})
+			.collect();
+		assert_eq!(names, ["ADDI", "ADDI", "ADD", "trap"]);
+
+		assert_eq!(
+			tracer.borrow().events[2],
+			TraceEvent::Instruction { pc: 8, word: program[2], name: "ADD" }
+		);
+		assert_eq!(
+			tracer.borrow().events[3],
+			TraceEvent::Trap(Trap::IllegalInstruction(0))
+		);
+
+		// Clones share the tracer
+		clone.tick();
+		assert_eq!(tracer.borrow().events.len(), 5);
+		assert_eq!(
+			tracer.borrow().events[4],
+			TraceEvent::Instruction { pc: 0, word: program[0], name: "ADDI" }
+		);
+
+		// The no-op tracer
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		cpu.set_tracer(Rc::new(RefCell::new(())));
+		cpu.tick();
+		assert_eq!(cpu.halt_trap(), Some(Trap::IllegalInstruction(0)));
+	}
+
+	#[test]
+	fn livelock() {
+		use crate::ins::asm::{b_type, i_type, j_type};
+
+		fn load(words: &[u32]) -> Cpu {
+			let mut cpu = Cpu::default();
+			cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+			for (addr, &word) in (0..).step_by(4).zip(words) {
+				cpu.mmu.memory.write_u32_le(addr, word).unwrap();
+			}
+			cpu
+		}
+
+		// beq x0, x0, 0
+		let spin = b_type(0b1100011, 0b000, 0, 0, 0);
+
+		// Only bounded by the number of steps unless enabled
+		let mut cpu = load(&[spin]);
+		assert_eq!(cpu.run_bounded(100), RunOutcome::StepLimit);
+		assert_eq!(cpu.cycles, 100);
+
+		let mut cpu = load(&[i_type(0b0010011, 1, 0, 0, 5), spin]);
+		cpu.set_livelock_detection(true);
+		assert_eq!(cpu.run_bounded(100), RunOutcome::Livelock(4));
+		assert_eq!(cpu.cycles, 2);
+		assert_eq!(cpu.status(), Status::Initializing);
+
+		// jal x1, 0 changes `x1` on the first iteration only
+		let mut cpu = load(&[j_type(0b1101111, 1, 0)]);
+		cpu.set_livelock_detection(true);
+		assert_eq!(cpu.run_bounded(100), RunOutcome::Livelock(0));
+		assert_eq!(cpu.cycles, 2);
+
+		// Counting down to `0` is not a livelock
+		// addi x1, x0, 3; addi x1, x1, -1; bne x1, x0, -4
+		let mut cpu = load(&[
+			i_type(0b0010011, 1, 0, 0, 3),
+			i_type(0b0010011, 1, 0, 1, -1),
+			b_type(0b1100011, 0b001, 1, 0, -4),
+		]);
+		cpu.set_livelock_detection(true);
+		assert_eq!(
+			cpu.run_bounded(100),
+			RunOutcome::Halted(Some(Trap::IllegalInstruction(0)))
+		);
+
+		// Stopping at a breakpoint does not change the state either
+		let mut cpu = load(&[i_type(0b0010011, 1, 0, 0, 5)]);
+		cpu.add_breakpoint(0);
+		cpu.set_trap_handler(|cpu, trap| {
+			if !matches!(trap, Trap::Breakpoint(_)) {
+				cpu.halt(trap);
 			}
+		});
+		cpu.set_livelock_detection(true);
+		assert_eq!(
+			cpu.run_bounded(100),
+			RunOutcome::Halted(Some(Trap::IllegalInstruction(0)))
+		);
+		assert_eq!(cpu.xregs.get(IntReg::x1), 5);
+	}
+
+	#[test]
+	fn execute_word() {
+		use crate::ins::asm::{i_type, r_type};
+
+		// No memory is needed
+		let mut cpu = Cpu::default();
+
+		// addi x1, x0, 5
+		cpu.execute_word(i_type(0b0010011, 1, 0, 0, 5), 0x100).unwrap();
+		assert_eq!(cpu.get_reg(IntReg::x1), 5);
+		assert_eq!(cpu.pc, 0x104);
+
+		// addi x1, x1, -7
+		cpu.execute_word(i_type(0b0010011, 1, 0, 1, -7), 0x104).unwrap();
+		assert_eq!(cpu.get_reg(IntReg::x1), -2);
+
+		// add x2, x1, x1
+		cpu.execute_word(r_type(0b0110011, 2, 0, 1, 1, 0), 0x200).unwrap();
+		assert_eq!(cpu.get_reg(IntReg::x2), -4);
+		assert_eq!(cpu.pc, 0x204);
+
+		// c.lwsp x10, 4(sp) expands to a load (from missing memory)
+		assert!(cpu.execute_word(0x4512, 0x300).is_err());
+		assert_eq!(cpu.pc, 0x302);
+
+		assert_eq!(
+			cpu.execute_word(0x0000_007f, 0),
+			Err(Trap::IllegalInstruction(0x0000_007f))
+		);
+		assert_eq!((cpu.cycles, cpu.instret), (0, 0));
+	}
+
+	#[test]
+	fn endianness() {
+		use crate::ins::asm::{i_type, s_type};
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		cpu.set_endianness(Endianness::Big);
+		assert_eq!(cpu.endianness(), Endianness::Big);
+
+		// Fetched little-endian
+		let program = [
+			// sw x1, 32(x0)
+			s_type(0b0100011, 0b010, 0, 1, 32),
+			// lh x2, 32(x0)
+			i_type(0b0000011, 2, 0b001, 0, 32),
+		];
+		for (addr, &word) in (0..).step_by(4).zip(&program) {
+			cpu.mmu.memory.write_u32_le(addr, word).unwrap();
+		}
+		assert_eq!(cpu.fetch(), Ok((program[0], PC_STEP)));
+
+		cpu.xregs.set(IntReg::x1, 0x8123_4567);
+		cpu.tick();
+		cpu.tick();
+		assert_eq!(cpu.pc, 8);
+
+		// Loads and stores use the configured byte order
+		assert_eq!(cpu.mmu.read_u32_be(32), Ok(0x8123_4567));
+		assert_eq!(cpu.get_reg(IntReg::x2), 0x8123_u16 as i16 as i64);
+		assert_eq!(cpu.load_u16(34), Ok(0x4567));
+
+		cpu.set_endianness(Endianness::Little);
+		assert_eq!(cpu.load_u32(32), Ok(0x6745_2381));
+		cpu.store_u64(40, 0x0102_0304_0506_0708).unwrap();
+		assert_eq!(cpu.mmu.read_u8(40), Ok(0x08));
+	}
+
+	#[test]
+	fn fetch_loaded_entry() {
+		use elf::elf::Elf;
+
+		use crate::mem::PagedMemory;
+
+		let bytes =
+			include_bytes!("../../../resources/riscv-tests/rv64ui-p-add");
+		let elf = Elf::from_bytes(bytes).unwrap();
+		let Elf::Elf64 { header, .. } = &elf else {
+			panic!("Expected a 64-bit elf");
+		};
+
+		let mut memory = PagedMemory::new();
+		for (addr, data, _) in elf.load_segments() {
+			memory.write(addr.0, data).unwrap();
+		}
+
+		let mut cpu = Cpu::with_reset_vector(header.e_entry);
+		cpu.mmu.memory = Box::new(memory);
+
+		let tracer = Rc::new(RefCell::new(VecTracer::default()));
+		cpu.set_tracer(tracer.clone());
+		cpu.run_bounded(1);
+
+		// The first instruction is fetched from where it was loaded to
+		let entry = elf.entry_bytes(4).unwrap();
+		assert_eq!(
+			tracer.borrow().events.first(),
+			Some(&TraceEvent::Instruction {
+				pc: header.e_entry,
+				word: u32::from_le_bytes(entry.try_into().unwrap()),
+				name: "JAL",
+			})
+		);
+	}
+
+	#[test]
+	fn decode_unknown() {
+		// Major opcode reserved for >= 80-bit instructions
+		const UNKNOWN: u32 = 0x0000_007f;
+
+		let mut cpu = Cpu::default();
+
+		assert_eq!(
+			cpu.decode(UNKNOWN).map(|(_, inst)| inst.name),
+			Err(Trap::IllegalInstruction(UNKNOWN))
+		);
+		// addi x1, x0, 5
+		assert_eq!(
+			cpu.decode(0x0050_0093).map(|(_, inst)| inst.name),
+			Ok("ADDI")
+		);
+	}
+
+	#[test]
+	fn defined_illegal_instructions() {
+		use crate::mem::Memory;
+
+		// `[15:0]` all zero (e.g. zeroed memory) and `[ILEN-1:0]` all one
+		for word in [0x0000_0000, 0xffff_ffff] {
+			let mut cpu = Cpu::default();
+			cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+			cpu.mmu.memory.write_u32_le(0, word).unwrap();
+
+			cpu.tick();
+
+			assert_eq!(cpu.status(), Status::Halted);
+			assert_eq!(
+				cpu.halt_trap(),
+				Some(Trap::IllegalInstruction(word)),
+				"{word:#010x}"
+			);
+			// Not advanced, like all other traps raised while fetching
+			assert_eq!(cpu.pc, 0, "{word:#010x}");
+		}
+	}
+
+	#[test]
+	fn trap_handler() {
+		use crate::mem::Memory;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		// Unknown major opcode
+		cpu.mmu.memory.write_u32_le(0, 0x0000_007f).unwrap();
+		// addi x1, x0, 5
+		cpu.mmu.memory.write_u32_le(4, 0x0050_0093).unwrap();
+
+		// Count the trap in `x31` and continue with the next instruction
+		cpu.set_trap_handler(|cpu, trap| {
+			assert_eq!(trap, Trap::IllegalInstruction(0x0000_007f));
+			assert_eq!(cpu.pc, 0);
+			cpu.xregs.set(IntReg::x31, cpu.xregs.get(IntReg::x31) + 1);
+			cpu.pc += 4;
+		});
+
+		cpu.tick();
+		cpu.tick();
+
+		assert_ne!(cpu.status(), Status::Halted);
+		assert_eq!(cpu.halt_trap(), None);
+		assert_eq!(cpu.xregs.get(IntReg::x31), 1);
+		assert_eq!(cpu.xregs.get(IntReg::x1), 5);
+		assert_eq!(cpu.pc, 8);
+	}
+
+	#[test]
+	fn breakpoints() {
+		use crate::ins::asm::i_type;
+		use crate::mem::Memory;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		for idx in 0..4 {
+			// addi x1, x1, 1
+			cpu.mmu
+				.memory
+				.write_u32_le(idx * 4, i_type(0b0010011, 1, 0, 1, 1))
+				.unwrap();
+		}
+
+		cpu.add_breakpoint(8);
+		for _ in 0..3 {
+			cpu.tick();
+		}
+
+		// Stopped before the third instruction
+		assert_eq!(cpu.status(), Status::Halted);
+		assert_eq!(cpu.halt_trap(), Some(Trap::Breakpoint(8)));
+		assert_eq!(cpu.pc, 8);
+		assert_eq!(cpu.xregs.get(IntReg::x1), 2);
+		assert_eq!(cpu.read_csr(csr::INSTRET), 2);
+
+		// Resumes with the instruction after the handler returned
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		cpu.mmu.memory.write_u32_le(0, i_type(0b0010011, 1, 0, 1, 1)).unwrap();
+		cpu.set_trap_handler(|cpu, trap| {
+			assert_eq!(trap, Trap::Breakpoint(0));
+			cpu.xregs.set(IntReg::x31, cpu.xregs.get(IntReg::x31) + 1);
+		});
+		cpu.add_breakpoint(0);
+
+		cpu.tick();
+		assert_eq!((cpu.pc, cpu.xregs.get(IntReg::x1)), (0, 0));
+		cpu.tick();
+		assert_eq!((cpu.pc, cpu.xregs.get(IntReg::x1)), (4, 1));
+		assert_eq!(cpu.xregs.get(IntReg::x31), 1);
+
+		assert!(cpu.remove_breakpoint(0));
+		assert!(!cpu.remove_breakpoint(0));
+	}
+
+	#[test]
+	fn watchpoints() {
+		use crate::ins::asm::s_type;
+		use crate::mem::{Access, AccessKind, Memory};
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		cpu.xregs.set(IntReg::x1, 0x1122_3344_5566_7788);
+		// sd x1, 0x20(x0)
+		cpu.mmu
+			.memory
+			.write_u32_le(0, s_type(0b0100011, 0b011, 0, 1, 0x20))
+			.unwrap();
+		// sd x1, 0x30(x0)
+		cpu.mmu
+			.memory
+			.write_u32_le(4, s_type(0b0100011, 0b011, 0, 1, 0x30))
+			.unwrap();
+
+		// Not hit by writes or other addresses
+		cpu.mmu.add_watchpoint(0x24, AccessKind::Read);
+		cpu.mmu.add_watchpoint(0x40, AccessKind::Write);
+		// Within the double word at `0x30`
+		cpu.mmu.add_watchpoint(0x34, AccessKind::Write);
+
+		let tracer = Rc::new(RefCell::new(VecTracer::default()));
+		cpu.set_tracer(tracer.clone());
+
+		cpu.tick();
+		assert_ne!(cpu.status(), Status::Halted);
+		assert_eq!(cpu.mmu.watch_hit(), None);
+
+		cpu.tick();
+		assert_eq!(cpu.status(), Status::Halted);
+		// Reports the store like a breakpoint on it
+		assert_eq!(cpu.halt_trap(), Some(Trap::Breakpoint(4)));
+		assert_eq!(
+			tracer.borrow().events.last(),
+			Some(&TraceEvent::Trap(Trap::Breakpoint(4)))
+		);
+		// Stops after the store
+		assert_eq!(cpu.pc, 8);
+		assert_eq!(
+			cpu.mmu.watch_hit(),
+			Some(&Access {
+				kind: AccessKind::Write,
+				addr: 0x30,
+				data: 0x1122_3344_5566_7788_u64.to_le_bytes().to_vec(),
+			})
+		);
+
+		assert!(cpu.mmu.remove_watchpoint(0x34, AccessKind::Write));
+		assert!(!cpu.mmu.remove_watchpoint(0x34, AccessKind::Read));
+	}
+
+	#[test]
+	fn fatal_trap_halts() {
+		use crate::mem::Memory;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		cpu.write_csr(csr::MTVEC, 0x20).unwrap();
+		// ld x1, 0x100(x0)
+		cpu.mmu
+			.memory
+			.write_u32_le(0, ins::asm::i_type(0b0000011, 1, 0b011, 0, 0x100))
+			.unwrap();
+
+		cpu.tick();
+
+		assert_eq!(cpu.status(), Status::Halted);
+		assert_eq!(cpu.halt_trap(), Some(Trap::LoadAccessFault(0x100)));
+		assert_eq!(cpu.read_csr(csr::MEPC), 0);
+	}
+
+	#[test]
+	fn resumable_trap_vectors() {
+		use crate::mem::Memory;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		// Vectored mode, still uses the base for exceptions
+		cpu.write_csr(csr::MTVEC, 0x20 | 0b01).unwrap();
+		// ecall
+		cpu.mmu.memory.write_u32_le(4, 0x0000_0073).unwrap();
+		// Handler: addi x1, x0, 5; mret
+		cpu.mmu.memory.write_u32_le(0x20, 0x0050_0093).unwrap();
+		cpu.mmu.memory.write_u32_le(0x24, 0x3020_0073).unwrap();
+
+		cpu.pc = 4;
+		cpu.tick();
+
+		assert_ne!(cpu.status(), Status::Halted);
+		assert_eq!(cpu.pc, 0x20);
+		assert_eq!(cpu.read_csr(csr::MEPC), 4);
+		assert_eq!(
+			cpu.read_csr(csr::MCAUSE),
+			Trap::EnvironmentCallFromMMode.cause_code()
+		);
+		assert_eq!(cpu.read_csr(csr::MTVAL), 0);
+
+		cpu.tick();
+		cpu.tick();
+
+		assert_eq!(cpu.xregs.get(IntReg::x1), 5);
+		// Returns to the `ecall` (the handler has to advance `mepc`)
+		assert_eq!(cpu.pc, 4);
+	}
+
+	#[test]
+	fn core_dump() {
+		use elf::elf::Elf;
+		use elf::header::consts::machine::E_MACHINE_RISCV;
+		use elf::header::consts::typ::E_TYPE_ET_CORE;
+		use elf::program_header::consts::typ::{
+			P_TYPE_PT_LOAD, P_TYPE_PT_NOTE,
+		};
+		use elf::program_header::VirtAddr;
+
+		let mut mem = Memory((0..=255).collect());
+		// ld x2, 0x100(x0)
+		mem.write_u32_le(
+			0x10,
+			ins::asm::i_type(0b0000011, 2, 0b011, 0, 0x100),
+		)
+		.unwrap();
+
+		let mut cpu = Cpu::with_reset_vector(0x10);
+		cpu.mmu.memory = Box::new(mem.clone());
+		cpu.xregs.set(IntReg::x1, 0x1234);
+		cpu.xregs.set(IntReg::x31, -1);
+		cpu.tick();
+		assert_eq!(cpu.halt_trap(), Some(Trap::LoadAccessFault(0x100)));
+
+		let bytes = cpu.write_core_dump(&mem);
+
+		let elf = Elf::from_bytes_strict(&bytes).unwrap();
+		assert_eq!(elf.verify(), Ok(()));
+		let Elf::Elf64 { header, pheaders, .. } = &elf else {
+			panic!("Expected a 64-bit elf");
+		};
+		assert_eq!(header.e_type, E_TYPE_ET_CORE);
+		assert_eq!(header.e_machine, E_MACHINE_RISCV);
+
+		let types: Vec<_> = pheaders.iter().map(|ph| ph.p_type).collect();
+		assert_eq!(types, [P_TYPE_PT_NOTE, P_TYPE_PT_LOAD]);
+
+		let segments: Vec<_> = elf.load_segments().collect();
+		assert_eq!(segments, [(VirtAddr(0), &mem.0[..], 256)]);
+
+		// Note header, name and `struct elf_prstatus`
+		let note = pheaders[0].extract_data(&bytes);
+		let word = |offset: usize| {
+			u32::from_le_bytes(
+				note.get(offset..offset + 4).unwrap().try_into().unwrap(),
+			)
+		};
+		let reg = |idx: usize| {
+			let offset = 20 + PRSTATUS_REG + idx * 8;
+			u64::from_le_bytes(
+				note.get(offset..offset + 8).unwrap().try_into().unwrap(),
+			)
+		};
+		assert_eq!((word(0), word(4), word(8)), (5, 376, NT_PRSTATUS));
+		assert_eq!(note.get(12..17), Some(&b"CORE\0"[..]));
+		assert_eq!(note.len(), 20 + PRSTATUS_SIZE);
+
+		// SIGSEGV at the load
+		assert_eq!(word(20 + PRSTATUS_CURSIG) & 0xffff, 11);
+		assert_eq!(reg(0), 0x10);
+		assert_eq!(reg(1), 0x1234);
+		assert_eq!(reg(31), u64::MAX);
+	}
+
+	#[test]
+	fn compressed_instructions() {
+		use crate::mem::Memory;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+		cpu.xregs.set(IntReg::x2, 32);
+		cpu.xregs.set(IntReg::x1, 0x1234);
+
+		// c.sdsp ra, 8(sp)
+		cpu.mmu.memory.write_u16_le(0, 0xe406).unwrap();
+		// addi x1, x0, 5
+		cpu.mmu.memory.write_u32_le(2, 0x0050_0093).unwrap();
+		// c.ldsp s0, 8(sp)
+		cpu.mmu.memory.write_u16_le(6, 0x6422).unwrap();
+		// c.addi4spn a0, sp, 16
+		cpu.mmu.memory.write_u16_le(8, 0x0808).unwrap();
+
+		cpu.tick();
+		assert_eq!(cpu.pc, 2);
+		assert_eq!(cpu.mmu.memory.read_u64_le(40).unwrap(), 0x1234);
+
+		cpu.tick();
+		assert_eq!(cpu.pc, 6);
+		assert_eq!(cpu.xregs.get(IntReg::x1), 5);
+
+		cpu.tick();
+		assert_eq!(cpu.pc, 8);
+		assert_eq!(cpu.xregs.get(IntReg::x8), 0x1234);
+
+		cpu.tick();
+		assert_eq!(cpu.pc, 10);
+		assert_eq!(cpu.xregs.get(IntReg::x10), 48);
+
+		// Reserved all-zero parcel
+		cpu.tick();
+		assert_eq!(cpu.pc, 10);
+		assert_eq!(cpu.halt_trap(), Some(Trap::IllegalInstruction(0)));
+	}
+
+	#[test]
+	fn fetch_trap_keeps_pc() {
+		use crate::mem::Memory;
+
+		// Outside of the memory
+		let mut cpu = Cpu::with_reset_vector(0x100);
+		cpu.mmu.memory = Box::new(Memory(vec![0; 8]));
+		cpu.tick();
+		assert_eq!(cpu.halt_trap(), Some(Trap::InstructionAccessFault(0x100)));
+		assert_eq!(cpu.pc, 0x100);
+
+		// Only the upper half of a 32-bit instruction is missing
+		let mut cpu = Cpu::with_reset_vector(6);
+		cpu.mmu.memory = Box::new(Memory(vec![0; 8]));
+		cpu.mmu.memory.write_u16_le(6, 0x0013).unwrap();
+		cpu.tick();
+		assert_eq!(cpu.halt_trap(), Some(Trap::InstructionAccessFault(6)));
+		assert_eq!(cpu.pc, 6);
+
+		// Reserved compressed instruction (c.lwsp zero, 0(sp))
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 8]));
+		cpu.mmu.memory.write_u16_le(0, 0x4002).unwrap();
+		cpu.tick();
+		assert_eq!(cpu.halt_trap(), Some(Trap::IllegalInstruction(0x4002)));
+		assert_eq!(cpu.pc, 0);
+	}
+
+	#[test]
+	fn reset_vector() {
+		let cpu = Cpu::new();
+		assert_eq!(cpu.reset_vector(), DEFAULT_RESET_VECTOR);
+		assert_eq!(cpu.pc, 0x8000_0000);
+		assert_eq!(cpu.status(), Status::Initializing);
+
+		let cpu = Cpu::with_reset_vector(0x1000);
+		assert_eq!(cpu.reset_vector(), 0x1000);
+		assert_eq!(cpu.pc, 0x1000);
+
+		let cpu = Cpu::default();
+		assert_eq!(cpu.reset_vector(), 0);
+		assert_eq!(cpu.pc, 0);
+	}
+
+	#[test]
+	fn set_regs() {
+		let mut cpu = Cpu::default();
+		// a0..a2
+		cpu.set_regs(&[(IntReg::x10, 1), (IntReg::x11, -2), (IntReg::x12, 3)]);
+
+		assert_eq!(cpu.get_reg(IntReg::x10), 1);
+		assert_eq!(cpu.get_reg(IntReg::x11), -2);
+		assert_eq!(cpu.get_reg(IntReg::x12), 3);
+		assert_eq!(cpu.get_reg(IntReg::x13), 0);
+
+		// Writes to `x0` are still ignored
+		cpu.set_regs(&[(IntReg::x0, 5)]);
+		assert_eq!(cpu.get_reg(IntReg::x0), 0);
+	}
+
+	#[test]
+	fn load_binary() {
+		use crate::mem::Memory;
+
+		// addi x1, x0, 5; addi x2, x1, 7; add x3, x1, x2
+		const PROGRAM: [u8; 12] = [
+			0x93, 0x00, 0x50, 0x00, 0x13, 0x81, 0x70, 0x00, 0xb3, 0x81, 0x20,
+			0x00,
+		];
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+
+		cpu.load_binary(0x20, &PROGRAM).unwrap();
+		assert_eq!(cpu.pc, 0x20);
 
-			None
+		for _ in 0..3 {
+			cpu.tick();
 		}
+
+		assert_eq!(cpu.xregs.get(IntReg::x1), 5);
+		assert_eq!(cpu.xregs.get(IntReg::x2), 12);
+		assert_eq!(cpu.xregs.get(IntReg::x3), 17);
+		assert_eq!(cpu.pc, 0x2c);
+	}
+
+	#[test]
+	fn snapshot_restore() {
+		use crate::mem::Memory;
+
+		let mut cpu = Cpu::default();
+		cpu.mmu.memory = Box::new(Memory(vec![0; 64]));
+
+		// addi x1, x0, 5
+		cpu.mmu.memory.write_u32_le(0, 0x0050_0093).unwrap();
+		// addi x2, x1, 7
+		cpu.mmu.memory.write_u32_le(4, 0x0070_8113).unwrap();
+		// csrrw x0, mscratch, x2
+		cpu.mmu.memory.write_u32_le(8, 0x3401_1073).unwrap();
+
+		cpu.write_csr(csr::MSCRATCH, 3).unwrap();
+		cpu.mmu.reserve(0x20, 8);
+		let snap = cpu.snapshot();
+
+		cpu.tick();
+		cpu.tick();
+		cpu.tick();
+		cpu.privilege = Privilege::User;
+		cpu.set_endianness(Endianness::Big);
+		// Consumed by e.g. a store-conditional
+		assert!(cpu.mmu.take_reservation(0x20, 8));
+		assert!(!cpu.mmu.reservation().valid);
+
+		assert_eq!(cpu.xregs.get(IntReg::x2), 12);
+		assert_eq!(cpu.read_csr(csr::MSCRATCH), 12);
+		assert_eq!(cpu.read_csr(csr::INSTRET), 3);
+		assert_eq!(cpu.pc, 12);
+
+		cpu.restore(&snap);
+
+		assert_eq!(cpu.snapshot(), snap);
+		assert_eq!(cpu.xregs.get(IntReg::x1), 0);
+		assert_eq!(cpu.xregs.get(IntReg::x2), 0);
+		assert_eq!(cpu.read_csr(csr::MSCRATCH), 3);
+		assert_eq!((cpu.cycles, cpu.instret), (0, 0));
+		assert_eq!(cpu.privilege(), Privilege::Machine);
+		assert_eq!(cpu.endianness(), Endianness::Little);
+		assert!(cpu.mmu.take_reservation(0x20, 8));
+		assert_eq!(cpu.pc, 0);
+
+		// Restoring after a halt resumes the cpu
+		cpu.run_bounded(10);
+		assert_eq!(cpu.halt_trap(), Some(Trap::IllegalInstruction(0)));
+		cpu.restore(&snap);
+		assert_eq!(cpu.status(), snap.status);
+		assert_eq!(cpu.halt_trap(), None);
+		cpu.tick();
+		assert_eq!(cpu.xregs.get(IntReg::x1), 5);
+
+		// Restoring after a breakpoint was hit executes the instruction
+		cpu.set_trap_handler(|_, _| {});
+		cpu.add_breakpoint(4);
+		cpu.tick();
+		assert_eq!(cpu.pc, 4);
+		let snap = cpu.snapshot();
+		cpu.tick();
+		assert_eq!(cpu.pc, 8);
+		cpu.restore(&snap);
+		cpu.tick();
+		assert_eq!(cpu.pc, 8);
 	}
 }
@@ -1,23 +1,25 @@
-use elf::elf::Elf;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use elf::elf::{Elf, SectionHeaderRef};
 use elf::header::consts::ident::class::EI_CLASS_32;
 use elf::header::consts::ident::osabi::EI_OSABI_SYSTEMV;
 use elf::header::consts::ident::version::EI_VERSION_CURRENT;
 use elf::header::consts::machine::E_MACHINE_RISCV;
 use elf::header::consts::typ::E_TYPE_ET_EXEC;
-use elf::program_header::consts::typ::P_TYPE_PT_LOAD;
-use elf::program_header::elf32::ProgramHeader as ProgramHeader32;
 use elf::section_header::consts::typ::{
 	SH_TYPE_SHT_STRTAB, SH_TYPE_SHT_SYMTAB,
 };
 use elf::strtab::Strtab;
-use rv64gc::cpu::Cpu;
-use rv64gc::mem::Memory;
-
-const KiB: usize = 1024;
-const MiB: usize = 1024 * KiB;
-const GiB: usize = 1024 * MiB;
+use rv64gc::adr::Addressable;
+use rv64gc::cpu::{
+	Cpu, RunOutcome, TraceEvent, VecTracer, DEFAULT_RESET_VECTOR,
+};
+use rv64gc::mem::{AccessKind, Memory, OffsetMemory};
+use rv64gc::tra::Trap;
 
-const MEM_BASE: u64 = 0x80000000;
+const MAX_TICKS: usize = 100_000;
+const STACK_SIZE: usize = 64 * 1024;
 
 mod tests;
 
@@ -37,7 +39,7 @@ fn rv32i_tests() -> Result<(), Box<dyn std::error::Error>> {
 
 				dump_elf32(&elf);
 
-				if let Elf::Elf32 { bytes, header, pheaders, sheaders } = elf {
+				if let Elf::Elf32 { header, .. } = &elf {
 					assert_eq!(header.e_ident.ei_class(), EI_CLASS_32);
 					assert_eq!(header.e_ident.ei_osabi(), EI_OSABI_SYSTEMV);
 
@@ -45,17 +47,36 @@ fn rv32i_tests() -> Result<(), Box<dyn std::error::Error>> {
 					assert_eq!(header.e_machine, E_MACHINE_RISCV);
 					assert_eq!(header.e_version, EI_VERSION_CURRENT);
 
-					// TODO: load data into memory and run cpu with it
-
-					let mut cpu = Cpu::default();
-					cpu.mmu.memory = prepare_memory(bytes, &pheaders);
-					cpu.pc = MEM_BASE;
-
-					loop {
-						cpu.tick();
-					}
+					let mut cpu = Cpu::new();
+					cpu.mmu.memory = Box::new(prepare_memory(&elf));
+					assert_eq!(header.e_entry as u64, DEFAULT_RESET_VECTOR);
+
+					// The test reports its result by writing to `tohost`
+					let (_, tohost) = elf
+						.symbols()
+						.find(|(name, _)| *name == "tohost")
+						.expect("Expected a `tohost` symbol");
+					cpu.mmu.add_watchpoint(tohost.st_value, AccessKind::Write);
+
+					cpu.set_livelock_detection(true);
+					let outcome = cpu.run_bounded(MAX_TICKS);
+					assert!(
+						matches!(
+							outcome,
+							RunOutcome::Halted(Some(Trap::Breakpoint(_)))
+						),
+						"{fname}: Unexpected outcome {outcome:?}"
+					);
 
-					panic!("END____");
+					// `1` on success, otherwise the number of the failed test
+					// case in the upper bits
+					let result = cpu.mmu.watch_hit().unwrap().data[0];
+					assert_eq!(
+						result,
+						1,
+						"{fname}: Test case {} failed",
+						result >> 1
+					);
 				} else {
 					panic!("Expected elf to be 32-bit but was 64-bit");
 				}
@@ -66,24 +87,79 @@ fn rv32i_tests() -> Result<(), Box<dyn std::error::Error>> {
 	Ok(())
 }
 
+#[test]
+fn load_at_virtual_address() -> Result<(), Box<dyn std::error::Error>> {
+	const PADDR_OFFSET: u32 = 0x4000_0000;
+
+	let bytes = std::fs::read(format!(
+		"{}/rv32ui-p-add",
+		tests::BASE_RISCV_TESTS_DIR
+	))?;
+
+	// Move the physical addresses of all segments away from the virtual ones
+	let mut patched = bytes.clone();
+	if let Elf::Elf32 { header, pheaders, .. } = Elf::from_bytes(&bytes)? {
+		for (idx, ph) in pheaders.iter().enumerate() {
+			let offset = header.e_phoff as usize
+				+ idx * header.e_phentsize as usize
+				+ 12;
+			let paddr = (ph.p_paddr + PADDR_OFFSET).to_le_bytes();
+			patched[offset..offset + 4].copy_from_slice(&paddr);
+		}
+	} else {
+		panic!("Expected elf to be 32-bit but was 64-bit");
+	}
+
+	let run = |bytes: &[u8]| {
+		let elf = Elf::from_bytes(bytes).unwrap();
+
+		let tracer = Rc::new(RefCell::new(VecTracer::default()));
+		let mut cpu = Cpu::new();
+		cpu.mmu.memory = Box::new(prepare_memory(&elf));
+		cpu.set_tracer(tracer.clone());
+		cpu.run_bounded(1000);
+
+		let events = tracer.borrow().events.clone();
+		(elf.entry_bytes(4).unwrap().to_vec(), events)
+	};
+
+	let elf = Elf::from_bytes(&patched)?;
+	let Elf::Elf32 { pheaders, .. } = &elf else { unreachable!() };
+	assert!(pheaders
+		.iter()
+		.filter(|ph| ph.is_load())
+		.all(|ph| ph.p_vaddr + PADDR_OFFSET == ph.p_paddr));
+
+	let (entry, events) = run(&patched);
+
+	// The first instruction is the one at the (virtual) entry point
+	assert_eq!(
+		events.first(),
+		Some(&TraceEvent::Instruction {
+			pc: DEFAULT_RESET_VECTOR,
+			word: u32::from_le_bytes(entry[..].try_into()?),
+			name: "JAL",
+		})
+	);
+
+	// And the program behaves the same as with identical addresses
+	assert_eq!(events, run(&bytes).1);
+
+	Ok(())
+}
+
 fn dump_elf32(elf: &Elf) {
 	if let Elf::Elf32 { bytes, header, pheaders, sheaders } = elf {
 		println!("{:#}", header);
 
-		let shstrtab = &sheaders[header.e_shstrndx as usize];
-		let strtab =
-			Strtab::new(Strtab::DEFAULT_DELIM, shstrtab.extract_data(&bytes));
-
 		for ph in pheaders {
 			println!("{:#}", ph);
 		}
 
-		for sh in sheaders {
-			println!(
-				"{:?} - {:#}",
-				unsafe { strtab.get_str_off_unchecked(sh.sh_name as usize) },
-				sh
-			);
+		for (name, sh) in elf.sections_with_names() {
+			if let SectionHeaderRef::Elf32(sh) = sh {
+				println!("{:?} - {:#}", name, sh);
+			}
 		}
 
 		for sh in sheaders {
@@ -105,8 +181,10 @@ fn dump_elf32(elf: &Elf) {
 				let shstrtab = &sheaders[sh.sh_link as usize];
 				let strtab = Strtab::new(b'\0', shstrtab.extract_data(&bytes));
 
-				let symtab =
-					elf::symtab::elf32::Symtab::new(EI_CLASS_32, &bytes[sh]);
+				let symtab = elf::symtab::elf32::Symtab::new(
+					header.e_ident.endianness().unwrap(),
+					&bytes[sh],
+				);
 				let mut idx = 0;
 
 				while let Some(s) = symtab.get_symbol(idx) {
@@ -127,20 +205,20 @@ fn dump_elf32(elf: &Elf) {
 	}
 }
 
-fn prepare_memory(bytes: &[u8], pheaders: &[ProgramHeader32]) -> Memory {
-	let mut mem = vec![0u8; 3 * GiB];
-
-	for ph in pheaders {
-		if ph.p_type == P_TYPE_PT_LOAD {
-			let data = &bytes[ph];
-			let addr = ph.p_paddr;
-
-			let start = addr as usize;
-			let end = start + (ph.p_filesz as usize);
-
-			(&mut mem[start..end]).copy_from_slice(data);
-		}
+/// Loads the segments of `elf` at their virtual address (`p_vaddr`).
+///
+/// There is no address translation, so the addresses fetched by the cpu are
+/// the virtual ones; the physical address (`p_paddr`) is ignored.
+fn prepare_memory(elf: &Elf) -> OffsetMemory<Memory> {
+	let (base, _) = elf.memory_span().unwrap();
+	let mut mem = OffsetMemory::new(
+		base,
+		Memory::sized_for(elf, base, STACK_SIZE).unwrap(),
+	);
+
+	for (addr, data, _) in elf.load_segments() {
+		mem.write(addr.0, data).unwrap();
 	}
 
-	Memory(mem)
+	mem
 }
@@ -0,0 +1,15 @@
+#include <stdio.h>
+
+__thread int counter = 1;
+int answer = 42;
+int zeroed[16];
+
+__attribute__((noinline)) int add(int a, int b) {
+	return a + b;
+}
+
+int main(void) {
+	counter = add(counter, zeroed[0]);
+	printf("Hello %d %d\n", counter, answer);
+	return 0;
+}